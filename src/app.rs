@@ -1,4 +1,4 @@
-use std::io;
+use std::{io, time::Duration};
 
 use ratatui::{
     crossterm::event::{
@@ -18,6 +18,9 @@ use crate::{
     screens::MainScreen,
 };
 
+/// How long to wait for a key press before ticking the top screen.
+const TICK_RATE: Duration = Duration::from_millis(100);
+
 pub struct App {
     pub state: AppState,
     pub screens: Vec<Box<dyn Screen>>,
@@ -25,9 +28,14 @@ pub struct App {
 
 impl App {
     pub fn new(state: AppState) -> Self {
+        Self::with_screen(state, Box::new(MainScreen::new()))
+    }
+
+    /// Starts on `screen` instead of the shortcut table. Closing it exits.
+    pub fn with_screen(state: AppState, screen: Box<dyn Screen>) -> Self {
         Self {
             state,
-            screens: vec![Box::new(MainScreen::new())],
+            screens: vec![screen],
         }
     }
 
@@ -39,35 +47,43 @@ impl App {
                 }
             });
 
-            if let Event::Key(key) = event::read()? {
-                if key.kind == event::KeyEventKind::Release {
-                    continue;
+            if !event::poll(TICK_RATE)? {
+                if let Some(screen) = self.screens.last_mut() {
+                    screen.on_tick(&mut self.state);
                 }
+                continue;
+            }
 
-                let cmd = self
-                    .screens
-                    .last_mut()
-                    .unwrap()
-                    .handle_event(key, &mut self.state);
-
-                match cmd {
-                    ScreenCommand::None => {}
-                    ScreenCommand::Close => {
-                        self.screens.pop();
-                        if self.screens.is_empty() {
-                            return Ok(());
-                        }
+            let screen = self.screens.last_mut().unwrap();
+            let cmd = match event::read()? {
+                Event::Key(key) => {
+                    if key.kind == event::KeyEventKind::Release {
+                        continue;
                     }
-                    ScreenCommand::Push(screen) => {
-                        self.screens.push(screen);
+                    screen.handle_event(key, &mut self.state)
+                }
+                Event::Mouse(mouse) => screen.handle_mouse(mouse, &mut self.state),
+                _ => continue,
+            };
+
+            match cmd {
+                ScreenCommand::None => {}
+                ScreenCommand::Close => {
+                    self.screens.pop();
+                    match self.screens.last_mut() {
+                        Some(screen) => screen.on_resume(&mut self.state),
+                        None => return Ok(()),
                     }
+                }
+                ScreenCommand::Push(screen) => {
+                    self.screens.push(screen);
+                }
 
-                    ScreenCommand::Quit(_value) => return Ok(()),
+                ScreenCommand::Quit(_value) => return Ok(()),
 
-                    ScreenCommand::Swap(screen) => {
-                        self.screens.pop();
-                        self.screens.push(screen);
-                    }
+                ScreenCommand::Swap(screen) => {
+                    self.screens.pop();
+                    self.screens.push(screen);
                 }
             }
         }
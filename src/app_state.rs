@@ -2,14 +2,22 @@ use crate::shortcuts::Shortcuts;
 
 pub struct AppState {
     pub shortcuts: Shortcuts,
-    pub temp_text: String,
+
+    /// Index of a shortcut that a closing screen wants selected in the table,
+    /// e.g. one that was just added.
+    pub selected_shortcut: Option<usize>,
+
+    /// Whether the terminal reports keys with the kitty keyboard protocol,
+    /// which tells apart combos legacy terminals send as the same bytes.
+    pub keyboard_enhancement: bool,
 }
 
 impl AppState {
     pub fn new(shortcuts: Shortcuts) -> Self {
         Self {
             shortcuts,
-            temp_text: String::from("Hello world"),
+            selected_shortcut: None,
+            keyboard_enhancement: false,
         }
     }
 }
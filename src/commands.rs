@@ -0,0 +1,367 @@
+use std::{
+    fmt,
+    io::{self, ErrorKind},
+    process::ExitCode,
+};
+
+use crate::{app_state::AppState, data_location::LocationOptions};
+
+mod add;
+mod bump;
+mod import;
+mod list;
+mod output;
+mod remove;
+mod restore;
+mod search;
+mod show;
+mod undo;
+
+pub use add::Add;
+pub use bump::Bump;
+pub use import::Import;
+pub use list::List;
+pub use remove::Remove;
+pub use restore::Restore;
+pub use search::Search;
+pub use show::Show;
+pub use undo::Undo;
+
+pub const USAGE: &str = "\
+Usage: shotty [--file PATH | --profile NAME] [COMMAND]
+
+Without a command the interactive shortcut browser opens.
+
+Options:
+  --file PATH         Use the shortcuts file at PATH
+  --profile NAME      Use a named set of shortcuts, e.g. --profile work
+
+Commands:
+  add <SHORTCUT> <DESCRIPTION>... [--app APP] [--tags TAG,...]
+                      Add a shortcut, e.g. shotty add 'Ctrl+Shift+P' command palette --app vscode
+  list [--app APP] [--tag TAG] [--sort frecency|lookups] [--format FORMAT]
+                      List shortcuts, often and recently looked up first
+  show <ID> [--format FORMAT]
+                      Show the shortcut with the given ID
+  remove <ID>...      Remove the shortcuts with the given IDs
+  search <QUERY>... [--app APP] [--tag TAG] [--format FORMAT]
+                      Fuzzy search shortcuts by combo and description
+  bump <ID> [--by N]  Count N lookups (default 1) of the shortcut with the given ID
+  undo                Undo the last change (needs SHOTTY_UNDO_JOURNAL across runs)
+  redo                Redo the last undone change
+  quiz                Quiz yourself on the shortcuts due for review today
+  restore [#]         List backups of the shortcuts file, or restore backup number #
+  import <IMPORT_FORMAT> <FILE> [--app APP] [--tags TAG,...] [--dry-run]
+                      Add the shortcuts from another program's keybindings file,
+                      or with --dry-run only list what would be added or changed
+  help                Show this help
+
+IDs are shown by list and search and stay the same until a shortcut is removed.
+
+FORMAT is one of:
+  plain   an aligned table (default)
+  tsv     tab separated with a header line: id, rank, shortcut, description,
+          app, tags, lookup_count
+  json    {\"schema_version\": 1, \"shortcuts\": [{\"id\", \"rank\", \"shortcut\",
+          \"strokes\", \"description\", \"app\", \"context\", \"tags\", \"lookup_count\",
+          \"frecency\", \"created_at\", \"updated_at\", \"last_looked_up\"}]}
+          rank is the position by lookup count over all shortcuts, times are
+          seconds since the Unix epoch
+
+IMPORT_FORMAT is one of:
+  vscode  VS Code's keybindings.json, `when` clauses become the context
+  tmux    .tmux.conf or the output of `tmux list-keys`, one context per key
+          table, prefix bindings become chords like `Ctrl+B C`
+  zellij  the keybinds block of Zellij's config.kdl, one context per mode
+  screen  .screenrc, bindings after the escape key become chords
+  i3, sway
+          the i3 or sway config, `$variables` are expanded and every mode
+          is a context
+  hyprland
+          hyprland.conf, every submap is a context
+  sxhkd   sxhkdrc, `{a,b}` sequences become one shortcut each
+  jetbrains
+          a JetBrains IDE keymap .xml, including the keymap it extends if that
+          is in the same directory
+  readline
+          ~/.inputrc or the output of bash's `bind -p` or `bind -P`, with the
+          keymap (emacs, vi-insert, vi-command) as the context
+  zsh     the output of `bindkey -L` or the bindkey lines of .zshrc, with the
+          keymap as the context
+  alacritty
+          the [[keyboard.bindings]] of alacritty.toml, with the mode as the
+          context
+  kitty   the map lines of kitty.conf, `>` sequences become chords and every
+          --mode is a context
+  wezterm the keys and key_tables of wezterm.lua where they're plain tables,
+          LEADER bindings become chords and every key table is a context
+
+Importing a file again updates the descriptions of the shortcuts it added
+before rather than adding them twice. A whole import is undone at once.
+
+Shortcuts are kept in $XDG_DATA_HOME/shotty/shortcuts.json
+(~/.local/share/shotty/shortcuts.json if XDG_DATA_HOME is unset), or in
+$SHOTTY_FILE if it is set. Profiles are kept in
+$XDG_DATA_HOME/shotty/profiles/NAME.json.
+
+Before a save overwrites the file, a backup is kept in the backups directory
+next to it, at most one every 15 minutes. $SHOTTY_BACKUPS sets how many are
+kept (default 10, 0 turns them off).
+
+Frecency counts every lookup as 1, halving with every $SHOTTY_HALF_LIFE_DAYS
+days that pass (default 14).
+
+$SHOTTY_UNDO_DEPTH sets how many changes can be undone (default 100). With
+SHOTTY_UNDO_JOURNAL=1 the undo history is kept in NAME.undo.json next to the
+shortcuts file, so changes can be undone after a restart.
+
+Exit status: 0 on success, 1 if the command failed, 2 on invalid usage.";
+
+/// A subcommand run from the command line, without the interactive UI.
+pub trait Command {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult;
+}
+
+pub type CommandResult = Result<(), CommandError>;
+
+#[derive(Debug)]
+pub enum CommandError {
+    /// The arguments were wrong, the usage is worth showing.
+    Usage(String),
+    /// The command ran but failed, e.g. an unknown ID or a failed save.
+    Failed(String),
+    /// Writing the output failed.
+    Output(io::Error),
+}
+
+impl From<io::Error> for CommandError {
+    fn from(err: io::Error) -> Self {
+        CommandError::Output(err)
+    }
+}
+
+impl CommandError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            CommandError::Usage(_) => ExitCode::from(2),
+            CommandError::Failed(_) => ExitCode::FAILURE,
+            // the reader went away, e.g. `shotty list | head -1`
+            CommandError::Output(err) if err.kind() == ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+            CommandError::Output(_) => ExitCode::FAILURE,
+        }
+    }
+
+    /// Whether the error is worth telling the user about.
+    pub fn is_quiet(&self) -> bool {
+        matches!(self, CommandError::Output(err) if err.kind() == ErrorKind::BrokenPipe)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Usage(message) => {
+                write!(f, "{message}\nRun `shotty help` for usage.")
+            }
+            CommandError::Failed(message) => write!(f, "{message}"),
+            CommandError::Output(err) => write!(f, "could not write the output: {err}"),
+        }
+    }
+}
+
+/// What the command line asked shotty to do.
+pub enum Invocation {
+    /// Open the interactive UI.
+    Tui,
+    /// Open the interactive UI straight into the quiz of today's due shortcuts.
+    Quiz,
+    Help,
+    Run(Box<dyn Command>),
+}
+
+/// Parses the arguments after the program name.
+///
+/// `--file` and `--profile` choose the shortcuts file for every command and
+/// may appear anywhere before `--`.
+pub fn parse(args: &[String]) -> Result<(LocationOptions, Invocation), CommandError> {
+    let (location, args) = take_location_options(args)?;
+    Ok((location, parse_invocation(&args)?))
+}
+
+fn parse_invocation(args: &[String]) -> Result<Invocation, CommandError> {
+    let Some((name, rest)) = args.split_first() else {
+        return Ok(Invocation::Tui);
+    };
+
+    let command: Box<dyn Command> = match name.as_str() {
+        "help" | "--help" | "-h" => return Ok(Invocation::Help),
+        "quiz" => {
+            if let Some(extra) = Args::parse(rest, &[])?.positional.first() {
+                return Err(CommandError::Usage(format!(
+                    "unexpected argument `{extra}`"
+                )));
+            }
+            return Ok(Invocation::Quiz);
+        }
+        "add" => Box::new(Add::parse(rest)?),
+        "list" | "ls" => Box::new(List::parse(rest)?),
+        "remove" | "rm" => Box::new(Remove::parse(rest)?),
+        "search" => Box::new(Search::parse(rest)?),
+        "show" => Box::new(Show::parse(rest)?),
+        "bump" => Box::new(Bump::parse(rest)?),
+        "restore" => Box::new(Restore::parse(rest)?),
+        "import" => Box::new(Import::parse(rest)?),
+        "undo" => Box::new(Undo::parse(rest, false)?),
+        "redo" => Box::new(Undo::parse(rest, true)?),
+        other => return Err(CommandError::Usage(format!("unknown command `{other}`"))),
+    };
+
+    Ok(Invocation::Run(command))
+}
+
+/// Removes `--file` and `--profile` from `args`, returning them and the rest.
+fn take_location_options(args: &[String]) -> Result<(LocationOptions, Vec<String>), CommandError> {
+    let mut location = LocationOptions::default();
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rest.push(arg.clone());
+            rest.extend(args.by_ref().cloned());
+            break;
+        }
+
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_owned())),
+            None => (arg.as_str(), None),
+        };
+        if name != "--file" && name != "--profile" {
+            rest.push(arg.clone());
+            continue;
+        }
+
+        let value = match inline_value {
+            Some(value) => value,
+            None => args
+                .next()
+                .cloned()
+                .ok_or_else(|| CommandError::Usage(format!("`{name}` needs a value")))?,
+        };
+        if name == "--file" {
+            location.file = Some(value.into());
+        } else {
+            location.profile = Some(value);
+        }
+    }
+
+    if location.file.is_some() && location.profile.is_some() {
+        return Err(CommandError::Usage(
+            "`--file` and `--profile` can't be used together".into(),
+        ));
+    }
+    Ok((location, rest))
+}
+
+/// Positional arguments, `--name value` options and `--name` switches of a
+/// subcommand.
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    switches: Vec<String>,
+}
+
+impl Args {
+    /// Splits `args` into positionals and the options listed in `known`.
+    /// Everything after `--` is positional.
+    fn parse(args: &[String], known: &[&str]) -> Result<Self, CommandError> {
+        Self::parse_with_switches(args, known, &[])
+    }
+
+    /// Like [`Args::parse`], also accepting the `switches`, which take no value.
+    fn parse_with_switches(
+        args: &[String],
+        known: &[&str],
+        switches: &[&str],
+    ) -> Result<Self, CommandError> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut given_switches = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                positional.extend(args.by_ref().cloned());
+                break;
+            }
+
+            let Some(option) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+            if switches.contains(&option) {
+                given_switches.push(option.to_owned());
+                continue;
+            }
+
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name, value.to_owned()),
+                None => {
+                    let value = args.next().ok_or_else(|| {
+                        CommandError::Usage(format!("`--{option}` needs a value"))
+                    })?;
+                    (option, value.clone())
+                }
+            };
+
+            if !known.contains(&name) {
+                return Err(CommandError::Usage(format!("unknown option `--{name}`")));
+            }
+            options.push((name.to_owned(), value));
+        }
+
+        Ok(Self {
+            positional,
+            options,
+            switches: given_switches,
+        })
+    }
+
+    /// Whether the switch `--name` was given.
+    fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|switch| switch == name)
+    }
+
+    /// The value of the last occurrence of `--name`.
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parses a 1-based shortcut ID as shown by `list` into an index.
+fn parse_id(id: &str, app_state: &AppState) -> Result<usize, CommandError> {
+    let number: usize = id
+        .parse()
+        .map_err(|_| CommandError::Usage(format!("`{id}` is not a valid ID")))?;
+
+    match number.checked_sub(1) {
+        Some(index) if index < app_state.shortcuts.get_all_shortcuts().len() => Ok(index),
+        _ => Err(CommandError::Failed(format!("no shortcut with ID {id}"))),
+    }
+}
+
+/// The ID of the shortcut at `index` as shown to the user.
+fn format_id(index: usize) -> String {
+    (index + 1).to_string()
+}
+
+fn save(app_state: &AppState) -> CommandResult {
+    app_state
+        .shortcuts
+        .save()
+        .map_err(|err| CommandError::Failed(format!("could not save shortcuts: {err}")))
+}
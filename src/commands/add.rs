@@ -1,22 +1,73 @@
-use crate::shortcuts::Shortcut;
-use crate::utils::take_input_line;
-use crate::{AppState, Command, CommandResult};
+use std::io::{self, Write};
 
-pub struct Add;
+use crate::{
+    app_state::AppState,
+    commands::{format_id, save, Args, Command, CommandError, CommandResult},
+    shortcuts::{KeyCombo, Shortcut},
+};
+
+pub struct Add {
+    key_combo: KeyCombo,
+    description: String,
+    app: Option<String>,
+    tags: Option<String>,
+}
+
+impl Add {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &["app", "tags"])?;
+        let Some((combo, description)) = args.positional.split_first() else {
+            return Err(CommandError::Usage(
+                "add needs a shortcut and a description".into(),
+            ));
+        };
+
+        let key_combo = combo
+            .parse()
+            .map_err(|err| CommandError::Usage(format!("invalid shortcut `{combo}`: {err}")))?;
+
+        let description = description.join(" ");
+        if description.trim().is_empty() {
+            return Err(CommandError::Usage("add needs a description".into()));
+        }
+
+        Ok(Self {
+            key_combo,
+            description: description.trim().to_owned(),
+            app: args.option("app").map(str::to_owned),
+            tags: args.option("tags").map(str::to_owned),
+        })
+    }
+}
 
 impl Command for Add {
     fn execute(&self, app_state: &mut AppState) -> CommandResult {
-        println!("You are adding a command!\n");
+        let mut new_shortcut = Shortcut::from(self.key_combo.clone(), self.description.clone());
+        new_shortcut.set_app(self.app.as_deref().unwrap_or_default());
+        new_shortcut.set_tags(self.tags.as_deref().unwrap_or_default());
 
-        let new_shortcut = Shortcut::from(
-            take_input_line("Shortcut (e.g. CTRL+S): "),
-            take_input_line("Description (e.g. open new tab): "),
-        );
+        if let Some(existing) = app_state.shortcuts.find(
+            new_shortcut.get_key_combo(),
+            new_shortcut.get_app(),
+            new_shortcut.get_context(),
+        ) {
+            return Err(CommandError::Failed(format!(
+                "{} already exists in {} with ID {}",
+                new_shortcut.get_key_combo(),
+                new_shortcut.get_app(),
+                format_id(existing)
+            )));
+        }
 
-        println!("New shortcut added! {}", new_shortcut);
-        app_state.shortcuts.add_shortcut(new_shortcut);
-        app_state.shortcuts.save().unwrap();
+        let index = app_state.shortcuts.add_shortcut(new_shortcut);
+        save(app_state)?;
 
-        CommandResult::Continue
+        writeln!(
+            io::stdout(),
+            "Added {}: {}",
+            format_id(index),
+            app_state.shortcuts.get_all_shortcuts()[index]
+        )?;
+        Ok(())
     }
 }
@@ -0,0 +1,65 @@
+use std::io::{self, Write};
+
+use crate::{
+    app_state::AppState,
+    commands::{parse_id, save, Args, Command, CommandError, CommandResult},
+};
+
+pub struct Bump {
+    id: String,
+    amount: u32,
+}
+
+impl Bump {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &["by"])?;
+        let id = match args.positional.as_slice() {
+            [id] => id.clone(),
+            [] => {
+                return Err(CommandError::Usage(
+                    "bump needs the ID of a shortcut".into(),
+                ))
+            }
+            [_, extra, ..] => {
+                return Err(CommandError::Usage(format!(
+                    "unexpected argument `{extra}`"
+                )))
+            }
+        };
+
+        let amount = match args.option("by") {
+            Some(amount) => amount
+                .parse()
+                .map_err(|_| CommandError::Usage(format!("`{amount}` is not a valid amount")))?,
+            None => 1,
+        };
+
+        Ok(Self { id, amount })
+    }
+}
+
+impl Command for Bump {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let index = parse_id(&self.id, app_state)?;
+        let count = app_state.shortcuts.get_all_shortcuts()[index].get_lookup_count();
+        if count.checked_add(self.amount).is_none() {
+            return Err(CommandError::Failed(format!(
+                "bumping by {} would take the count past {}",
+                self.amount,
+                u32::MAX
+            )));
+        }
+
+        app_state
+            .shortcuts
+            .increment_lookup_count(index, self.amount);
+        save(app_state)?;
+
+        writeln!(
+            io::stdout(),
+            "{}",
+            app_state.shortcuts.get_all_shortcuts()[index]
+        )?;
+        Ok(())
+    }
+}
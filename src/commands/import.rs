@@ -0,0 +1,146 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::{
+    app_state::AppState,
+    commands::{save, Args, Command, CommandError, CommandResult},
+    importers::{self, Change, Format, MergeSummary},
+};
+
+/// Adds the shortcuts from another program's keybindings file.
+pub struct Import {
+    format: Format,
+    path: PathBuf,
+    app: Option<String>,
+    tags: Option<String>,
+    /// Only list what the import would do.
+    dry_run: bool,
+}
+
+impl Import {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse_with_switches(args, &["app", "tags"], &["dry-run"])?;
+        let (format, path) = match args.positional.as_slice() {
+            [format, path] => (format, path),
+            [_, _, extra, ..] => {
+                return Err(CommandError::Usage(format!(
+                    "unexpected argument `{extra}`"
+                )))
+            }
+            _ => {
+                return Err(CommandError::Usage(
+                    "import needs a format and a file".into(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            format: format.parse().map_err(CommandError::Usage)?,
+            path: path.into(),
+            app: args.option("app").map(str::to_owned),
+            tags: args.option("tags").map(str::to_owned),
+            dry_run: args.switch("dry-run"),
+        })
+    }
+}
+
+impl Command for Import {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let text = fs::read_to_string(&self.path).map_err(|err| {
+            CommandError::Failed(format!("could not read {}: {err}", self.path.display()))
+        })?;
+        let mut imported = self
+            .format
+            .parse(&text, &self.path)
+            .map_err(|err| CommandError::Failed(format!("{}: {err}", self.path.display())))?;
+
+        for shortcut in &mut imported.shortcuts {
+            if let Some(app) = &self.app {
+                shortcut.set_app(app);
+            }
+            if let Some(tags) = &self.tags {
+                shortcut.set_tags(tags);
+            }
+        }
+
+        for reason in &imported.skipped {
+            eprintln!("shotty: skipped {reason}");
+        }
+
+        let changes = importers::plan(&app_state.shortcuts, imported.shortcuts);
+        let summary = MergeSummary::of(&changes);
+
+        if self.dry_run {
+            let mut out = io::stdout().lock();
+            for change in &changes {
+                print_change(&mut out, change, app_state)?;
+            }
+            writeln!(
+                out,
+                "Would import {} shortcuts, {} updated, {} already present, {} skipped",
+                summary.added,
+                summary.updated,
+                summary.present,
+                imported.skipped.len()
+            )?;
+            return Ok(());
+        }
+
+        let file_name = self.path.file_name().unwrap_or(self.path.as_os_str());
+        let label = format!("import {}", file_name.to_string_lossy());
+        importers::merge(&mut app_state.shortcuts, changes, &label);
+        if summary.added + summary.updated > 0 {
+            save(app_state)?;
+        }
+
+        writeln!(
+            io::stdout(),
+            "Imported {} shortcuts, {} updated, {} already present, {} skipped",
+            summary.added,
+            summary.updated,
+            summary.present,
+            imported.skipped.len()
+        )?;
+        Ok(())
+    }
+}
+
+/// One line of the `--dry-run` preview: `+` for a new shortcut, `~` for a
+/// changed one. Shortcuts that already match aren't listed.
+fn print_change(out: &mut impl Write, change: &Change, app_state: &AppState) -> io::Result<()> {
+    match change {
+        Change::Add(shortcut) => {
+            let context = shortcut
+                .get_context()
+                .map(|context| format!(" [{context}]"))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "+ {}  {}{context}",
+                shortcut.get_key_combo(),
+                shortcut.get_description()
+            )?;
+        }
+        Change::Update(index, shortcut) => {
+            let old = &app_state.shortcuts.get_all_shortcuts()[*index];
+            let change = if old.get_description() == shortcut.get_description() {
+                let tags: Vec<&str> = shortcut.get_tags().iter().map(String::as_str).collect();
+                format!("tags {}", tags.join(", "))
+            } else {
+                format!("was: {}", old.get_description())
+            };
+            writeln!(
+                out,
+                "~ {} (ID {})  {} ({change})",
+                shortcut.get_key_combo(),
+                index + 1,
+                shortcut.get_description()
+            )?;
+        }
+        Change::Keep => {}
+    }
+    Ok(())
+}
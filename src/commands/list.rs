@@ -1,62 +1,61 @@
 use crate::{
-    AppState, Command, CommandResult,
-    utils::{self, take_input_number},
+    app_state::AppState,
+    commands::{
+        output::{print_shortcuts, OutputFormat},
+        Args, Command, CommandError, CommandResult,
+    },
+    shortcuts::Ranking,
 };
 
-pub struct List;
-
-impl Command for List {
-    fn execute(&self, app_state: &mut AppState) -> crate::CommandResult {
-        if app_state.shortcuts.get_all_shortcuts().is_empty() {
-            println!("No shortcuts are stored! Use the `add` command to add new shortcuts.");
-            return CommandResult::Continue;
-        };
-
-        let shortcuts = app_state.shortcuts.get_all_shortcuts();
-
-        let headers = ["Number", "Shortcut", "Description", "Lookup count"]
-            .map(String::from)
-            .to_vec();
-
-        let sorted_indexes = app_state.shortcuts.get_sorted_indexes();
-
-        utils::print_table(
-            headers,
-            sorted_indexes
-                .iter()
-                .enumerate()
-                .map(|(display_idx, &shortcut_index)| {
-                    let s = &shortcuts[shortcut_index];
-                    vec![
-                        (display_idx + 1).to_string(),
-                        s.get_key_combo().to_string(),
-                        s.get_description().to_string(),
-                        s.get_lookup_count().to_string(),
-                    ]
-                })
-                .collect(),
-        );
-
-        let Some(target_number) =
-            take_input_number("\nWhich of the following shortcuts you came for: ")
-        else {
-            println!("Invalid number entered!");
-            return CommandResult::Continue;
-        };
+pub struct List {
+    app: Option<String>,
+    tag: Option<String>,
+    sort: Ranking,
+    format: OutputFormat,
+}
 
-        if target_number < 1 || target_number > shortcuts.len().try_into().unwrap() {
-            println!("Target is out of range.");
-            return CommandResult::Continue;
+impl List {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &["app", "tag", "sort", "format"])?;
+        if let Some(extra) = args.positional.first() {
+            return Err(CommandError::Usage(format!(
+                "unexpected argument `{extra}`"
+            )));
         }
 
-        let target_index = target_number as usize - 1;
-
-        let real_idx = sorted_indexes[target_index];
-        app_state.shortcuts.increment_lookup_count(real_idx, 5);
+        let sort = match args.option("sort") {
+            None | Some("frecency") => Ranking::Frecency,
+            Some("lookups") => Ranking::Lookups,
+            Some(other) => {
+                return Err(CommandError::Usage(format!(
+                    "unknown sort order `{other}`, expected frecency or lookups"
+                )))
+            }
+        };
 
-        app_state.shortcuts.save().unwrap();
-        println!("Shortcut updated!");
+        Ok(Self {
+            app: args.option("app").map(str::to_owned),
+            tag: args.option("tag").map(str::to_owned),
+            sort,
+            format: OutputFormat::parse_option(args.option("format"))?,
+        })
+    }
+}
 
-        CommandResult::Continue
+impl Command for List {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let matching = app_state
+            .shortcuts
+            .filter_indexes(self.app.as_deref(), self.tag.as_deref());
+
+        let sorted_indexes: Vec<usize> = app_state
+            .shortcuts
+            .get_sorted_indexes(self.sort)
+            .into_iter()
+            .filter(|index| matching.binary_search(index).is_ok())
+            .collect();
+
+        print_shortcuts(app_state, &sorted_indexes, self.format)?;
+        Ok(())
     }
 }
@@ -0,0 +1,253 @@
+//! Output of the `list`, `search` and `show` commands.
+//!
+//! Three formats are available through `--format`:
+//!
+//! - `plain` (default): an aligned table for people, not meant to be parsed.
+//! - `tsv`: a header line followed by one line per shortcut, columns
+//!   `id`, `rank`, `shortcut`, `description`, `app`, `tags` and `lookup_count`
+//!   separated by tabs. Tags are joined with `,`. Tabs and line breaks inside
+//!   values are replaced by spaces.
+//! - `json`: a single document following the schema below.
+//!
+//! # JSON schema, version 1
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "shortcuts": [
+//!     {
+//!       "id": 2,
+//!       "rank": 1,
+//!       "shortcut": "Ctrl+X Ctrl+S",
+//!       "strokes": ["Ctrl+X", "Ctrl+S"],
+//!       "description": "save buffer",
+//!       "app": "emacs",
+//!       "context": null,
+//!       "tags": ["files"],
+//!       "lookup_count": 3,
+//!       "frecency": 2.71,
+//!       "created_at": 1760000000,
+//!       "updated_at": 1760000000,
+//!       "last_looked_up": 1760700000
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! - `id`: the ID the other commands accept, stable until a shortcut is removed.
+//! - `rank`: 1-based position when all shortcuts are ordered by lookups, as
+//!   returned by `Shortcuts::get_sorted_indexes`.
+//! - `shortcut`: the combo in canonical notation, `strokes` the same split
+//!   into the keys pressed one after another.
+//! - `context`: where within the app the shortcut applies, e.g. a VS Code
+//!   `when` clause, `null` for everywhere.
+//! - `tags`: sorted, lowercase.
+//! - `frecency`: recent lookups, each weighted down by its age, see
+//!   `Shortcuts::get_frecency`.
+//! - `created_at`, `updated_at`, `last_looked_up`: seconds since the Unix
+//!   epoch, `null` if unknown or, for `last_looked_up`, never.
+//!
+//! The `shortcuts` array is in the order the command ranks them: by `--sort`
+//! for `list`, by match quality for `search`.
+//!
+//! Fields may be added within a version; renaming or removing a field, or
+//! changing its meaning, bumps `schema_version`. These structs are separate
+//! from the ones used for the shortcuts file for exactly that reason.
+
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
+
+use serde::Serialize;
+
+use crate::{
+    app_state::AppState,
+    commands::{format_id, CommandError},
+    shortcuts::{Ranking, Shortcut},
+    utils,
+};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Plain,
+    Tsv,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(OutputFormat::Plain),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(CommandError::Usage(format!(
+                "unknown format `{other}`, expected json, tsv or plain"
+            ))),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Parses the value of a `--format` option, defaulting to plain.
+    pub fn parse_option(value: Option<&str>) -> Result<Self, CommandError> {
+        value.map_or(Ok(OutputFormat::Plain), str::parse)
+    }
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+    schema_version: u32,
+    shortcuts: Vec<ShortcutOutput<'a>>,
+}
+
+#[derive(Serialize)]
+struct ShortcutOutput<'a> {
+    id: usize,
+    rank: usize,
+    shortcut: String,
+    strokes: Vec<String>,
+    description: &'a str,
+    app: &'a str,
+    context: Option<&'a str>,
+    tags: Vec<&'a str>,
+    lookup_count: u32,
+    frecency: f64,
+    created_at: Option<u64>,
+    updated_at: Option<u64>,
+    last_looked_up: Option<u64>,
+}
+
+impl<'a> ShortcutOutput<'a> {
+    fn new(index: usize, rank: usize, frecency: f64, shortcut: &'a Shortcut) -> Self {
+        Self {
+            id: index + 1,
+            rank,
+            shortcut: shortcut.get_key_combo().to_string(),
+            strokes: shortcut
+                .get_key_combo()
+                .strokes()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            description: shortcut.get_description(),
+            app: shortcut.get_app(),
+            context: shortcut.get_context(),
+            tags: shortcut.get_tags().iter().map(String::as_str).collect(),
+            lookup_count: shortcut.get_lookup_count(),
+            // two decimals are plenty to compare them
+            frecency: (frecency * 100.0).round() / 100.0,
+            created_at: shortcut.get_created_at(),
+            updated_at: shortcut.get_updated_at(),
+            last_looked_up: shortcut.get_last_looked_up(),
+        }
+    }
+}
+
+/// Prints the shortcuts at `indexes` in the given order and format.
+pub fn print_shortcuts(
+    app_state: &AppState,
+    indexes: &[usize],
+    format: OutputFormat,
+) -> io::Result<()> {
+    let shortcuts = app_state.shortcuts.get_all_shortcuts();
+
+    // rank[index] is the 1-based position of shortcut `index` by lookups
+    let mut ranks = vec![0; shortcuts.len()];
+    for (position, index) in app_state
+        .shortcuts
+        .get_sorted_indexes(Ranking::Lookups)
+        .into_iter()
+        .enumerate()
+    {
+        ranks[index] = position + 1;
+    }
+
+    let rows: Vec<ShortcutOutput> = indexes
+        .iter()
+        .map(|&index| {
+            let frecency = app_state.shortcuts.get_frecency(index);
+            ShortcutOutput::new(index, ranks[index], frecency, &shortcuts[index])
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Plain => print_plain(rows),
+        OutputFormat::Tsv => print_tsv(rows),
+        OutputFormat::Json => {
+            let document = Document {
+                schema_version: SCHEMA_VERSION,
+                shortcuts: rows,
+            };
+            let json = serde_json::to_string_pretty(&document)?;
+            writeln!(io::stdout(), "{json}")
+        }
+    }
+}
+
+fn print_plain(rows: Vec<ShortcutOutput>) -> io::Result<()> {
+    let mut headers = [
+        "ID",
+        "Shortcut",
+        "Description",
+        "App",
+        "Tags",
+        "Lookup count",
+    ]
+    .map(String::from)
+    .to_vec();
+    let show_context = rows.iter().any(|row| row.context.is_some());
+    if show_context {
+        headers.push("Context".into());
+    }
+
+    utils::print_table(
+        headers,
+        rows.into_iter()
+            .map(|row| {
+                let mut cells = vec![
+                    format_id(row.id - 1),
+                    row.shortcut,
+                    row.description.to_string(),
+                    row.app.to_string(),
+                    row.tags.join(","),
+                    row.lookup_count.to_string(),
+                ];
+                if show_context {
+                    cells.push(row.context.unwrap_or_default().to_string());
+                }
+                cells
+            })
+            .collect(),
+    )
+}
+
+fn print_tsv(rows: Vec<ShortcutOutput>) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    writeln!(
+        out,
+        "id\trank\tshortcut\tdescription\tapp\ttags\tlookup_count"
+    )?;
+    for row in rows {
+        let fields = [
+            row.id.to_string(),
+            row.rank.to_string(),
+            row.shortcut,
+            row.description.to_string(),
+            row.app.to_string(),
+            row.tags.join(","),
+            row.lookup_count.to_string(),
+        ];
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| field.replace(['\t', '\n', '\r'], " "))
+            .collect();
+        writeln!(out, "{}", fields.join("\t"))?;
+    }
+    Ok(())
+}
@@ -1,60 +1,56 @@
-use crate::utils::{self, take_input_number};
-use crate::{AppState, Command, CommandResult};
+use std::io::{self, Write};
 
-pub struct Remove;
+use crate::{
+    app_state::AppState,
+    commands::{parse_id, save, Args, Command, CommandError, CommandResult},
+};
 
-impl Command for Remove {
-    fn execute(&self, app_state: &mut AppState) -> CommandResult {
-        if app_state.shortcuts.get_all_shortcuts().is_empty() {
-            println!("No shortcuts are stored! Nothing to delete.");
-            return CommandResult::Continue;
-        };
-
-        let shortcuts = app_state.shortcuts.get_all_shortcuts();
-
-        let sorted_indexes = app_state.shortcuts.get_sorted_indexes();
-
-        let headers = ["Number", "Shortcut", "Description", "Lookup count"]
-            .map(String::from)
-            .to_vec();
-
-        utils::print_table(
-            headers,
-            sorted_indexes
-                .iter()
-                .enumerate()
-                .map(|(display_idx, &shortcut_index)| {
-                    let s = &shortcuts[shortcut_index];
-                    vec![
-                        (display_idx + 1).to_string(),
-                        s.get_key_combo().to_string(),
-                        s.get_description().to_string(),
-                        s.get_lookup_count().to_string(),
-                    ]
-                })
-                .collect(),
-        );
-
-        let Some(target_number) = take_input_number("\nWhich shortcut do you want to remove: ")
-        else {
-            println!("Invalid number entered!");
-            return CommandResult::Continue;
-        };
+pub struct Remove {
+    ids: Vec<String>,
+}
 
-        if target_number < 1 || target_number > shortcuts.len().try_into().unwrap() {
-            println!("Target is out of range.");
-            return CommandResult::Continue;
+impl Remove {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &[])?;
+        if args.positional.is_empty() {
+            return Err(CommandError::Usage(
+                "remove needs the ID of a shortcut".into(),
+            ));
         }
 
-        let target_index = target_number as usize - 1;
-
-        let real_idx = sorted_indexes[target_index];
-
-        app_state.shortcuts.remove_at(real_idx).unwrap();
-        app_state.shortcuts.save().unwrap();
-
-        println!("Shortcut removed!");
+        Ok(Self {
+            ids: args.positional,
+        })
+    }
+}
 
-        CommandResult::Continue
+impl Command for Remove {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let mut indexes = self
+            .ids
+            .iter()
+            .map(|id| parse_id(id, app_state))
+            .collect::<Result<Vec<usize>, CommandError>>()?;
+        // back to front, so removing one doesn't shift the IDs of the others
+        indexes.sort_unstable_by(|a, b| b.cmp(a));
+        indexes.dedup();
+
+        if indexes.len() > 1 {
+            app_state
+                .shortcuts
+                .begin_batch(&format!("delete {} shortcuts", indexes.len()));
+        }
+        let removed: Vec<_> = indexes
+            .iter()
+            .filter_map(|&index| app_state.shortcuts.remove_at(index))
+            .collect();
+        app_state.shortcuts.end_batch();
+        save(app_state)?;
+
+        let mut out = io::stdout().lock();
+        for shortcut in removed.iter().rev() {
+            writeln!(out, "Removed {shortcut}")?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+
+use crate::{
+    app_state::AppState,
+    commands::{Args, Command, CommandError, CommandResult},
+    shortcuts::Shortcuts,
+    utils,
+};
+
+/// Lists the backups of the shortcuts file, or restores one of them.
+pub struct Restore {
+    number: Option<usize>,
+}
+
+impl Restore {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &[])?;
+        let number = match args.positional.as_slice() {
+            [] => None,
+            [number] => Some(
+                number
+                    .parse()
+                    .ok()
+                    .filter(|&number| number > 0)
+                    .ok_or_else(|| {
+                        CommandError::Usage(format!("`{number}` is not a valid backup number"))
+                    })?,
+            ),
+            [_, extra, ..] => {
+                return Err(CommandError::Usage(format!(
+                    "unexpected argument `{extra}`"
+                )))
+            }
+        };
+
+        Ok(Self { number })
+    }
+}
+
+impl Command for Restore {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let backups = app_state
+            .shortcuts
+            .list_backups()
+            .map_err(|err| CommandError::Failed(format!("could not list backups: {err}")))?;
+
+        let Some(number) = self.number else {
+            if backups.is_empty() {
+                writeln!(io::stdout(), "No backups yet")?;
+                return Ok(());
+            }
+
+            let rows = backups
+                .iter()
+                .enumerate()
+                .map(|(i, backup)| {
+                    let count = match Shortcuts::read_from_file(&backup.path) {
+                        Ok(shortcuts) => shortcuts.get_all_shortcuts().len().to_string(),
+                        Err(_) => "unreadable".into(),
+                    };
+                    vec![
+                        (i + 1).to_string(),
+                        format!("{} UTC", backup.taken_at),
+                        count,
+                    ]
+                })
+                .collect();
+            utils::print_table(
+                ["#", "Taken at", "Shortcuts"].map(String::from).to_vec(),
+                rows,
+            )?;
+            writeln!(io::stdout(), "\nRun `shotty restore <#>` to restore one.")?;
+            return Ok(());
+        };
+
+        let backup = backups.get(number - 1).ok_or_else(|| {
+            CommandError::Failed(format!(
+                "no backup number {number}, there are {}",
+                backups.len()
+            ))
+        })?;
+
+        let restored = Shortcuts::read_from_file(&backup.path)
+            .map_err(|err| CommandError::Failed(format!("could not read the backup: {err}")))?;
+        app_state
+            .shortcuts
+            .restore(restored)
+            .map_err(|err| CommandError::Failed(format!("could not restore the backup: {err}")))?;
+
+        writeln!(
+            io::stdout(),
+            "Restored {} shortcuts from the backup taken at {} UTC",
+            app_state.shortcuts.get_all_shortcuts().len(),
+            backup.taken_at
+        )?;
+        Ok(())
+    }
+}
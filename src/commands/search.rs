@@ -0,0 +1,60 @@
+use crate::{
+    app_state::AppState,
+    commands::{
+        output::{print_shortcuts, OutputFormat},
+        Args, Command, CommandError, CommandResult,
+    },
+};
+
+pub struct Search {
+    query: String,
+    app: Option<String>,
+    tag: Option<String>,
+    format: OutputFormat,
+}
+
+impl Search {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &["app", "tag", "format"])?;
+        let query = args.positional.join(" ");
+        if query.trim().is_empty() {
+            return Err(CommandError::Usage("search needs a query".into()));
+        }
+
+        Ok(Self {
+            query,
+            app: args.option("app").map(str::to_owned),
+            tag: args.option("tag").map(str::to_owned),
+            format: OutputFormat::parse_option(args.option("format"))?,
+        })
+    }
+}
+
+impl Command for Search {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let candidates = app_state
+            .shortcuts
+            .filter_indexes(self.app.as_deref(), self.tag.as_deref());
+
+        let ranked: Vec<usize> = app_state
+            .shortcuts
+            .search(&self.query, &candidates)
+            .into_iter()
+            .map(|result| result.index)
+            .collect();
+
+        if ranked.is_empty() {
+            // scripts still get a well-formed, empty document
+            if self.format != OutputFormat::Plain {
+                print_shortcuts(app_state, &ranked, self.format)?;
+            }
+            return Err(CommandError::Failed(format!(
+                "no shortcut matches `{}`",
+                self.query
+            )));
+        }
+
+        print_shortcuts(app_state, &ranked, self.format)?;
+        Ok(())
+    }
+}
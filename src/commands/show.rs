@@ -0,0 +1,44 @@
+use crate::{
+    app_state::AppState,
+    commands::{
+        output::{print_shortcuts, OutputFormat},
+        parse_id, Args, Command, CommandError, CommandResult,
+    },
+};
+
+pub struct Show {
+    id: String,
+    format: OutputFormat,
+}
+
+impl Show {
+    pub fn parse(args: &[String]) -> Result<Self, CommandError> {
+        let args = Args::parse(args, &["format"])?;
+        let id = match args.positional.as_slice() {
+            [id] => id.clone(),
+            [] => {
+                return Err(CommandError::Usage(
+                    "show needs the ID of a shortcut".into(),
+                ))
+            }
+            [_, extra, ..] => {
+                return Err(CommandError::Usage(format!(
+                    "unexpected argument `{extra}`"
+                )))
+            }
+        };
+
+        Ok(Self {
+            id,
+            format: OutputFormat::parse_option(args.option("format"))?,
+        })
+    }
+}
+
+impl Command for Show {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let index = parse_id(&self.id, app_state)?;
+        print_shortcuts(app_state, &[index], self.format)?;
+        Ok(())
+    }
+}
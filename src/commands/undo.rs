@@ -0,0 +1,47 @@
+use std::io::{self, Write};
+
+use crate::{
+    app_state::AppState,
+    commands::{save, Args, Command, CommandError, CommandResult},
+};
+
+/// Undoes the last change, or with `redo` set, redoes the last undone one.
+///
+/// Only useful with the journal on, otherwise every run starts without history.
+pub struct Undo {
+    redo: bool,
+}
+
+impl Undo {
+    pub fn parse(args: &[String], redo: bool) -> Result<Self, CommandError> {
+        if let Some(extra) = Args::parse(args, &[])?.positional.first() {
+            return Err(CommandError::Usage(format!(
+                "unexpected argument `{extra}`"
+            )));
+        }
+        Ok(Self { redo })
+    }
+}
+
+impl Command for Undo {
+    fn execute(&self, app_state: &mut AppState) -> CommandResult {
+        let (operation, done, what) = if self.redo {
+            (app_state.shortcuts.redo(), "Redid", "redo")
+        } else {
+            (app_state.shortcuts.undo(), "Undid", "undo")
+        };
+
+        let Some(operation) = operation else {
+            let hint = if app_state.shortcuts.has_journal() {
+                ""
+            } else {
+                ", set SHOTTY_UNDO_JOURNAL=1 to keep the history between runs"
+            };
+            return Err(CommandError::Failed(format!("nothing to {what}{hint}")));
+        };
+
+        save(app_state)?;
+        writeln!(io::stdout(), "{done} {}", operation.label)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    env,
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Where shortcuts used to be kept: `shortcuts.json` in the working directory.
+const LEGACY_FILE: &str = "shortcuts.json";
+
+const DEFAULT_FILE: &str = "shortcuts.json";
+const PROFILES_DIR: &str = "profiles";
+
+/// How the user asked for the shortcuts file to be picked, from the command
+/// line and the environment.
+#[derive(Debug, Default)]
+pub struct LocationOptions {
+    /// `--file PATH`
+    pub file: Option<PathBuf>,
+    /// `--profile NAME`
+    pub profile: Option<String>,
+}
+
+/// The shortcuts file to use, in order of precedence: `--file`, `--profile`,
+/// `$SHOTTY_FILE`, and `$XDG_DATA_HOME/shotty/shortcuts.json` by default.
+///
+/// Profiles live next to the default file, in
+/// `$XDG_DATA_HOME/shotty/profiles/NAME.json`.
+pub fn resolve(options: &LocationOptions) -> Result<PathBuf, String> {
+    resolve_with(options, &|name| env::var_os(name))
+}
+
+/// [`resolve`] with the environment variables read by `var`.
+fn resolve_with(
+    options: &LocationOptions,
+    var: &dyn Fn(&str) -> Option<OsString>,
+) -> Result<PathBuf, String> {
+    if let Some(file) = &options.file {
+        return Ok(file.clone());
+    }
+
+    if let Some(profile) = &options.profile {
+        validate_profile(profile)?;
+        return Ok(data_dir(var)?
+            .join(PROFILES_DIR)
+            .join(format!("{profile}.json")));
+    }
+
+    if let Some(file) = var("SHOTTY_FILE").filter(|file| !file.is_empty()) {
+        return Ok(file.into());
+    }
+
+    Ok(data_dir(var)?.join(DEFAULT_FILE))
+}
+
+/// Whether `options` leave the choice of file to the default location, the
+/// only one an old file from the working directory is migrated to.
+pub fn is_default(options: &LocationOptions) -> bool {
+    options.file.is_none()
+        && options.profile.is_none()
+        && env::var_os("SHOTTY_FILE").is_none_or(|file| file.is_empty())
+}
+
+/// An old shortcuts file that was copied to the new location. Displays as
+/// the notice telling the user about it.
+#[derive(Debug)]
+pub struct LegacyMigration {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl fmt::Display for LegacyMigration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "copied {} to {}, shortcuts are kept there from now on \
+             and the old file can be deleted",
+            self.from.display(),
+            self.to.display()
+        )
+    }
+}
+
+/// Copies a `shortcuts.json` from the working directory to `destination` if
+/// there is one and nothing exists at `destination` yet.
+///
+/// The old file is left alone, so older versions of shotty can still use it.
+pub fn migrate_legacy_file(destination: &Path) -> io::Result<Option<LegacyMigration>> {
+    migrate_legacy_file_from(&env::current_dir()?, destination)
+}
+
+/// [`migrate_legacy_file`] with the old file looked for in `dir`.
+fn migrate_legacy_file_from(dir: &Path, destination: &Path) -> io::Result<Option<LegacyMigration>> {
+    let legacy = dir.join(LEGACY_FILE);
+    if destination.exists() || !legacy.is_file() || legacy == destination {
+        return Ok(None);
+    }
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(&legacy, destination)?;
+    Ok(Some(LegacyMigration {
+        from: legacy,
+        to: destination.to_owned(),
+    }))
+}
+
+/// `$XDG_DATA_HOME/shotty`, falling back to `~/.local/share/shotty` as the
+/// XDG base directory spec says.
+fn data_dir(var: &dyn Fn(&str) -> Option<OsString>) -> Result<PathBuf, String> {
+    // relative paths are invalid per the spec and must be ignored
+    let xdg_data_home = var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute());
+
+    let data_home =
+        match xdg_data_home {
+            Some(path) => path,
+            None => match var("HOME").filter(|home| !home.is_empty()) {
+                Some(home) => PathBuf::from(home).join(".local").join("share"),
+                None => return Err(
+                    "could not find a data directory, set XDG_DATA_HOME or HOME, or pass --file"
+                        .into(),
+                ),
+            },
+        };
+
+    Ok(data_home.join("shotty"))
+}
+
+fn validate_profile(profile: &str) -> Result<(), String> {
+    let valid = !profile.is_empty()
+        && !profile.starts_with('.')
+        && profile
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid profile name `{profile}`, use letters, digits, `-`, `_` and `.`"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    const ENV: &[(&str, &str)] = &[
+        ("SHOTTY_FILE", "/env/shortcuts.json"),
+        ("XDG_DATA_HOME", "/xdg"),
+        ("HOME", "/home/me"),
+    ];
+
+    /// Resolves with only `vars` set in the environment.
+    fn resolve_in(options: &LocationOptions, vars: &[(&str, &str)]) -> Result<PathBuf, String> {
+        resolve_with(options, &|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.into())
+        })
+    }
+
+    fn profile(name: &str) -> LocationOptions {
+        LocationOptions {
+            file: None,
+            profile: Some(name.to_owned()),
+        }
+    }
+
+    #[test]
+    fn options_win_over_the_environment() {
+        let both = LocationOptions {
+            file: Some("/given.json".into()),
+            profile: Some("work".into()),
+        };
+        assert_eq!(resolve_in(&both, ENV), Ok("/given.json".into()));
+        assert_eq!(
+            resolve_in(&profile("work"), ENV),
+            Ok("/xdg/shotty/profiles/work.json".into())
+        );
+    }
+
+    #[test]
+    fn the_environment_picks_the_default_file() {
+        let default = LocationOptions::default();
+        assert_eq!(resolve_in(&default, ENV), Ok("/env/shortcuts.json".into()));
+        assert_eq!(
+            resolve_in(&default, &ENV[1..]),
+            Ok("/xdg/shotty/shortcuts.json".into())
+        );
+        assert_eq!(
+            resolve_in(&default, &ENV[2..]),
+            Ok("/home/me/.local/share/shotty/shortcuts.json".into())
+        );
+        assert!(resolve_in(&default, &[]).is_err());
+    }
+
+    #[test]
+    fn empty_and_relative_variables_are_ignored() {
+        let default = LocationOptions::default();
+        let vars = [
+            ("SHOTTY_FILE", ""),
+            ("XDG_DATA_HOME", "relative/data"),
+            ("HOME", "/home/me"),
+        ];
+        assert_eq!(
+            resolve_in(&default, &vars),
+            Ok("/home/me/.local/share/shotty/shortcuts.json".into())
+        );
+        assert!(resolve_in(&default, &[("HOME", "")]).is_err());
+    }
+
+    #[test]
+    fn profile_names_stay_in_the_profiles_dir() {
+        for name in ["work", "my-laptop_2", "v1.2"] {
+            assert!(resolve_in(&profile(name), ENV).is_ok(), "{name}");
+        }
+        for name in ["", "..", "../work", "a/b", "/etc/passwd", ".hidden", "a\\b"] {
+            assert!(resolve_in(&profile(name), ENV).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn legacy_file_is_copied_once() {
+        let dir = TempDir::new("legacy-file-is-copied-once");
+        let cwd = dir.path().join("project");
+        fs::create_dir(&cwd).unwrap();
+        let legacy = cwd.join(LEGACY_FILE);
+        fs::write(&legacy, "old").unwrap();
+        let destination = dir.path().join("data").join("shotty").join(DEFAULT_FILE);
+
+        let migration = migrate_legacy_file_from(&cwd, &destination)
+            .unwrap()
+            .expect("copied");
+        assert_eq!(migration.from, legacy);
+        assert_eq!(migration.to, destination);
+        assert_eq!(
+            migration.to_string(),
+            format!(
+                "copied {} to {}, shortcuts are kept there from now on and the old file can be \
+                 deleted",
+                legacy.display(),
+                destination.display()
+            )
+        );
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "old");
+        assert!(legacy.exists(), "older versions still use it");
+
+        // from now on the new file is used, whatever happens to the old one
+        fs::write(&legacy, "changed").unwrap();
+        assert!(migrate_legacy_file_from(&cwd, &destination)
+            .unwrap()
+            .is_none());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "old");
+    }
+
+    #[test]
+    fn nothing_to_migrate() {
+        let dir = TempDir::new("nothing-to-migrate");
+        let destination = dir.path().join("data").join(DEFAULT_FILE);
+        assert!(migrate_legacy_file_from(dir.path(), &destination)
+            .unwrap()
+            .is_none());
+        assert!(!destination.exists());
+
+        // the working directory is the data directory
+        let legacy = dir.path().join(LEGACY_FILE);
+        fs::write(&legacy, "old").unwrap();
+        assert!(migrate_legacy_file_from(dir.path(), &legacy)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,182 @@
+/// Result of a successful [`fuzzy_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better. Only comparable between matches of the same pattern.
+    pub score: i64,
+    /// Char indices into the matched text, in ascending order.
+    pub positions: Vec<usize>,
+}
+
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 8;
+const WORD_START: i64 = 10;
+const TEXT_START: i64 = 4;
+const GAP: i64 = 1;
+const MAX_LEADING_GAP: i64 = 8;
+
+const NONE: i64 = i64::MIN / 2;
+
+/// Matches `pattern` as a case-insensitive subsequence of `text`.
+///
+/// Whitespace in the pattern is ignored, so `ctrl s` finds `Ctrl+S`. Among all
+/// possible alignments the best scoring one is returned: matches at the start
+/// of words and runs of consecutive characters are rewarded, gaps penalized.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let text: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text
+        .iter()
+        .map(|&c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    let (n, m) = (pattern.len(), text.len());
+    if n > m {
+        return None;
+    }
+
+    // score[i][j]: best score with pattern[..=i] matched and pattern[i] at text[j]
+    let mut score = vec![vec![NONE; m]; n];
+    let mut previous = vec![vec![0; m]; n];
+
+    for i in 0..n {
+        // best predecessor in row i - 1 at least one char before j - 1,
+        // already charged with the gap up to the current column
+        let mut best = NONE;
+        let mut best_position = 0;
+
+        for j in 0..m {
+            if i > 0 && j >= 2 {
+                if best > NONE {
+                    best -= GAP;
+                }
+                let candidate = score[i - 1][j - 2];
+                if candidate > NONE && candidate - GAP > best {
+                    best = candidate - GAP;
+                    best_position = j - 2;
+                }
+            }
+
+            if lower[j] != pattern[i] {
+                continue;
+            }
+
+            let bonus = MATCH + boundary_bonus(&text, j);
+            if i == 0 {
+                score[i][j] = bonus - (j as i64 * GAP).min(MAX_LEADING_GAP);
+                continue;
+            }
+
+            let mut value = NONE;
+            if j >= 1 && score[i - 1][j - 1] > NONE {
+                value = score[i - 1][j - 1] + CONSECUTIVE;
+                previous[i][j] = j - 1;
+            }
+            if best > value {
+                value = best;
+                previous[i][j] = best_position;
+            }
+            if value > NONE {
+                score[i][j] = value + bonus;
+            }
+        }
+    }
+
+    let (mut j, &best) = score[n - 1]
+        .iter()
+        .enumerate()
+        .filter(|(_, &value)| value > NONE)
+        .max_by_key(|(j, &value)| (value, std::cmp::Reverse(*j)))?;
+
+    let mut positions = vec![0; n];
+    for i in (0..n).rev() {
+        positions[i] = j;
+        j = previous[i][j];
+    }
+
+    Some(FuzzyMatch {
+        score: best,
+        positions,
+    })
+}
+
+fn boundary_bonus(text: &[char], j: usize) -> i64 {
+    let Some(j_before) = j.checked_sub(1) else {
+        return WORD_START + TEXT_START;
+    };
+
+    let (before, current) = (text[j_before], text[j]);
+    if !before.is_alphanumeric() && current.is_alphanumeric() {
+        WORD_START
+    } else if before.is_lowercase() && current.is_uppercase() {
+        // camelCase hump
+        WORD_START / 2
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(pattern: &str, text: &str) -> Option<Vec<usize>> {
+        fuzzy_match(pattern, text).map(|found| found.positions)
+    }
+
+    fn score(pattern: &str, text: &str) -> i64 {
+        fuzzy_match(pattern, text).unwrap().score
+    }
+
+    #[test]
+    fn matches_subsequences() {
+        assert_eq!(positions("cs", "Ctrl+S"), Some(vec![0, 5]));
+        assert_eq!(positions("ctrl s", "Ctrl+S"), Some(vec![0, 1, 2, 3, 5]));
+        assert_eq!(positions("sc", "Ctrl+S"), None);
+        assert_eq!(positions("ctrl+ss", "Ctrl+S"), None);
+    }
+
+    #[test]
+    fn empty_pattern_matches_anything() {
+        let found = fuzzy_match(" ", "Ctrl+S").unwrap();
+        assert_eq!(found.score, 0);
+        assert!(found.positions.is_empty());
+        assert!(fuzzy_match("", "").is_some());
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert_eq!(positions("CTRL", "ctrl"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(positions("ctrl", "CTRL"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(score("CTRL", "ctrl"), score("ctrl", "ctrl"));
+    }
+
+    #[test]
+    fn positions_are_char_indices() {
+        assert_eq!(positions("s", "é s"), Some(vec![2]));
+        assert_eq!(positions("ÉS", "café s"), Some(vec![3, 5]));
+    }
+
+    #[test]
+    fn prefers_word_starts() {
+        assert_eq!(positions("t", "cut text"), Some(vec![4]));
+        assert_eq!(positions("s", "newSelection"), Some(vec![3]));
+        assert!(score("cs", "Ctrl+S") > score("cs", "docs"));
+        assert!(score("sel", "Selection") > score("sel", "cancel selection"));
+    }
+
+    #[test]
+    fn prefers_consecutive_runs() {
+        assert_eq!(positions("ab", "a_x ab"), Some(vec![4, 5]));
+        assert!(score("line", "line") > score("line", "lxixnxe"));
+    }
+}
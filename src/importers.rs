@@ -0,0 +1,591 @@
+//! Reading shortcuts from the keybinding files of other programs, for
+//! `shotty import`.
+//!
+//! Every format turns a file into [`Imported`] shortcuts, which [`merge`]
+//! then adds to the user's shortcuts as a single undoable operation.
+
+use std::{cmp::Reverse, path::Path, str::FromStr};
+
+use crate::shortcuts::{Key, KeyStroke, Modifiers, Shortcut, Shortcuts};
+
+mod alacritty;
+mod hyprland;
+mod i3;
+mod jetbrains;
+mod kitty;
+mod readline;
+mod screen;
+mod sxhkd;
+mod tmux;
+mod vscode;
+mod wezterm;
+mod zellij;
+mod zsh;
+
+/// The keybinding files shotty can import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// VS Code's `keybindings.json`.
+    VsCode,
+    /// `.tmux.conf` or the output of `tmux list-keys`.
+    Tmux,
+    /// The `keybinds` block of Zellij's `config.kdl`.
+    Zellij,
+    /// GNU screen's `.screenrc`.
+    Screen,
+    /// i3's config file.
+    I3,
+    /// Sway's config file, which extends i3's.
+    Sway,
+    /// `hyprland.conf`.
+    Hyprland,
+    /// `sxhkdrc`.
+    Sxhkd,
+    /// A keymap `.xml` of IntelliJ IDEA, CLion and the other JetBrains IDEs.
+    JetBrains,
+    /// `~/.inputrc` or the output of bash's `bind -p` or `bind -P`.
+    Readline,
+    /// The output of zsh's `bindkey -L`, or a `.zshrc`.
+    Zsh,
+    /// `alacritty.toml`.
+    Alacritty,
+    /// `kitty.conf`.
+    Kitty,
+    /// `wezterm.lua`.
+    WezTerm,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vscode" | "code" => Ok(Format::VsCode),
+            "tmux" => Ok(Format::Tmux),
+            "zellij" => Ok(Format::Zellij),
+            "screen" => Ok(Format::Screen),
+            "i3" => Ok(Format::I3),
+            "sway" => Ok(Format::Sway),
+            "hyprland" => Ok(Format::Hyprland),
+            "sxhkd" => Ok(Format::Sxhkd),
+            "jetbrains" | "intellij" => Ok(Format::JetBrains),
+            "readline" | "inputrc" | "bash" => Ok(Format::Readline),
+            "zsh" => Ok(Format::Zsh),
+            "alacritty" => Ok(Format::Alacritty),
+            "kitty" => Ok(Format::Kitty),
+            "wezterm" => Ok(Format::WezTerm),
+            other => Err(format!(
+                "unknown import format `{other}`, expected vscode, tmux, zellij, screen, \
+                 i3, sway, hyprland, sxhkd, jetbrains, readline, zsh, alacritty, kitty or \
+                 wezterm"
+            )),
+        }
+    }
+}
+
+impl Format {
+    /// The app imported shortcuts belong to unless the user picks another.
+    pub fn app(self) -> &'static str {
+        match self {
+            Format::VsCode => "vscode",
+            Format::Tmux => "tmux",
+            Format::Zellij => "zellij",
+            Format::Screen => "screen",
+            Format::I3 => "i3",
+            Format::Sway => "sway",
+            Format::Hyprland => "hyprland",
+            Format::Sxhkd => "sxhkd",
+            Format::JetBrains => "jetbrains",
+            Format::Readline => "readline",
+            Format::Zsh => "zsh",
+            Format::Alacritty => "alacritty",
+            Format::Kitty => "kitty",
+            Format::WezTerm => "wezterm",
+        }
+    }
+
+    /// Reads the shortcuts from `text`, the contents of the file at `path`
+    /// in this format. Files it refers to are looked up relative to `path`.
+    ///
+    /// Fails only if the file as a whole can't be read, single bindings that
+    /// don't make sense are skipped.
+    pub fn parse(self, text: &str, path: &Path) -> Result<Imported, String> {
+        let mut imported = Imported::default();
+        match self {
+            Format::VsCode => vscode::parse(text, &mut imported)?,
+            Format::Tmux => tmux::parse(text, &mut imported),
+            Format::Zellij => zellij::parse(text, &mut imported)?,
+            Format::Screen => screen::parse(text, &mut imported),
+            Format::I3 | Format::Sway => i3::parse(text, &mut imported),
+            Format::Hyprland => hyprland::parse(text, &mut imported),
+            Format::Sxhkd => sxhkd::parse(text, &mut imported),
+            Format::JetBrains => jetbrains::parse(text, path, &mut imported)?,
+            Format::Readline => readline::parse(text, &mut imported),
+            Format::Zsh => zsh::parse(text, &mut imported),
+            Format::Alacritty => alacritty::parse(text, &mut imported)?,
+            Format::Kitty => kitty::parse(text, &mut imported),
+            Format::WezTerm => wezterm::parse(text, &mut imported)?,
+        }
+
+        for shortcut in &mut imported.shortcuts {
+            shortcut.set_app(self.app());
+        }
+        Ok(imported)
+    }
+}
+
+/// What was read from a keybindings file.
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub shortcuts: Vec<Shortcut>,
+    /// Bindings that could not be imported, each with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl Imported {
+    /// Adds a shortcut, replacing an earlier one with the same combo and
+    /// context: like in the programs themselves, the last binding wins.
+    fn push(&mut self, shortcut: Shortcut) {
+        let earlier = self.shortcuts.iter().position(|other| {
+            other.get_key_combo() == shortcut.get_key_combo()
+                && other.get_context() == shortcut.get_context()
+        });
+        match earlier {
+            Some(index) => self.shortcuts[index] = shortcut,
+            None => self.shortcuts.push(shortcut),
+        }
+    }
+
+    fn skip(&mut self, reason: String) {
+        self.skipped.push(reason);
+    }
+
+    /// The shortcuts as `combo = description`, followed by ` [context]` if
+    /// they have one, for tests to compare.
+    #[cfg(test)]
+    fn lines(&self) -> Vec<String> {
+        self.shortcuts
+            .iter()
+            .map(|shortcut| {
+                let line = format!(
+                    "{} = {}",
+                    shortcut.get_key_combo(),
+                    shortcut.get_description()
+                );
+                match shortcut.get_context() {
+                    Some(context) => format!("{line} [{context}]"),
+                    None => line,
+                }
+            })
+            .collect()
+    }
+}
+
+/// What importing one shortcut does to the user's shortcuts.
+#[derive(Debug)]
+pub enum Change {
+    Add(Shortcut),
+    /// Replaces the shortcut at the index, whose description or tags differ.
+    Update(usize, Shortcut),
+    /// An existing shortcut already matches.
+    Keep,
+}
+
+/// How many shortcuts [`merge`] adds, updates and leaves alone.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MergeSummary {
+    pub added: usize,
+    /// Existing shortcuts whose description or tags changed.
+    pub updated: usize,
+    /// Existing shortcuts that already matched.
+    pub present: usize,
+}
+
+impl MergeSummary {
+    pub fn of(changes: &[Change]) -> Self {
+        let mut summary = MergeSummary::default();
+        for change in changes {
+            match change {
+                Change::Add(_) => summary.added += 1,
+                Change::Update(..) => summary.updated += 1,
+                Change::Keep => summary.present += 1,
+            }
+        }
+        summary
+    }
+}
+
+/// Works out what importing `imported` changes, without changing anything.
+///
+/// A shortcut with the same combo, app and context is the same binding
+/// imported before: it takes the new description and gains the new tags,
+/// keeping its lookups, tags and review schedule.
+pub fn plan(shortcuts: &Shortcuts, imported: Vec<Shortcut>) -> Vec<Change> {
+    imported
+        .into_iter()
+        .map(|shortcut| {
+            let existing = shortcuts.find(
+                shortcut.get_key_combo(),
+                shortcut.get_app(),
+                shortcut.get_context(),
+            );
+            let Some(index) = existing else {
+                return Change::Add(shortcut);
+            };
+
+            let mut updated = shortcuts.get_all_shortcuts()[index].clone();
+            let mut changed = updated.get_description() != shortcut.get_description();
+            updated.set_description(shortcut.get_description());
+            for tag in shortcut.get_tags() {
+                changed |= updated.add_tag(tag);
+            }
+
+            if changed {
+                Change::Update(index, updated)
+            } else {
+                Change::Keep
+            }
+        })
+        .collect()
+}
+
+/// Applies the `changes` from [`plan`] as one operation labelled `label`
+/// for undo.
+pub fn merge(shortcuts: &mut Shortcuts, changes: Vec<Change>, label: &str) {
+    shortcuts.begin_batch(label);
+    for change in changes {
+        match change {
+            Change::Add(shortcut) => {
+                shortcuts.add_shortcut(shortcut);
+            }
+            Change::Update(index, shortcut) => {
+                shortcuts.update(index, shortcut);
+            }
+            Change::Keep => {}
+        }
+    }
+    shortcuts.end_batch();
+}
+
+/// Turns an identifier such as `commentLine`, `focus_next` or `move-word`
+/// into lowercase words: "comment line".
+fn words(identifier: &str) -> String {
+    let mut words = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in identifier.chars() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            if !words.is_empty() && !words.ends_with(' ') {
+                words.push(' ');
+            }
+        } else {
+            // a new word starts at `lineU` in `commentLineUp`, but not inside `HTML`
+            let starts_word = c.is_uppercase()
+                && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit());
+            if starts_word && !words.ends_with(' ') {
+                words.push(' ');
+            }
+            words.extend(c.to_lowercase());
+        }
+        previous = Some(c);
+    }
+
+    words.trim_end().to_owned()
+}
+
+/// Joins lines ending in a backslash with the next one, as shells and the
+/// config files of terminal programs do.
+fn logical_lines(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        match line.strip_suffix('\\') {
+            // an escaped backslash, e.g. `bind \\ split`, doesn't continue
+            Some(start) if !start.ends_with('\\') => {
+                current.push_str(start);
+                current.push(' ');
+            }
+            _ => {
+                current.push_str(line);
+                lines.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Cuts off a `#` comment, which starts a word outside of quotes. `#{`
+/// starts a tmux format rather than a comment.
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (None, '#')
+                if previous.is_whitespace()
+                    && chars.peek().is_none_or(|&(_, next)| next != '{') =>
+            {
+                return &line[..i];
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None | Some('"'), '\\') => {
+                chars.next();
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Splits the first word off a shell-like command line, returning it
+/// unquoted and unescaped together with the rest of the line. `None` if
+/// only whitespace is left.
+///
+/// Single quotes keep everything literal, double quotes and bare words
+/// allow backslash escapes like `\;` or `\"`.
+fn split_word(text: &str) -> Option<(String, &str)> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = text.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => return Some((word, &text[i..])),
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.push(c),
+            (_, '\\') => word.extend(chars.next().map(|(_, c)| c)),
+            (_, c) => word.push(c),
+        }
+    }
+    Some((word, ""))
+}
+
+/// Applies an X11 modifier name such as `Mod4` or `Control` to `modifiers`,
+/// `false` if `name` isn't one.
+fn apply_x_modifier(name: &str, modifiers: &mut Modifiers) -> bool {
+    match name.to_lowercase().as_str() {
+        "ctrl" | "control" => modifiers.ctrl = true,
+        "shift" => modifiers.shift = true,
+        "alt" | "meta" | "mod1" => modifiers.alt = true,
+        "super" | "hyper" | "win" | "logo" | "mod4" => modifiers.super_key = true,
+        _ => return false,
+    }
+    true
+}
+
+/// A stroke for an X keysym name such as `Return`, `Prior` or
+/// `bracketleft` pressed with `modifiers`.
+fn keysym_stroke(mut modifiers: Modifiers, name: &str) -> Option<KeyStroke> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        // with modifiers the letter case is cosmetic, like in [`KeyCombo`] parsing
+        let c = if modifiers.is_empty() {
+            c
+        } else {
+            c.to_ascii_lowercase()
+        };
+        return Some(KeyStroke::new(modifiers, Key::Char(c)));
+    }
+
+    let key = match name.to_lowercase().as_str() {
+        "return" | "kp_enter" => Key::Enter,
+        "escape" => Key::Esc,
+        "space" => Key::Space,
+        "tab" => Key::Tab,
+        "iso_left_tab" => {
+            modifiers.shift = true;
+            Key::Tab
+        }
+        "backspace" => Key::Backspace,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "prior" | "page_up" => Key::PageUp,
+        "next" | "page_down" => Key::PageDown,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        lower => match keysym_char(lower) {
+            Some(c) => Key::Char(c),
+            None => {
+                let number = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+                if !(1..=24).contains(&number) {
+                    return None;
+                }
+                Key::F(number)
+            }
+        },
+    };
+    Some(KeyStroke::new(modifiers, key))
+}
+
+/// Like [`apply_x_modifier`], also taking the macOS names `Cmd` and
+/// `Option` that terminal emulators accept.
+fn apply_modifier(name: &str, modifiers: &mut Modifiers) -> bool {
+    match name.to_lowercase().as_str() {
+        "cmd" | "command" => modifiers.super_key = true,
+        "opt" | "option" => modifiers.alt = true,
+        _ => return apply_x_modifier(name, modifiers),
+    }
+    true
+}
+
+/// A stroke for a key name as terminal emulators spell them: `Enter`,
+/// `PageUp`, `page_up`, `UpArrow` or `Key1`. Anything else is taken as an X
+/// keysym name.
+fn key_name_stroke(modifiers: Modifiers, name: &str) -> Option<KeyStroke> {
+    if name.chars().count() == 1 {
+        return keysym_stroke(modifiers, name);
+    }
+
+    let normalized = name.to_lowercase().replace('_', "");
+    let key = match normalized.as_str() {
+        "enter" => Key::Enter,
+        "esc" => Key::Esc,
+        "back" => Key::Backspace,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "arrowup" | "uparrow" => Key::Up,
+        "arrowdown" | "downarrow" => Key::Down,
+        "arrowleft" | "leftarrow" => Key::Left,
+        "arrowright" | "rightarrow" => Key::Right,
+        "equals" => Key::Char('='),
+        "lbracket" | "leftbracket" => Key::Char('['),
+        "rbracket" | "rightbracket" => Key::Char(']'),
+        "graveaccent" => Key::Char('`'),
+        _ => {
+            let digit = normalized
+                .strip_prefix("key")
+                .or_else(|| normalized.strip_prefix("digit"))
+                .filter(|digit| digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()));
+            match digit {
+                Some(digit) => Key::Char(digit.chars().next()?),
+                None => return keysym_stroke(modifiers, name),
+            }
+        }
+    };
+    Some(KeyStroke::new(modifiers, key))
+}
+
+/// The character of a punctuation keysym, e.g. `,` for `comma`.
+fn keysym_char(name: &str) -> Option<char> {
+    let c = match name {
+        "minus" => '-',
+        "equal" => '=',
+        "plus" => '+',
+        "bracketleft" => '[',
+        "bracketright" => ']',
+        "braceleft" => '{',
+        "braceright" => '}',
+        "parenleft" => '(',
+        "parenright" => ')',
+        "semicolon" => ';',
+        "colon" => ':',
+        "apostrophe" => '\'',
+        "quotedbl" => '"',
+        "grave" => '`',
+        "asciitilde" => '~',
+        "comma" => ',',
+        "period" => '.',
+        "less" => '<',
+        "greater" => '>',
+        "slash" => '/',
+        "question" => '?',
+        "backslash" => '\\',
+        "bar" => '|',
+        "exclam" => '!',
+        "at" => '@',
+        "numbersign" => '#',
+        "dollar" => '$',
+        "percent" => '%',
+        "asciicircum" => '^',
+        "ampersand" => '&',
+        "asterisk" => '*',
+        "underscore" => '_',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// The key an X keycode stands for on a US keyboard, for `bindcode`.
+fn keycode_key(code: u32) -> Option<Key> {
+    // the first keycode of each row of character keys
+    const ROWS: [(u32, &str); 4] = [
+        (10, "1234567890-="),
+        (24, "qwertyuiop[]"),
+        (38, "asdfghjkl;'`"),
+        (52, "zxcvbnm,./"),
+    ];
+
+    for (first, keys) in ROWS {
+        if let Some(c) = code
+            .checked_sub(first)
+            .and_then(|i| keys.chars().nth(i as usize))
+        {
+            return Some(Key::Char(c));
+        }
+    }
+
+    let key = match code {
+        9 => Key::Esc,
+        22 => Key::Backspace,
+        23 => Key::Tab,
+        36 => Key::Enter,
+        51 => Key::Char('\\'),
+        65 => Key::Space,
+        67..=76 => Key::F((code - 66) as u8),
+        95 => Key::F(11),
+        96 => Key::F(12),
+        110 => Key::Home,
+        111 => Key::Up,
+        112 => Key::PageUp,
+        113 => Key::Left,
+        114 => Key::Right,
+        115 => Key::End,
+        116 => Key::Down,
+        117 => Key::PageDown,
+        118 => Key::Insert,
+        119 => Key::Delete,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// `$name` variables, replaced longest name first so `$mod` doesn't eat
+/// the start of `$mode_system`.
+#[derive(Default)]
+struct Variables {
+    values: Vec<(String, String)>,
+}
+
+impl Variables {
+    fn set(&mut self, name: String, value: String) {
+        self.values.retain(|(other, _)| *other != name);
+        self.values.push((name, value));
+        self.values.sort_by_key(|(name, _)| Reverse(name.len()));
+    }
+
+    fn expand(&self, text: &str) -> String {
+        let mut text = text.to_owned();
+        for (name, value) in &self.values {
+            text = text.replace(name.as_str(), value);
+        }
+        text
+    }
+}
@@ -0,0 +1,522 @@
+//! `alacritty.toml`: `[[keyboard.bindings]]` tables with a `key`, `mods`,
+//! `mode` and one of `action`, `chars` or `command`. The inline form under
+//! `[keyboard]`, `bindings = [{ key = "N", mods = "Control", action = ... }]`,
+//! works as well.
+//!
+//! Bindings limited to a `mode` such as `Vi|~Search` get it as their context.
+//! Only as much TOML is understood as these files need.
+
+use crate::{
+    importers::{apply_modifier, key_name_stroke, words, Imported},
+    shortcuts::{KeyCombo, Modifiers, Shortcut},
+};
+
+/// A TOML value. Numbers, booleans and dates are kept as written.
+enum Value {
+    String(String),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+    Other(String),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Table(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) | Value::Other(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(text: &str, imported: &mut Imported) -> Result<(), String> {
+    let mut parser = Parser::new(text);
+    let mut section: Vec<String> = Vec::new();
+    let mut bindings: Vec<Value> = Vec::new();
+    // the `[[keyboard.bindings]]` table being read
+    let mut binding: Option<Vec<(String, Value)>> = None;
+
+    loop {
+        parser.skip_blank();
+        match parser.peek() {
+            None => break,
+            Some('[') => {
+                let (path, is_array) = parser.header()?;
+                bindings.extend(binding.take().map(Value::Table));
+                if is_array && path == ["keyboard", "bindings"] {
+                    binding = Some(Vec::new());
+                }
+                section = path;
+            }
+            Some(_) => {
+                let key = parser.key()?;
+                parser.expect('=')?;
+                let value = parser.value()?;
+                if let Some(binding) = &mut binding {
+                    binding.push((key.join("."), value));
+                } else if section
+                    .iter()
+                    .chain(&key)
+                    .eq(["keyboard", "bindings"].iter())
+                {
+                    if let Value::Array(items) = value {
+                        bindings.extend(items);
+                    }
+                }
+            }
+        }
+    }
+    bindings.extend(binding.map(Value::Table));
+
+    for binding in &bindings {
+        bind(binding, imported);
+    }
+    Ok(())
+}
+
+fn bind(binding: &Value, imported: &mut Imported) {
+    let Some(key) = binding.get("key").and_then(Value::as_str) else {
+        imported.skip("a binding without a key".into());
+        return;
+    };
+    let mods = binding
+        .get("mods")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let mode = binding
+        .get("mode")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let written = if mods.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{mods}+{key}")
+    };
+
+    let Some(key_combo) = parse_key(key, mods) else {
+        imported.skip(format!("`{written}`: unknown key"));
+        return;
+    };
+
+    let action = binding.get("action").and_then(Value::as_str);
+    // `None` and `ReceiveChar` hand the key to the shell, undoing a default
+    if matches!(action, Some("None" | "ReceiveChar")) {
+        imported.shortcuts.retain(|shortcut| {
+            shortcut.get_key_combo() != &key_combo
+                || shortcut.get_context().unwrap_or_default() != mode
+        });
+        return;
+    }
+
+    let Some(description) = describe(binding) else {
+        imported.skip(format!("`{written}`: no action, chars or command"));
+        return;
+    };
+    let mut shortcut = Shortcut::from(key_combo, description);
+    shortcut.set_context(mode);
+    imported.push(shortcut);
+}
+
+/// Parses a key such as `N`, `PageUp` or `Key1` with mods like
+/// `Control|Shift`.
+fn parse_key(key: &str, mods: &str) -> Option<KeyCombo> {
+    let mut modifiers = Modifiers::NONE;
+    for modifier in mods.split('|').map(str::trim) {
+        if !modifier.is_empty() && modifier != "None" && !apply_modifier(modifier, &mut modifiers) {
+            return None;
+        }
+    }
+
+    // letter keys are written uppercase, shift is part of mods
+    let key = if key.len() == 1 {
+        key.to_ascii_lowercase()
+    } else {
+        key.to_owned()
+    };
+    Some(KeyCombo::new(vec![key_name_stroke(modifiers, &key)?]))
+}
+
+/// "spawn new instance" for an action, "types ..." for chars and "run ..."
+/// for a command, either `"program"` or `{ program, args }`.
+fn describe(binding: &Value) -> Option<String> {
+    if let Some(action) = binding.get("action").and_then(Value::as_str) {
+        return Some(words(action));
+    }
+    if let Some(chars) = binding.get("chars").and_then(Value::as_str) {
+        return Some(format!("types {}", chars.escape_debug()));
+    }
+
+    let command = binding.get("command")?;
+    if let Some(program) = command.as_str() {
+        return Some(format!("run {program}"));
+    }
+    let mut description = format!("run {}", command.get("program")?.as_str()?);
+    if let Some(Value::Array(args)) = command.get("args") {
+        for arg in args.iter().filter_map(Value::as_str) {
+            description.push(' ');
+            description.push_str(arg);
+        }
+    }
+    Some(description)
+}
+
+/// Reads TOML one piece at a time, tracking the line for errors.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = 1 + self.chars[..self.position]
+            .iter()
+            .filter(|&&c| c == '\n')
+            .count();
+        format!("line {line}: {message}")
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_spaces();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(self.error(&format!("expected `{expected}`"))),
+        }
+    }
+
+    /// Skips spaces and tabs, but not line breaks.
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.position += 1;
+        }
+    }
+
+    /// Skips whitespace, line breaks and comments.
+    fn skip_blank(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                '#' => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.position += 1;
+                    }
+                }
+                c if c.is_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Reads `[a.b]` or `[[a.b]]`, returning the path and whether it's an
+    /// array of tables.
+    fn header(&mut self) -> Result<(Vec<String>, bool), String> {
+        self.expect('[')?;
+        let is_array = self.peek() == Some('[');
+        if is_array {
+            self.position += 1;
+        }
+        let path = self.key()?;
+        self.expect(']')?;
+        if is_array {
+            self.expect(']')?;
+        }
+        Ok((path, is_array))
+    }
+
+    /// Reads a dotted key: `mods`, `keyboard.bindings`, `"quoted".key`.
+    fn key(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_spaces();
+            let part = match self.peek() {
+                Some('"' | '\'') => self.string()?,
+                _ => {
+                    let start = self.position;
+                    while self
+                        .peek()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-')
+                    {
+                        self.position += 1;
+                    }
+                    if self.position == start {
+                        return Err(self.error("expected a key"));
+                    }
+                    self.chars[start..self.position].iter().collect()
+                }
+            };
+            path.push(part);
+
+            self.skip_spaces();
+            if self.peek() != Some('.') {
+                return Ok(path);
+            }
+            self.position += 1;
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_spaces();
+        match self.peek() {
+            Some('"' | '\'') => Ok(Value::String(self.string()?)),
+            Some('[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_blank();
+                    if self.peek() == Some(']') {
+                        self.position += 1;
+                        return Ok(Value::Array(items));
+                    }
+                    items.push(self.value()?);
+                    self.skip_blank();
+                    match self.next() {
+                        Some(',') => {}
+                        Some(']') => return Ok(Value::Array(items)),
+                        _ => return Err(self.error("expected `,` or `]` in an array")),
+                    }
+                }
+            }
+            Some('{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                loop {
+                    // inline tables are one line, but spreading them out is a common slip
+                    self.skip_blank();
+                    if self.peek() == Some('}') {
+                        self.position += 1;
+                        return Ok(Value::Table(fields));
+                    }
+                    let key = self.key()?;
+                    self.expect('=')?;
+                    fields.push((key.join("."), self.value()?));
+                    self.skip_blank();
+                    match self.next() {
+                        Some(',') => {}
+                        Some('}') => return Ok(Value::Table(fields)),
+                        _ => return Err(self.error("expected `,` or `}` in an inline table")),
+                    }
+                }
+            }
+            _ => {
+                let start = self.position;
+                while self
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | ']' | '}' | '\n' | '#'))
+                {
+                    self.position += 1;
+                }
+                let text: String = self.chars[start..self.position].iter().collect();
+                match text.trim() {
+                    "" => Err(self.error("expected a value")),
+                    text => Ok(Value::Other(text.to_owned())),
+                }
+            }
+        }
+    }
+
+    /// Reads a basic `"..."` or literal `'...'` string, or their multi-line
+    /// forms with three quotes.
+    fn string(&mut self) -> Result<String, String> {
+        let Some(quote) = self.next() else {
+            return Err(self.error("expected a string"));
+        };
+        let multi_line = self.chars[self.position..].starts_with(&[quote, quote]);
+        if multi_line {
+            self.position += 2;
+            // a line break right after the opening quotes isn't part of it
+            if self.peek() == Some('\n') {
+                self.position += 1;
+            }
+        }
+
+        let mut text = String::new();
+        loop {
+            let Some(c) = self.next() else {
+                return Err(self.error("unterminated string"));
+            };
+            match c {
+                c if c == quote => {
+                    if !multi_line {
+                        return Ok(text);
+                    }
+                    if self.chars[self.position..].starts_with(&[quote, quote]) {
+                        self.position += 2;
+                        return Ok(text);
+                    }
+                    text.push(c);
+                }
+                '\n' if !multi_line => return Err(self.error("unterminated string")),
+                '\\' if quote == '"' => text.push(self.escape()?),
+                c => text.push(c),
+            }
+        }
+    }
+
+    /// Reads the escape after a backslash in a basic string.
+    fn escape(&mut self) -> Result<char, String> {
+        let c = match self.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('b') => '\x08',
+            Some('f') => '\x0c',
+            Some('e') => '\x1b',
+            Some(digits @ ('u' | 'U')) => {
+                let len = if digits == 'u' { 4 } else { 8 };
+                let end = (self.position + len).min(self.chars.len());
+                let code: String = self.chars[self.position..end].iter().collect();
+                self.position = end;
+                u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            Some(c @ ('"' | '\\')) => c,
+            _ => return Err(self.error("invalid escape")),
+        };
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Result<Imported, String> {
+        let mut imported = Imported::default();
+        parse(text, &mut imported)?;
+        Ok(imported)
+    }
+
+    #[test]
+    fn reads_binding_tables() {
+        let imported = import(
+            r#"[window]
+            opacity = 0.9
+
+            [[keyboard.bindings]]
+            key = "N"
+            mods = "Control|Shift"
+            action = "SpawnNewInstance"
+
+            [[keyboard.bindings]]
+            key = "PageUp"
+            mods = "Shift"
+            mode = "~Alt"
+            action = "ScrollPageUp" # scrollback
+
+            [[keyboard.bindings]]
+            key = "K"
+            mods = "Command"
+            chars = "\u000c"
+
+            [[keyboard.bindings]]
+            key = "Return"
+            mods = "Super"
+            command = { program = "alacritty", args = ["-e", "htop"] }
+
+            [[keyboard.bindings]]
+            key = "F1"
+            command = 'firefox'"#,
+        )
+        .unwrap();
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+Shift+N = spawn new instance",
+                "Shift+PageUp = scroll page up [~Alt]",
+                "Super+K = types \\u{c}",
+                "Super+Enter = run alacritty -e htop",
+                "F1 = run firefox",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_inline_bindings() {
+        let imported = import(
+            r#"[keyboard]
+            bindings = [
+                { key = "V", mods = "Control|Shift", action = "Paste" },
+                { key = "I", mode = "Vi|~Search", action = "ToggleViMode" },
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            imported.lines(),
+            ["Ctrl+Shift+V = paste", "i = toggle vi mode [Vi|~Search]"]
+        );
+    }
+
+    #[test]
+    fn none_removes_a_binding() {
+        let imported = import(
+            r#"[keyboard]
+            bindings = [
+                { key = "C", mods = "Control|Shift", action = "Copy" },
+                { key = "Q", mods = "Control", action = "Quit" },
+                { key = "C", mods = "Control|Shift", action = "None" },
+                { key = "Q", mods = "Control", mode = "Vi", action = "ReceiveChar" },
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(imported.lines(), ["Ctrl+Q = quit"]);
+    }
+
+    #[test]
+    fn incomplete_bindings_are_skipped() {
+        let imported = import(
+            r#"[[keyboard.bindings]]
+            mods = "Control"
+            action = "Copy"
+
+            [[keyboard.bindings]]
+            key = "C"
+            mods = "Fancy"
+            action = "Copy"
+
+            [[keyboard.bindings]]
+            key = "C"
+            mods = "Alt""#,
+        )
+        .unwrap();
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 3);
+    }
+
+    #[test]
+    fn reports_the_line_of_errors() {
+        let error = |text| import(text).err().unwrap_or_default();
+        assert_eq!(
+            error("[keyboard]\nbindings = [\"a\" \"b\"]"),
+            "line 2: expected `,` or `]` in an array"
+        );
+        assert_eq!(error("key = \"N"), "line 1: unterminated string");
+        assert_eq!(error("[[keyboard.bindings]"), "line 1: expected `]`");
+        assert_eq!(error("key = \"\\q\""), "line 1: invalid escape");
+    }
+}
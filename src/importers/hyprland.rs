@@ -0,0 +1,179 @@
+//! `hyprland.conf`: `bind = MODS, key, dispatcher, params` lines, with
+//! flags after `bind` such as `binde` or `bindd`.
+//!
+//! `$name = value` variables are expanded. Bindings between `submap = name`
+//! and `submap = reset` get the submap as their context.
+
+use crate::{
+    importers::{apply_x_modifier, keycode_key, keysym_stroke, logical_lines, Imported, Variables},
+    shortcuts::{KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut variables = Variables::default();
+    let mut submap: Option<String> = None;
+
+    for line in logical_lines(text) {
+        let line = strip_comment(&line);
+        let Some((keyword, value)) = line.split_once('=') else {
+            continue;
+        };
+        let keyword = keyword.trim();
+        let value = value.trim();
+
+        if keyword.starts_with('$') {
+            variables.set(keyword.to_owned(), variables.expand(value));
+            continue;
+        }
+        if keyword == "submap" {
+            submap = (value != "reset").then(|| value.to_owned());
+            continue;
+        }
+
+        let Some(flags) = keyword.strip_prefix("bind") else {
+            continue;
+        };
+        // mouse bindings, like dragging windows around, aren't key presses
+        if flags.contains('m') {
+            continue;
+        }
+
+        let value = variables.expand(value);
+        let mut fields = value.splitn(if flags.contains('d') { 5 } else { 4 }, ',');
+        let (Some(mods), Some(key)) = (fields.next(), fields.next()) else {
+            imported.skip(format!("`{}`: no key", line.trim()));
+            continue;
+        };
+        let key = key.trim();
+        if key.starts_with("mouse") {
+            continue;
+        }
+
+        let note = if flags.contains('d') {
+            fields.next().map(str::trim)
+        } else {
+            None
+        };
+        let dispatcher = fields.next().unwrap_or_default().trim();
+        let params = fields.next().unwrap_or_default().trim();
+        if dispatcher.is_empty() {
+            imported.skip(format!("`{}`: no dispatcher", line.trim()));
+            continue;
+        }
+
+        let Some(stroke) = parse_key(mods, key) else {
+            imported.skip(format!("`{mods}, {key}` ({dispatcher}): unknown key"));
+            continue;
+        };
+
+        let description = match note.filter(|note| !note.is_empty()) {
+            Some(note) => note.to_owned(),
+            None => describe(dispatcher, params),
+        };
+        let mut shortcut = Shortcut::from(KeyCombo::new(vec![stroke]), description);
+        shortcut.set_context(submap.as_deref().unwrap_or_default());
+        imported.push(shortcut);
+    }
+}
+
+/// Cuts off a `#` comment, `##` is a literal `#`.
+fn strip_comment(line: &str) -> String {
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '#' && chars.next_if_eq(&'#').is_none() {
+            break;
+        }
+        text.push(c);
+    }
+    text
+}
+
+/// Parses modifiers such as `SUPER SHIFT` or `SUPER_SHIFT` and a key, which
+/// is a keysym or `code:38`.
+fn parse_key(mods: &str, key: &str) -> Option<KeyStroke> {
+    let mut modifiers = Modifiers::NONE;
+    for modifier in mods.split(|c: char| c == '_' || c == '+' || c.is_whitespace()) {
+        if !modifier.is_empty() && !apply_x_modifier(modifier, &mut modifiers) {
+            return None;
+        }
+    }
+
+    match key.strip_prefix("code:") {
+        Some(code) => Some(KeyStroke::new(modifiers, keycode_key(code.parse().ok()?)?)),
+        None => keysym_stroke(modifiers, key),
+    }
+}
+
+/// "run kitty" for `exec, kitty`, otherwise the dispatcher with its params:
+/// "movefocus l".
+fn describe(dispatcher: &str, params: &str) -> String {
+    match (dispatcher, params) {
+        ("exec" | "execr", program) => format!("run {program}"),
+        (dispatcher, "") => dispatcher.to_owned(),
+        (dispatcher, params) => format!("{dispatcher} {params}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_a_config() {
+        let imported = import(
+            "$mainMod = SUPER
+            $terminal = kitty
+            bind = $mainMod, Q, exec, $terminal # open a terminal
+            bind = $mainMod SHIFT, left, movewindow, l
+            binde = , XF86AudioRaiseVolume, exec, wpctl set-volume @ 5%+
+            bind = SUPER_ALT, code:10, workspace, 1
+            bindd = $mainMod, C, Close the window, killactive,
+            bindm = $mainMod, mouse:272, movewindow
+            bind = CTRL, Return, exec, notify-send ##1",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Super+Q = run kitty",
+                "Shift+Super+Left = movewindow l",
+                "Alt+Super+1 = workspace 1",
+                "Super+C = Close the window",
+                "Ctrl+Enter = run notify-send #1",
+            ]
+        );
+        assert_eq!(imported.skipped.len(), 1, "{:?}", imported.skipped);
+    }
+
+    #[test]
+    fn submaps_are_contexts() {
+        let imported = import(
+            "bind = ALT, R, submap, resize
+            submap = resize
+            binde = , right, resizeactive, 10 0
+            bind = , escape, submap, reset
+            submap = reset",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Alt+R = submap resize",
+                "Right = resizeactive 10 0 [resize]",
+                "Esc = submap reset [resize]",
+            ]
+        );
+    }
+
+    #[test]
+    fn broken_bindings_are_skipped() {
+        let imported = import("bind = SUPER\nbind = SUPER, Q\nbind = HYPERX, Q, killactive");
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 3);
+    }
+}
@@ -0,0 +1,215 @@
+//! i3 and sway config files: `bindsym $mod+Return exec foot` and
+//! `bindcode`, inside `mode "name" { ... }` blocks or at the top level.
+//!
+//! Variables set with `set $name value` are expanded. Bindings in a mode get
+//! the mode's name as their context, the default mode has none.
+
+use crate::{
+    importers::{
+        apply_x_modifier, keycode_key, keysym_stroke, logical_lines, split_word, Imported,
+        Variables,
+    },
+    shortcuts::{KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut variables = Variables::default();
+    let mut mode: Option<String> = None;
+
+    let lines = logical_lines(text);
+    let mut lines = lines.iter().map(|line| line.trim());
+    while let Some(line) = lines.next() {
+        // i3 only has whole-line comments, a `#` later on is part of a command
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((directive, rest)) = split_word(line) else {
+            continue;
+        };
+
+        match directive.as_str() {
+            "set" => {
+                if let Some((name, value)) = split_word(rest) {
+                    variables.set(name, variables.expand(value.trim()));
+                }
+            }
+            "mode" => {
+                let rest = variables.expand(rest);
+                let name = words_after_flags(&rest)
+                    .and_then(|(name, rest)| (rest.trim() == "{").then_some(name));
+                if name.is_some() {
+                    mode = name;
+                }
+            }
+            "}" => mode = None,
+            "bindsym" | "bindcode" => {
+                let by_code = directive == "bindcode";
+                let rest = variables.expand(rest);
+                let Some((key, command)) = words_after_flags(&rest) else {
+                    continue;
+                };
+
+                // sway also takes a block with a binding per line
+                if key == "{" {
+                    for line in lines.by_ref().take_while(|&line| line != "}") {
+                        let line = variables.expand(line);
+                        if let Some((key, command)) = split_word(&line) {
+                            bind(&key, command, by_code, mode.as_deref(), imported);
+                        }
+                    }
+                } else {
+                    bind(&key, command, by_code, mode.as_deref(), imported);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn bind(key: &str, command: &str, by_code: bool, mode: Option<&str>, imported: &mut Imported) {
+    let command = command.trim();
+    if command.is_empty() {
+        imported.skip(format!("`{key}`: no command"));
+        return;
+    }
+    let Some(stroke) = parse_key(key, by_code) else {
+        imported.skip(format!("`{key}` ({command}): unknown key"));
+        return;
+    };
+
+    let mut shortcut = Shortcut::from(KeyCombo::new(vec![stroke]), describe(command));
+    shortcut.set_context(mode.unwrap_or_default());
+    imported.push(shortcut);
+}
+
+/// Skips `--release`, `--to-code` and other flags, returning the next word
+/// and the rest.
+fn words_after_flags(text: &str) -> Option<(String, &str)> {
+    let mut rest = text;
+    loop {
+        let (word, after) = split_word(rest)?;
+        if !word.starts_with("--") {
+            return Some((word, after));
+        }
+        rest = after;
+    }
+}
+
+/// Parses `Mod4+Shift+q`, or `Mod4+38` for `bindcode`.
+fn parse_key(key: &str, by_code: bool) -> Option<KeyStroke> {
+    let mut parts: Vec<&str> = key.split('+').collect();
+    let name = parts.pop()?;
+
+    let mut modifiers = Modifiers::NONE;
+    for modifier in parts {
+        if !apply_x_modifier(modifier, &mut modifiers) {
+            return None;
+        }
+    }
+
+    if by_code {
+        let key = keycode_key(name.parse().ok()?)?;
+        return Some(KeyStroke::new(modifiers, key));
+    }
+    keysym_stroke(modifiers, name)
+}
+
+/// Shortens what the binding runs: `exec --no-startup-id firefox` becomes
+/// "run firefox", `workspace number 3` becomes "workspace 3".
+fn describe(command: &str) -> String {
+    let command = command.replace("workspace number ", "workspace ");
+
+    for exec in ["exec", "exec_always"] {
+        let Some(program) = command.strip_prefix(exec) else {
+            continue;
+        };
+        if !program.starts_with(' ') {
+            continue;
+        }
+        let program = program.trim_start();
+        let program = program.strip_prefix("--no-startup-id").unwrap_or(program);
+        return format!("run {}", program.trim());
+    }
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_a_config() {
+        let imported = import(
+            "set $mod Mod4
+            set $term foot
+            # comments are whole lines
+            bindsym $mod+Return exec $term
+            bindsym --release $mod+Shift+q kill
+            bindsym $mod+1 workspace number 1
+            bindsym $mod+d exec --no-startup-id rofi -show drun # not a comment
+            bindcode $mod+38 focus left",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Super+Enter = run foot",
+                "Shift+Super+Q = kill",
+                "Super+1 = workspace 1",
+                "Super+D = run rofi -show drun # not a comment",
+                "Super+A = focus left",
+            ]
+        );
+    }
+
+    #[test]
+    fn modes_are_contexts() {
+        let imported = import(
+            r#"set $resize "resize"
+            mode $resize {
+                bindsym h resize shrink width 10 px
+                bindsym Escape mode "default"
+            }
+            bindsym Mod1+r mode $resize"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "h = resize shrink width 10 px [resize]",
+                "Esc = mode \"default\" [resize]",
+                "Alt+R = mode \"resize\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn sway_binding_blocks() {
+        let imported = import(
+            "bindsym --to-code {
+                Mod4+Left focus left
+                Mod4+Right focus right
+            }
+            bindsym Mod4+f fullscreen",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Super+Left = focus left",
+                "Super+Right = focus right",
+                "Super+F = fullscreen",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_skipped() {
+        let imported = import("bindsym Mod7+x kill\nbindsym Mod4+y\nbindcode Mod4+999 kill");
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 3);
+    }
+}
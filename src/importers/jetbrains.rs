@@ -0,0 +1,589 @@
+//! JetBrains keymaps: `<action id="...">` elements holding
+//! `<keyboard-shortcut first-keystroke="ctrl K" second-keystroke="ctrl C"/>`.
+//!
+//! A keymap only lists the actions it changes compared to its `parent`. If
+//! the parent keymap's file is in the same directory it's read as well, and
+//! overridden action by action, the way the IDE does.
+
+use std::{fs, path::Path};
+
+use crate::{
+    importers::{words, Imported},
+    shortcuts::{Key, KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+/// Descriptions of common actions, as the IDE's menus name them. Other
+/// action IDs are split into words.
+const ACTION_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("$Copy", "copy"),
+    ("$Cut", "cut"),
+    ("$Delete", "delete"),
+    ("$Paste", "paste"),
+    ("$Redo", "redo"),
+    ("$SelectAll", "select all"),
+    ("$Undo", "undo"),
+    ("ActivateDebugToolWindow", "debug tool window"),
+    ("ActivateProjectToolWindow", "project tool window"),
+    ("ActivateRunToolWindow", "run tool window"),
+    ("ActivateTerminalToolWindow", "terminal tool window"),
+    (
+        "ActivateVersionControlToolWindow",
+        "version control tool window",
+    ),
+    ("Back", "navigate back"),
+    ("CallHierarchy", "call hierarchy"),
+    ("CheckinProject", "commit"),
+    ("ChooseDebugConfiguration", "debug configuration"),
+    ("ChooseRunConfiguration", "run configuration"),
+    ("CloseContent", "close tab"),
+    ("CodeCompletion", "basic completion"),
+    ("CollapseAllRegions", "collapse all"),
+    ("CollapseRegion", "collapse"),
+    ("CommentByBlockComment", "comment with block comment"),
+    ("CommentByLineComment", "comment with line comment"),
+    ("CompileDirty", "build project"),
+    ("Debug", "debug"),
+    ("EditorCloneCaretAbove", "clone caret above"),
+    ("EditorCloneCaretBelow", "clone caret below"),
+    ("EditorCompleteStatement", "complete current statement"),
+    ("EditorDeleteLine", "delete line"),
+    ("EditorDuplicate", "duplicate line or selection"),
+    ("EditorJoinLines", "join lines"),
+    ("EditorSelectWord", "extend selection"),
+    ("EditorStartNewLine", "start new line"),
+    ("EditorToggleColumnMode", "column selection mode"),
+    ("EditorUnSelectWord", "shrink selection"),
+    ("EvaluateExpression", "evaluate expression"),
+    ("ExpandAllRegions", "expand all"),
+    ("ExpandRegion", "expand"),
+    ("ExpressionTypeInfo", "type info"),
+    ("ExtractMethod", "extract method"),
+    ("FileStructurePopup", "file structure"),
+    ("Find", "find"),
+    ("FindInPath", "find in files"),
+    ("FindNext", "find next occurrence"),
+    ("FindPrevious", "find previous occurrence"),
+    ("FindUsages", "find usages"),
+    ("Forward", "navigate forward"),
+    ("Generate", "generate"),
+    ("GotoAction", "find action"),
+    ("GotoClass", "go to class"),
+    ("GotoDeclaration", "go to declaration"),
+    ("GotoFile", "go to file"),
+    ("GotoImplementation", "go to implementation"),
+    ("GotoLine", "go to line"),
+    ("GotoNextError", "next highlighted error"),
+    ("GotoPreviousError", "previous highlighted error"),
+    ("GotoSuperMethod", "go to super method"),
+    ("GotoSymbol", "go to symbol"),
+    ("GotoTypeDeclaration", "go to type declaration"),
+    ("HideAllWindows", "hide all tool windows"),
+    ("ImplementMethods", "implement methods"),
+    ("Inline", "inline"),
+    ("InsertLiveTemplate", "insert live template"),
+    ("IntroduceConstant", "introduce constant"),
+    ("IntroduceField", "introduce field"),
+    ("IntroduceParameter", "introduce parameter"),
+    ("IntroduceVariable", "introduce variable"),
+    ("MethodHierarchy", "method hierarchy"),
+    ("Move", "move"),
+    ("MoveLineDown", "move line down"),
+    ("MoveLineUp", "move line up"),
+    ("MoveStatementDown", "move statement down"),
+    ("MoveStatementUp", "move statement up"),
+    ("NextTab", "next tab"),
+    ("OptimizeImports", "optimize imports"),
+    ("OverrideMethods", "override methods"),
+    ("ParameterInfo", "parameter info"),
+    ("PasteMultiple", "paste from history"),
+    ("PreviousTab", "previous tab"),
+    ("QuickImplementations", "quick definition"),
+    ("QuickJavaDoc", "quick documentation"),
+    ("RecentFiles", "recent files"),
+    ("RecentLocations", "recent locations"),
+    ("Refactorings.QuickListPopupAction", "refactor this"),
+    ("ReformatCode", "reformat code"),
+    ("RenameElement", "rename"),
+    ("Replace", "replace"),
+    ("ReplaceInPath", "replace in files"),
+    ("Resume", "resume program"),
+    ("Run", "run"),
+    ("RunToCursor", "run to cursor"),
+    ("SaveAll", "save all"),
+    ("SearchEverywhere", "search everywhere"),
+    ("SelectAllOccurrences", "select all occurrences"),
+    ("SelectNextOccurrence", "add selection for next occurrence"),
+    ("ShowBookmarks", "show bookmarks"),
+    ("ShowErrorDescription", "error description"),
+    ("ShowIntentionActions", "show context actions"),
+    ("ShowProjectStructureSettings", "project structure"),
+    ("ShowSettings", "settings"),
+    ("ShowUsages", "show usages"),
+    ("SmartTypeCompletion", "type-matching completion"),
+    ("SplitVertically", "split vertically"),
+    ("StepInto", "step into"),
+    ("StepOut", "step out"),
+    ("StepOver", "step over"),
+    ("Stop", "stop"),
+    ("SurroundWith", "surround with"),
+    ("ToggleBookmark", "toggle bookmark"),
+    ("ToggleLineBreakpoint", "toggle line breakpoint"),
+    ("TypeHierarchy", "type hierarchy"),
+    ("UnselectPreviousOccurrence", "unselect occurrence"),
+    ("Vcs.Push", "push"),
+    ("Vcs.QuickListPopupAction", "VCS operations"),
+    ("Vcs.UpdateProject", "update project"),
+];
+
+struct Keymap {
+    name: Option<String>,
+    parent: Option<String>,
+    actions: Vec<Action>,
+}
+
+struct Action {
+    id: String,
+    /// First and optional second keystroke of each keyboard shortcut.
+    keystrokes: Vec<(String, Option<String>)>,
+}
+
+pub fn parse(text: &str, path: &Path, imported: &mut Imported) -> Result<(), String> {
+    let keymap = read_keymap(text)?;
+
+    let mut seen: Vec<String> = keymap.name.iter().cloned().collect();
+    let mut actions = Vec::new();
+    let mut parent = keymap.parent.clone();
+    let mut layers = vec![keymap.actions];
+
+    // collect the ancestors available next to the file, nearest first
+    while let Some(name) = parent.take() {
+        if seen.contains(&name) {
+            break;
+        }
+        match find_keymap(&name, path) {
+            Some(ancestor) => {
+                parent = ancestor.parent;
+                layers.push(ancestor.actions);
+            }
+            None => imported.skip(format!(
+                "the bindings of the parent keymap `{name}`: its file isn't next to {}",
+                path.display()
+            )),
+        }
+        seen.push(name);
+    }
+
+    // an action listed in a keymap replaces all its bindings from the parent
+    for layer in layers.into_iter().rev() {
+        for action in layer {
+            actions.retain(|other: &Action| other.id != action.id);
+            actions.push(action);
+        }
+    }
+
+    for action in actions {
+        for (first, second) in &action.keystrokes {
+            let strokes = std::iter::once(first)
+                .chain(second)
+                .map(|keystroke| parse_keystroke(keystroke).ok_or(keystroke))
+                .collect::<Result<Vec<_>, _>>();
+            match strokes {
+                Ok(strokes) => {
+                    imported.push(Shortcut::from(KeyCombo::new(strokes), describe(&action.id)))
+                }
+                Err(keystroke) => {
+                    imported.skip(format!("`{keystroke}` ({}): unknown key", action.id))
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Looks for the keymap called `name` among the `.xml` files in the
+/// directory of `path`.
+fn find_keymap(name: &str, path: &Path) -> Option<Keymap> {
+    let directory = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let entries = fs::read_dir(directory.unwrap_or(Path::new("."))).ok()?;
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|file| file.extension().is_some_and(|ext| ext == "xml"))
+        .filter_map(|file| read_keymap(&fs::read_to_string(file).ok()?).ok())
+        .find(|keymap| keymap.name.as_deref() == Some(name))
+}
+
+fn read_keymap(text: &str) -> Result<Keymap, String> {
+    let mut keymap: Option<Keymap> = None;
+    let mut action: Option<Action> = None;
+
+    for tag in tags(text)? {
+        match (tag.name.as_str(), tag.closing) {
+            ("keymap", false) => {
+                keymap = Some(Keymap {
+                    name: tag.attribute("name").map(str::to_owned),
+                    // the built-in root keymaps have an empty parent
+                    parent: tag
+                        .attribute("parent")
+                        .filter(|parent| !parent.is_empty())
+                        .map(str::to_owned),
+                    actions: Vec::new(),
+                })
+            }
+            ("action", false) => {
+                let id = tag.attribute("id").unwrap_or_default().to_owned();
+                let new = Action {
+                    id,
+                    keystrokes: Vec::new(),
+                };
+                if tag.self_closing {
+                    // an empty action removes the bindings it had in the parent
+                    if let Some(keymap) = &mut keymap {
+                        keymap.actions.push(new);
+                    }
+                } else {
+                    action = Some(new);
+                }
+            }
+            ("action", true) => {
+                if let (Some(keymap), Some(action)) = (&mut keymap, action.take()) {
+                    keymap.actions.push(action);
+                }
+            }
+            ("keyboard-shortcut", false) => {
+                if let (Some(action), Some(first)) = (&mut action, tag.attribute("first-keystroke"))
+                {
+                    let second = tag.attribute("second-keystroke").map(str::to_owned);
+                    action.keystrokes.push((first.to_owned(), second));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    keymap.ok_or_else(|| "not a keymap file: there's no <keymap> element".into())
+}
+
+fn describe(action_id: &str) -> String {
+    match ACTION_DESCRIPTIONS.iter().find(|(id, _)| *id == action_id) {
+        Some((_, description)) => (*description).to_owned(),
+        None => words(&action_id.trim_start_matches('$').replace('.', " ")),
+    }
+}
+
+/// Parses a keystroke in Java's notation: `ctrl shift D`, `alt F7`,
+/// `meta BACK_SPACE`.
+fn parse_keystroke(text: &str) -> Option<KeyStroke> {
+    let mut modifiers = Modifiers::NONE;
+    let mut key = None;
+
+    for word in text.split_whitespace() {
+        match word {
+            "ctrl" | "control" => modifiers.ctrl = true,
+            "shift" => modifiers.shift = true,
+            "alt" => modifiers.alt = true,
+            // Command on macOS
+            "meta" => modifiers.super_key = true,
+            "pressed" | "released" | "typed" => {}
+            name if key.is_none() => key = Some(parse_key_name(name)?),
+            _ => return None,
+        }
+    }
+
+    Some(KeyStroke::new(modifiers, key?))
+}
+
+/// A `java.awt.event.KeyEvent` key name without its `VK_` prefix.
+fn parse_key_name(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c.to_ascii_lowercase()));
+    }
+
+    let key = match name {
+        "ENTER" => Key::Enter,
+        "ESCAPE" => Key::Esc,
+        "SPACE" => Key::Space,
+        "TAB" => Key::Tab,
+        "BACK_SPACE" => Key::Backspace,
+        "DELETE" => Key::Delete,
+        "INSERT" => Key::Insert,
+        "HOME" => Key::Home,
+        "END" => Key::End,
+        "PAGE_UP" => Key::PageUp,
+        "PAGE_DOWN" => Key::PageDown,
+        "UP" | "KP_UP" => Key::Up,
+        "DOWN" | "KP_DOWN" => Key::Down,
+        "LEFT" | "KP_LEFT" => Key::Left,
+        "RIGHT" | "KP_RIGHT" => Key::Right,
+        "MINUS" | "SUBTRACT" => Key::Char('-'),
+        "EQUALS" => Key::Char('='),
+        "PLUS" | "ADD" => Key::Char('+'),
+        "MULTIPLY" => Key::Char('*'),
+        "DIVIDE" | "SLASH" => Key::Char('/'),
+        "DECIMAL" | "PERIOD" => Key::Char('.'),
+        "SEPARATOR" | "COMMA" => Key::Char(','),
+        "OPEN_BRACKET" => Key::Char('['),
+        "CLOSE_BRACKET" => Key::Char(']'),
+        "SEMICOLON" => Key::Char(';'),
+        "QUOTE" => Key::Char('\''),
+        "BACK_QUOTE" => Key::Char('`'),
+        "BACK_SLASH" => Key::Char('\\'),
+        _ => {
+            if let Some(digit) = name.strip_prefix("NUMPAD") {
+                let digit = digit.parse::<u8>().ok().filter(|&digit| digit < 10)?;
+                return Some(Key::Char(char::from(b'0' + digit)));
+            }
+            let number = name.strip_prefix('F')?.parse::<u8>().ok()?;
+            if !(1..=24).contains(&number) {
+                return None;
+            }
+            Key::F(number)
+        }
+    };
+    Some(key)
+}
+
+/// An XML tag, with its attributes.
+struct Tag {
+    name: String,
+    attributes: Vec<(String, String)>,
+    /// `</name>`
+    closing: bool,
+    /// `<name/>`, which also counts as opened.
+    self_closing: bool,
+}
+
+impl Tag {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The tags of an XML document in order, skipping text, comments and
+/// declarations. Enough for keymaps, which are nothing but tags.
+fn tags(text: &str) -> Result<Vec<Tag>, String> {
+    let mut tags = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let end_marker = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else if rest.starts_with("<!") {
+            ">"
+        } else {
+            let end = tag_end(rest).ok_or("not a keymap file: a tag is never closed")?;
+            tags.push(parse_tag(&rest[1..end]));
+            rest = &rest[end + 1..];
+            continue;
+        };
+        let end = rest
+            .find(end_marker)
+            .ok_or("not a keymap file: a comment is never closed")?;
+        rest = &rest[end + end_marker.len()..];
+    }
+
+    Ok(tags)
+}
+
+/// The index of the `>` closing the tag at the start of `text`, skipping
+/// any inside attribute values.
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses the inside of `<...>`.
+fn parse_tag(inner: &str) -> Tag {
+    let closing = inner.starts_with('/');
+    let self_closing = inner.ends_with('/');
+    let inner = inner.trim_start_matches('/').trim_end_matches('/');
+
+    let (name, mut rest) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+    let mut attributes = Vec::new();
+
+    while let Some((attribute, after)) = rest.split_once('=') {
+        let after = after.trim_start();
+        let Some(quote) = after.chars().next().filter(|&c| c == '"' || c == '\'') else {
+            break;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            break;
+        };
+        attributes.push((attribute.trim().to_owned(), unescape(&after[1..end + 1])));
+        rest = &after[end + 2..];
+    }
+
+    Tag {
+        name: name.to_owned(),
+        attributes,
+        closing,
+        self_closing,
+    }
+}
+
+/// Replaces XML entities such as `&amp;` and `&#39;`.
+fn unescape(value: &str) -> String {
+    let mut text = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                text.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str, path: &Path) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, path, &mut imported).unwrap();
+        imported
+    }
+
+    #[test]
+    fn reads_a_keymap() {
+        let imported = import(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <keymap version="1" name="Mine" parent="">
+              <!-- chords have a second keystroke -->
+              <action id="CommentByLineComment">
+                <keyboard-shortcut first-keystroke="ctrl K" second-keystroke="ctrl C" />
+                <keyboard-shortcut first-keystroke="ctrl SLASH" />
+              </action>
+              <action id="Vcs.ShowTabbedFileHistory">
+                <keyboard-shortcut first-keystroke="meta alt H"/>
+                <mouse-shortcut keystroke="button4" />
+              </action>
+              <action id="$Undo"><keyboard-shortcut first-keystroke="ctrl Z"/></action>
+              <action id="Broken"><keyboard-shortcut first-keystroke="ctrl WHAT"/></action>
+            </keymap>"#,
+            Path::new("Mine.xml"),
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+K Ctrl+C = comment with line comment",
+                "Ctrl+/ = comment with line comment",
+                "Alt+Super+H = vcs show tabbed file history",
+                "Ctrl+Z = undo",
+            ]
+        );
+        assert_eq!(imported.skipped, ["`ctrl WHAT` (Broken): unknown key"]);
+    }
+
+    #[test]
+    fn keystrokes() {
+        let stroke = |text| parse_keystroke(text).map(|stroke| stroke.to_string());
+        assert_eq!(stroke("ctrl shift D").unwrap(), "Ctrl+Shift+D");
+        assert_eq!(stroke("alt F7").unwrap(), "Alt+F7");
+        assert_eq!(stroke("meta BACK_SPACE").unwrap(), "Super+Backspace");
+        assert_eq!(stroke("shift pressed NUMPAD3").unwrap(), "Shift+3");
+        assert!(stroke("ctrl").is_none());
+        assert!(stroke("ctrl A B").is_none());
+        assert!(stroke("F25").is_none());
+    }
+
+    #[test]
+    fn entities_are_unescaped() {
+        assert_eq!(unescape("a &amp; b &lt;&#62;&#x41;"), "a & b <>A");
+        assert_eq!(unescape("R&D &unknown;"), "R&D &unknown;");
+    }
+
+    #[test]
+    fn children_override_their_parent_keymap() {
+        let dir = std::env::temp_dir().join(format!("shotty-jetbrains-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("base.xml"),
+            r#"<keymap name="Base">
+              <action id="GotoFile"><keyboard-shortcut first-keystroke="ctrl shift N"/></action>
+              <action id="Find"><keyboard-shortcut first-keystroke="ctrl F"/></action>
+              <action id="Run"><keyboard-shortcut first-keystroke="shift F10"/></action>
+            </keymap>"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("middle.xml"),
+            r#"<keymap name="Middle" parent="Base">
+              <action id="Find"><keyboard-shortcut first-keystroke="alt F"/></action>
+            </keymap>"#,
+        )
+        .unwrap();
+
+        let path = dir.join("child.xml");
+        let imported = import(
+            r#"<keymap name="Child" parent="Middle">
+              <action id="Run"/>
+              <action id="GotoFile"><keyboard-shortcut first-keystroke="ctrl P"/></action>
+            </keymap>"#,
+            &path,
+        );
+        let mut lines = imported.lines();
+        lines.sort();
+        assert_eq!(lines, ["Alt+F = find", "Ctrl+P = go to file"]);
+
+        let imported = import(r#"<keymap name="Orphan" parent="Missing"/>"#, &path);
+        assert_eq!(imported.skipped.len(), 1);
+        assert!(imported.skipped[0].contains("`Missing`"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut imported = Imported::default();
+        let path = Path::new("x.xml");
+        assert!(parse("<settings/>", path, &mut imported).is_err());
+        assert!(parse("<keymap name='a'", path, &mut imported).is_err());
+        assert!(parse("<!-- <keymap>", path, &mut imported).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! `kitty.conf`: `map ctrl+shift+t new_tab`, with `kitty_mod` standing for
+//! the modifiers it's set to (`ctrl+shift` unless changed) and `>` between
+//! the keys of a sequence: `map ctrl+a>c new_tab`.
+//!
+//! Mappings made with `--mode NAME` get the mode as their context, those
+//! limited with `--when-focus-on` their condition.
+
+use crate::{
+    importers::{apply_modifier, key_name_stroke, words, Imported},
+    shortcuts::{KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut kitty_mod = Modifiers {
+        ctrl: true,
+        shift: true,
+        ..Modifiers::NONE
+    };
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((directive, rest)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let rest = rest.trim();
+
+        match directive {
+            "kitty_mod" => match parse_modifiers(rest) {
+                Some(modifiers) => kitty_mod = modifiers,
+                None => imported.skip(format!("`kitty_mod {rest}`: unknown modifier")),
+            },
+            "clear_all_shortcuts" if rest == "yes" => imported.shortcuts.clear(),
+            "map" => map(rest, kitty_mod, imported),
+            _ => {}
+        }
+    }
+}
+
+fn map(text: &str, kitty_mod: Modifiers, imported: &mut Imported) {
+    let mut rest = text;
+    let mut mode = None;
+    let mut new_mode = None;
+    let mut condition = None;
+
+    while let Some(option) = rest.strip_prefix("--") {
+        let (option, after) = split_first_word(option);
+        let (name, value, after) = match option.split_once('=') {
+            Some((name, value)) => (name, value, after),
+            None => {
+                let (value, after) = split_first_word(after);
+                (option, value, after)
+            }
+        };
+        match name {
+            "mode" => mode = Some(value),
+            "new-mode" => new_mode = Some(value),
+            "when-focus-on" => condition = Some(value),
+            _ => {}
+        }
+        rest = after;
+    }
+
+    let (keys, action) = split_first_word(rest);
+    let description = match new_mode {
+        Some(new_mode) => format!("enter {new_mode} mode"),
+        None => describe(action),
+    };
+    if description.is_empty() {
+        imported.skip(format!("`{keys}`: no action"));
+        return;
+    }
+    let Some(key_combo) = parse_keys(keys, kitty_mod) else {
+        imported.skip(format!("`{keys}` ({description}): unknown key"));
+        return;
+    };
+
+    let context = mode.or(condition).unwrap_or_default();
+    // mapping a key to nothing takes away its default action
+    if matches!(action, "no_op" | "discard_event") {
+        imported.shortcuts.retain(|shortcut| {
+            shortcut.get_key_combo() != &key_combo
+                || shortcut.get_context().unwrap_or_default() != context
+        });
+        return;
+    }
+
+    let mut shortcut = Shortcut::from(key_combo, description);
+    shortcut.set_context(context);
+    imported.push(shortcut);
+}
+
+/// Splits off the first word, returning it and the rest without leading
+/// whitespace.
+fn split_first_word(text: &str) -> (&str, &str) {
+    let (word, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    (word, rest.trim_start())
+}
+
+/// `ctrl+shift`, as kitty_mod is set.
+fn parse_modifiers(text: &str) -> Option<Modifiers> {
+    let mut modifiers = Modifiers::NONE;
+    for name in text.split('+') {
+        if !apply_modifier(name, &mut modifiers) {
+            return None;
+        }
+    }
+    Some(modifiers)
+}
+
+/// Parses `kitty_mod+t` or the sequence `ctrl+a>shift+c`.
+fn parse_keys(keys: &str, kitty_mod: Modifiers) -> Option<KeyCombo> {
+    let strokes = keys
+        .split('>')
+        .map(|stroke| parse_stroke(stroke, kitty_mod))
+        .collect::<Option<Vec<_>>>()?;
+    Some(KeyCombo::new(strokes))
+}
+
+fn parse_stroke(text: &str, kitty_mod: Modifiers) -> Option<KeyStroke> {
+    let mut parts: Vec<&str> = text.split('+').collect();
+    let name = parts.pop().filter(|name| !name.is_empty())?;
+
+    let mut modifiers = Modifiers::NONE;
+    for modifier in parts {
+        if modifier == "kitty_mod" {
+            modifiers.ctrl |= kitty_mod.ctrl;
+            modifiers.shift |= kitty_mod.shift;
+            modifiers.alt |= kitty_mod.alt;
+            modifiers.super_key |= kitty_mod.super_key;
+        } else if !apply_modifier(modifier, &mut modifiers) {
+            return None;
+        }
+    }
+    key_name_stroke(modifiers, name)
+}
+
+/// The action's name in words, followed by its arguments as written:
+/// "new tab", "launch --cwd=current".
+fn describe(action: &str) -> String {
+    let (name, arguments) = split_first_word(action.trim());
+    let name = words(name);
+    if arguments.is_empty() {
+        name
+    } else {
+        format!("{name} {arguments}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_maps() {
+        let imported = import(
+            "# tabs
+            map kitty_mod+t new_tab
+            map ctrl+a>c launch --type=tab --cwd=current
+            map cmd+enter new_os_window
+            map --when-focus-on title:vim ctrl+f send_text all \\x06
+            font_size 12.0",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+Shift+T = new tab",
+                "Ctrl+A c = launch --type=tab --cwd=current",
+                "Super+Enter = new os window",
+                "Ctrl+F = send text all \\x06 [title:vim]",
+            ]
+        );
+    }
+
+    #[test]
+    fn kitty_mod_can_be_changed() {
+        let imported = import(
+            "kitty_mod ctrl+alt
+            map kitty_mod+w close_window
+            kitty_mod hyperspace",
+        );
+        assert_eq!(imported.lines(), ["Ctrl+Alt+W = close window"]);
+        assert_eq!(imported.skipped.len(), 1);
+    }
+
+    #[test]
+    fn modes_become_contexts() {
+        let imported = import(
+            "map --new-mode resize --on-unknown end kitty_mod+r
+            map --mode resize h resize_window narrower
+            map --mode=resize esc pop_keyboard_mode",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+Shift+R = enter resize mode",
+                "h = resize window narrower [resize]",
+                "Esc = pop keyboard mode [resize]",
+            ]
+        );
+    }
+
+    #[test]
+    fn no_op_and_clearing_remove_maps() {
+        let imported = import(
+            "map ctrl+shift+q close_tab
+            map ctrl+shift+n new_os_window
+            map ctrl+shift+q no_op",
+        );
+        assert_eq!(imported.lines(), ["Ctrl+Shift+N = new os window"]);
+
+        let imported = import(
+            "map ctrl+shift+q close_tab
+            clear_all_shortcuts yes
+            map ctrl+shift+n new_os_window",
+        );
+        assert_eq!(imported.lines(), ["Ctrl+Shift+N = new os window"]);
+    }
+
+    #[test]
+    fn unknown_keys_are_skipped() {
+        let imported = import(
+            "map ctrl+nokey new_tab
+            map ctrl+t
+            map ctrl+> new_tab",
+        );
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 3);
+    }
+}
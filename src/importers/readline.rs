@@ -0,0 +1,708 @@
+//! Readline key bindings: `~/.inputrc` and the output of bash's `bind -p`
+//! (`"\C-a": beginning-of-line`) and `bind -P`
+//! (`beginning-of-line can be found on "\C-a", "\eOH".`).
+//!
+//! Every binding gets its keymap as the context: emacs, vi-insert or
+//! vi-command. Bindings in `$if mode=emacs` and `$if mode=vi` blocks go into
+//! that mode's keymap, `$else` into the other one. Other `$if` conditions
+//! aren't evaluated, all their bindings are read.
+//!
+//! The decoding of key sequences is shared with the zsh importer.
+
+use crate::{
+    importers::{words, Imported},
+    shortcuts::{Key, KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+const ESC: char = '\x1b';
+
+/// Descriptions of functions and zsh widgets whose names don't say much on
+/// their own. Others are split into words.
+const FUNCTION_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("abort", "abort"),
+    ("accept-line", "run the command"),
+    ("backward-delete-char", "delete previous character"),
+    ("backward-kill-line", "cut to start of line"),
+    ("backward-kill-word", "cut to start of word"),
+    ("beginning-of-history", "first command in history"),
+    ("call-last-kbd-macro", "replay macro"),
+    ("character-search", "jump to character"),
+    ("character-search-backward", "jump back to character"),
+    ("complete", "complete"),
+    (
+        "delete-char-or-list",
+        "delete character or list completions",
+    ),
+    ("down-line-or-history", "next line or command in history"),
+    ("edit-and-execute-command", "edit the command in $EDITOR"),
+    ("edit-command-line", "edit the command in $EDITOR"),
+    ("emacs-editing-mode", "switch to emacs mode"),
+    ("end-kbd-macro", "stop recording macro"),
+    ("end-of-history", "back to the line being edited"),
+    ("exchange-point-and-mark", "swap cursor and mark"),
+    ("expand-or-complete", "complete"),
+    ("forward-search-history", "search history forwards"),
+    ("history-expand-line", "expand history references"),
+    (
+        "history-incremental-search-backward",
+        "search history backwards",
+    ),
+    (
+        "history-incremental-search-forward",
+        "search history forwards",
+    ),
+    (
+        "history-search-backward",
+        "previous command starting like this one",
+    ),
+    (
+        "history-search-forward",
+        "next command starting like this one",
+    ),
+    ("insert-comment", "comment out the line and run it"),
+    (
+        "insert-last-word",
+        "insert last argument of previous command",
+    ),
+    ("kill-line", "cut to end of line"),
+    ("kill-whole-line", "cut whole line"),
+    ("kill-word", "cut to end of word"),
+    ("list-choices", "list completions"),
+    ("magic-space", "expand history and insert a space"),
+    ("menu-complete", "cycle through completions"),
+    (
+        "operate-and-get-next",
+        "run the command and fetch the next one",
+    ),
+    ("possible-completions", "list completions"),
+    ("pound-insert", "comment out the line and run it"),
+    ("push-line", "stash the line and clear it"),
+    ("push-line-or-edit", "stash the line and clear it"),
+    ("quoted-insert", "insert next key literally"),
+    ("re-read-init-file", "reload inputrc"),
+    ("reverse-search-history", "search history backwards"),
+    ("revert-line", "undo all changes to the line"),
+    ("run-help", "show help for the command"),
+    ("send-break", "abort"),
+    ("set-mark-command", "set mark"),
+    ("shell-expand-line", "expand the line"),
+    ("start-kbd-macro", "start recording macro"),
+    ("transpose-chars", "swap characters"),
+    ("transpose-words", "swap words"),
+    ("universal-argument", "numeric argument"),
+    ("unix-line-discard", "cut to start of line"),
+    ("unix-word-rubout", "cut previous whitespace-separated word"),
+    ("up-line-or-history", "previous line or command in history"),
+    ("vi-cmd-mode", "switch to vi command mode"),
+    ("vi-editing-mode", "switch to vi mode"),
+    ("vi-movement-mode", "switch to vi command mode"),
+    ("which-command", "show what the command runs"),
+    ("yank", "paste last cut text"),
+    ("yank-last-arg", "insert last argument of previous command"),
+    ("yank-pop", "cycle through earlier cut text"),
+];
+
+/// What a key sequence is bound to.
+pub enum Action<'a> {
+    Function(&'a str),
+    /// Text typed as if it came from the keyboard, as written in the file.
+    Macro(&'a str),
+}
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut keymap = Keymap::EMACS;
+    let mut conditions: Vec<Condition> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.ends_with("is not bound to any keys") {
+            continue;
+        }
+
+        if let Some(directive) = line.strip_prefix('$') {
+            let (name, test) = directive
+                .split_once(char::is_whitespace)
+                .unwrap_or((directive, ""));
+            match name {
+                "if" => {
+                    let mode = match test.trim() {
+                        "mode=emacs" => Some(Keymap::EMACS),
+                        "mode=vi" => Some(Keymap::VI_INSERT),
+                        _ => None,
+                    };
+                    conditions.push(Condition {
+                        outer: keymap,
+                        mode,
+                    });
+                    keymap = mode.unwrap_or(keymap);
+                }
+                "else" => {
+                    if let Some(mode) = conditions.last().and_then(|condition| condition.mode) {
+                        keymap = mode.other_mode();
+                    }
+                }
+                "endif" => {
+                    if let Some(condition) = conditions.pop().filter(|c| c.mode.is_some()) {
+                        keymap = condition.outer;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(setting) = line.strip_prefix("set ") {
+            let mut words = setting.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("editing-mode"), Some("vi")) => keymap = Keymap::VI_INSERT,
+                (Some("editing-mode"), Some("emacs")) => keymap = Keymap::EMACS,
+                (Some("keymap"), Some(name)) => match Keymap::named(name) {
+                    Some(named) => keymap = named,
+                    None => imported.skip(format!("the keymap `{name}`: unknown keymap")),
+                },
+                _ => {}
+            }
+            continue;
+        }
+
+        // `bind -P` lists the sequences of each function
+        if let Some((function, sequences)) = line.split_once(" can be found on ") {
+            for sequence in quoted_strings(sequences) {
+                let input = unescape(sequence, false).map(|input| keymap.prefixed(input));
+                let action = Action::Function(function.trim());
+                bind(sequence, input, action, keymap.context, imported);
+            }
+            continue;
+        }
+
+        let Some((sequence, value)) = split_binding(line) else {
+            imported.skip(format!("`{line}`: not a binding"));
+            continue;
+        };
+        let input = match sequence.strip_prefix('"') {
+            Some(quoted) => unescape(quoted.strip_suffix('"').unwrap_or(quoted), false),
+            None => key_name(sequence),
+        };
+        let input = input.map(|input| keymap.prefixed(input));
+
+        let value = value.trim();
+        let action = match value.strip_prefix(['"', '\'']) {
+            Some(text) => Action::Macro(text.strip_suffix(['"', '\'']).unwrap_or(text)),
+            None => Action::Function(value.split_whitespace().next().unwrap_or_default()),
+        };
+        bind(sequence, input, action, keymap.context, imported);
+    }
+}
+
+/// An open `$if` block.
+struct Condition {
+    /// The keymap from before the block, bound in again after it.
+    outer: Keymap,
+    /// The keymap of the mode tested by `$if mode=...`.
+    mode: Option<Keymap>,
+}
+
+/// A keymap to bind in, with the keys its bindings are prefixed with:
+/// `emacs-ctlx` is the part of emacs after `Ctrl+X`.
+#[derive(Clone, Copy)]
+struct Keymap {
+    context: &'static str,
+    prefix: Option<char>,
+}
+
+impl Keymap {
+    const EMACS: Keymap = Keymap::new("emacs", None);
+    const VI_INSERT: Keymap = Keymap::new("vi-insert", None);
+
+    const fn new(context: &'static str, prefix: Option<char>) -> Self {
+        Self { context, prefix }
+    }
+
+    fn named(name: &str) -> Option<Self> {
+        let keymap = match name {
+            "emacs" | "emacs-standard" => Keymap::EMACS,
+            "emacs-meta" => Keymap::new("emacs", Some(ESC)),
+            "emacs-ctlx" => Keymap::new("emacs", Some('\x18')),
+            "vi" | "vi-move" | "vi-command" => Keymap::new("vi-command", None),
+            "vi-insert" => Keymap::VI_INSERT,
+            _ => return None,
+        };
+        Some(keymap)
+    }
+
+    /// The keymap of the editing mode this one isn't part of, for `$else`.
+    fn other_mode(self) -> Self {
+        if self.context == Keymap::EMACS.context {
+            Keymap::VI_INSERT
+        } else {
+            Keymap::EMACS
+        }
+    }
+
+    fn prefixed(self, input: Vec<char>) -> Vec<char> {
+        self.prefix.into_iter().chain(input).collect()
+    }
+}
+
+/// Adds the shortcut for `sequence`, which decoded to `input`. Shared with
+/// the zsh importer.
+pub fn bind(
+    sequence: &str,
+    input: Option<Vec<char>>,
+    action: Action,
+    context: &str,
+    imported: &mut Imported,
+) {
+    let description = match action {
+        Action::Function(function) => match describe(function) {
+            Some(description) => description,
+            None => return,
+        },
+        Action::Macro(text) => format!("types {text}"),
+    };
+
+    match input.as_deref().and_then(decode_input) {
+        Some(key_combo) => {
+            let mut shortcut = Shortcut::from(key_combo, description);
+            shortcut.set_context(context);
+            imported.push(shortcut);
+        }
+        None => imported.skip(format!("`{sequence}` ({description}): unknown key")),
+    }
+}
+
+/// A readable description of a readline function or zsh widget, `None` for
+/// the ones that type the key itself.
+fn describe(function: &str) -> Option<String> {
+    if function.is_empty()
+        || matches!(
+            function,
+            "self-insert" | "do-lowercase-version" | "undefined-key"
+        )
+    {
+        return None;
+    }
+    let description = match FUNCTION_DESCRIPTIONS
+        .iter()
+        .find(|(name, _)| *name == function)
+    {
+        Some((_, description)) => (*description).to_owned(),
+        None => words(function),
+    };
+    Some(description)
+}
+
+/// Splits `"\C-a": beginning-of-line` or `Control-a: ...` at the colon
+/// after the key sequence.
+fn split_binding(line: &str) -> Option<(&str, &str)> {
+    if !line.starts_with('"') {
+        return line.split_once(':');
+    }
+
+    let mut escaped = false;
+    for (i, c) in line.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                let value = line[i + 1..].trim_start().strip_prefix(':')?;
+                return Some((&line[..=i], value));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The contents of the `"..."` strings in `text`, still escaped.
+fn quoted_strings(text: &str) -> Vec<&str> {
+    let mut strings = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        match (start, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(from), '"') => {
+                strings.push(&text[from..i]);
+                start = None;
+            }
+            (None, '"') => start = Some(i + 1),
+            _ => {}
+        }
+    }
+    strings
+}
+
+/// Turns the escapes of a key sequence into the characters a terminal
+/// sends: `\C-x` and `\M-x`, `\e`, `\t`, octal and hex codes. With `caret`,
+/// zsh's `^X` is a control character too.
+pub fn unescape(sequence: &str, caret: bool) -> Option<Vec<char>> {
+    let mut input = Vec::new();
+    let mut chars = sequence.chars().peekable();
+    let (mut ctrl, mut meta) = (false, false);
+
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '\\' => match chars.next()? {
+                prefix @ ('C' | 'M') if chars.next_if_eq(&'-').is_some() => {
+                    match prefix {
+                        'C' => ctrl = true,
+                        _ => meta = true,
+                    }
+                    continue;
+                }
+                'e' | 'E' => ESC,
+                'a' => '\x07',
+                'b' => '\x08',
+                'd' => '\x7f',
+                'f' => '\x0c',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'v' => '\x0b',
+                'x' => {
+                    let mut code = String::new();
+                    while code.len() < 2 {
+                        let Some(digit) = chars.next_if(char::is_ascii_hexdigit) else {
+                            break;
+                        };
+                        code.push(digit);
+                    }
+                    char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+                }
+                first @ '0'..='7' => {
+                    let mut code = String::from(first);
+                    while code.len() < 3 {
+                        let Some(digit) = chars.next_if(|c| ('0'..='7').contains(c)) else {
+                            break;
+                        };
+                        code.push(digit);
+                    }
+                    char::from_u32(u32::from_str_radix(&code, 8).ok()?)?
+                }
+                other => other,
+            },
+            '^' if caret => control(chars.next()?)?,
+            c => c,
+        };
+
+        let c = if std::mem::take(&mut ctrl) {
+            control(c)?
+        } else {
+            c
+        };
+        if std::mem::take(&mut meta) {
+            input.push(ESC);
+        }
+        input.push(c);
+    }
+    Some(input)
+}
+
+/// The control character for `c`: `\x01` for `a`, DEL for `?`.
+fn control(c: char) -> Option<char> {
+    match c {
+        '?' => Some('\x7f'),
+        '@'..='_' | 'a'..='z' => Some(char::from(c.to_ascii_uppercase() as u8 & 0x1f)),
+        _ => None,
+    }
+}
+
+/// Parses the key names of inputrc bindings without quotes:
+/// `Control-u`, `Meta-Rubout`, `C-M-x`.
+fn key_name(text: &str) -> Option<Vec<char>> {
+    let mut name = text.trim();
+    let (mut ctrl, mut meta) = (false, false);
+
+    while let Some((modifier, rest)) = name.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+        match modifier.to_lowercase().as_str() {
+            "control" | "c" => ctrl = true,
+            "meta" | "m" => meta = true,
+            _ => return None,
+        }
+        name = rest;
+    }
+
+    let mut chars = name.chars();
+    let c = match name.to_lowercase().as_str() {
+        "del" | "rubout" => '\x7f',
+        "esc" | "escape" => ESC,
+        "lfd" | "newline" => '\n',
+        "ret" | "return" => '\r',
+        "space" | "spc" => ' ',
+        "tab" => '\t',
+        _ => match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return None,
+        },
+    };
+
+    let c = if ctrl { control(c)? } else { c };
+    Some(if meta { vec![ESC, c] } else { vec![c] })
+}
+
+/// Decodes the characters a terminal sends for a key sequence into strokes.
+/// `ESC` before a key is Alt, and `ESC [` sequences are cursor and function
+/// keys: `\e[1;5C` is `Ctrl+Right`.
+pub fn decode_input(input: &[char]) -> Option<KeyCombo> {
+    let mut strokes = Vec::new();
+    let mut rest = input;
+
+    while let Some((&c, after)) = rest.split_first() {
+        if c != ESC || after.is_empty() {
+            strokes.push(control_stroke(c)?);
+            rest = after;
+            continue;
+        }
+
+        if let Some((stroke, len)) = escape_sequence(after) {
+            strokes.push(stroke);
+            rest = &after[len..];
+            continue;
+        }
+
+        // ESC before another key is how terminals send Alt
+        let (&next, after) = after.split_first()?;
+        let (mut stroke, after) = match escape_sequence(after).filter(|_| next == ESC) {
+            Some((stroke, len)) => (stroke, &after[len..]),
+            None => (control_stroke(next)?, after),
+        };
+        stroke.modifiers.alt = true;
+        strokes.push(stroke);
+        rest = after;
+    }
+
+    (!strokes.is_empty()).then(|| KeyCombo::new(strokes))
+}
+
+/// The key for a single character, with control characters as `Ctrl+key`.
+fn control_stroke(c: char) -> Option<KeyStroke> {
+    let (ctrl, key) = match c {
+        '\0' => (true, Key::Space),
+        '\t' => (false, Key::Tab),
+        '\r' => (false, Key::Enter),
+        ESC => (false, Key::Esc),
+        '\x7f' => (false, Key::Backspace),
+        '\x01'..='\x1a' => (true, Key::Char(char::from(c as u8 + b'a' - 1))),
+        '\x1c'..='\x1f' => (true, Key::Char(char::from(c as u8 + b'@'))),
+        c if c.is_control() => return None,
+        c => (false, Key::Char(c)),
+    };
+    let modifiers = Modifiers {
+        ctrl,
+        ..Modifiers::NONE
+    };
+    Some(KeyStroke::new(modifiers, key))
+}
+
+/// Decodes an xterm style escape sequence after its `ESC`: `[A`, `OP`,
+/// `[3~` or `[1;5C` with a modifier. Returns the stroke and how many
+/// characters it took.
+fn escape_sequence(sequence: &[char]) -> Option<(KeyStroke, usize)> {
+    let (&intro, rest) = sequence.split_first()?;
+    if intro != '[' && intro != 'O' {
+        return None;
+    }
+
+    let params_len = rest
+        .iter()
+        .take_while(|c| c.is_ascii_digit() || **c == ';')
+        .count();
+    let params: String = rest[..params_len].iter().collect();
+    let &last = rest.get(params_len)?;
+    let mut params = params.split(';').map(|param| param.parse::<u8>().ok());
+    let number = params.next().flatten();
+    let modifier = params.next().flatten();
+
+    let key = match (last, number) {
+        ('A', _) => Key::Up,
+        ('B', _) => Key::Down,
+        ('C', _) => Key::Right,
+        ('D', _) => Key::Left,
+        ('H', _) => Key::Home,
+        ('F', _) => Key::End,
+        ('Z', _) => Key::Tab,
+        ('P'..='S', _) => Key::F(1 + (last as u8 - b'P')),
+        ('~', Some(number)) if intro == '[' => match number {
+            1 | 7 => Key::Home,
+            2 => Key::Insert,
+            3 => Key::Delete,
+            4 | 8 => Key::End,
+            5 => Key::PageUp,
+            6 => Key::PageDown,
+            11..=15 => Key::F(number - 10),
+            17..=21 => Key::F(number - 11),
+            23..=26 => Key::F(number - 12),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    // xterm sends 1 + a bit each for shift, alt, ctrl and super
+    let mut modifiers = Modifiers::NONE;
+    if let Some(modifier) = modifier {
+        let bits = modifier.checked_sub(1)?;
+        modifiers.shift = bits & 1 != 0;
+        modifiers.alt = bits & 2 != 0;
+        modifiers.ctrl = bits & 4 != 0;
+        modifiers.super_key = bits & 8 != 0;
+    }
+    if last == 'Z' {
+        modifiers.shift = true;
+    }
+
+    Some((KeyStroke::new(modifiers, key), params_len + 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_an_inputrc() {
+        let imported = import(
+            r#"# history search
+            "\e[A": history-search-backward
+            "\e[1;5C": forward-word
+            Control-u: unix-line-discard
+            Meta-Rubout: backward-kill-word
+            "\C-xe": "echo hi\n"
+            "a": self-insert"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Up = previous command starting like this one [emacs]",
+                "Ctrl+Right = forward word [emacs]",
+                "Ctrl+U = cut to start of line [emacs]",
+                "Alt+Backspace = cut to start of word [emacs]",
+                "Ctrl+X e = types echo hi\\n [emacs]",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_bind_output() {
+        let imported = import(
+            r#""\C-a": beginning-of-line
+            "\C-k": kill-line
+            abort can be found on "\C-g", "\C-x\C-g".
+            dump-functions is not bound to any keys"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+A = beginning of line [emacs]",
+                "Ctrl+K = cut to end of line [emacs]",
+                "Ctrl+G = abort [emacs]",
+                "Ctrl+X Ctrl+G = abort [emacs]",
+            ]
+        );
+    }
+
+    #[test]
+    fn keymaps_become_contexts() {
+        let imported = import(
+            r#"set editing-mode vi
+            "\C-l": clear-screen
+            set keymap vi-command
+            "k": history-search-backward
+            set keymap emacs-ctlx
+            "u": undo
+            set keymap emacs-meta
+            "f": forward-word
+            set keymap vi-nonsense"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+L = clear screen [vi-insert]",
+                "k = previous command starting like this one [vi-command]",
+                "Ctrl+X u = undo [emacs]",
+                "Alt+F = forward word [emacs]",
+            ]
+        );
+        assert_eq!(imported.skipped.len(), 1);
+    }
+
+    #[test]
+    fn mode_conditions_pick_the_keymap() {
+        let imported = import(
+            r#"set editing-mode vi
+            $if mode=emacs
+            "\C-a": beginning-of-line
+            $else
+            "\C-b": backward-char
+            $endif
+            $if Bash
+            $if mode=vi
+            "\C-e": end-of-line
+            $endif
+            "\C-f": forward-char
+            $endif
+            set keymap vi-command
+            $if term=xterm
+            "j": next-history
+            $endif"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+A = beginning of line [emacs]",
+                "Ctrl+B = backward char [vi-insert]",
+                "Ctrl+E = end of line [vi-insert]",
+                "Ctrl+F = forward char [vi-insert]",
+                "j = next history [vi-command]",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_sequences_are_skipped() {
+        let imported = import(
+            r#""\x9b": kill-word
+            Hyper-x: kill-word
+            not a binding"#,
+        );
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 3);
+    }
+
+    #[test]
+    fn unescapes_sequences() {
+        assert_eq!(unescape(r"\C-x\M-a", false), Some(vec!['\x18', ESC, 'a']));
+        assert_eq!(
+            unescape(r#"\e\t\\\""#, false),
+            Some(vec![ESC, '\t', '\\', '"'])
+        );
+        assert_eq!(unescape(r"\101\x42", false), Some(vec!['A', 'B']));
+        assert_eq!(unescape("^X^?", true), Some(vec!['\x18', '\x7f']));
+        assert_eq!(unescape("^X", false), Some(vec!['^', 'X']));
+    }
+
+    #[test]
+    fn decodes_terminal_input() {
+        let decode = |input: &str| {
+            let input: Vec<char> = input.chars().collect();
+            decode_input(&input).map(|key_combo| key_combo.to_string())
+        };
+        assert_eq!(decode("\x1b[3~").as_deref(), Some("Delete"));
+        assert_eq!(decode("\x1bOP").as_deref(), Some("F1"));
+        assert_eq!(decode("\x1b[15;2~").as_deref(), Some("Shift+F5"));
+        assert_eq!(decode("\x1b[Z").as_deref(), Some("Shift+Tab"));
+        assert_eq!(decode("\x1b\x1b[D").as_deref(), Some("Alt+Left"));
+        assert_eq!(decode("\x1bb").as_deref(), Some("Alt+B"));
+        assert_eq!(decode("\x00").as_deref(), Some("Ctrl+Space"));
+        assert_eq!(decode("\x1b").as_deref(), Some("Esc"));
+        assert_eq!(decode("\u{9b}"), None);
+        assert_eq!(decode(""), None);
+    }
+}
@@ -0,0 +1,255 @@
+//! GNU screen's `.screenrc`: `bind` adds commands after the escape key,
+//! `bindkey` binds keys directly.
+//!
+//! `bind` bindings become two-stroke chords in the "prefix" context,
+//! `bindkey -m` ones go into "copy", other `bindkey` ones have no context.
+
+use crate::{
+    importers::{logical_lines, split_word, strip_comment, Imported},
+    shortcuts::{Key, KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+const PREFIX_CONTEXT: &str = "prefix";
+const COPY_CONTEXT: &str = "copy";
+
+/// A binding as read, turned into a shortcut once the final escape key is known.
+struct Binding {
+    context: Option<String>,
+    /// Whether the escape key is pressed first.
+    prefixed: bool,
+    stroke: KeyStroke,
+    command: String,
+}
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut escape = KeyStroke::new(
+        Modifiers {
+            ctrl: true,
+            ..Modifiers::NONE
+        },
+        Key::Char('a'),
+    );
+    let mut bindings: Vec<Binding> = Vec::new();
+
+    for line in logical_lines(text) {
+        let Some((command, rest)) = split_word(strip_comment(&line)) else {
+            continue;
+        };
+
+        let binding = match command.as_str() {
+            "escape" | "defescape" => {
+                match split_word(rest).and_then(|(keys, _)| parse_escape(&keys)) {
+                    Some(stroke) => escape = stroke,
+                    None => imported.skip(format!("`{}`: unknown escape key", line.trim())),
+                }
+                continue;
+            }
+            "bind" => parse_bind(rest),
+            "bindkey" => parse_bindkey(rest),
+            _ => continue,
+        };
+
+        match binding {
+            Ok(binding) => {
+                bindings.retain(|other| {
+                    other.context != binding.context || other.stroke != binding.stroke
+                });
+                // a binding without a command removes the key
+                if !binding.command.is_empty() {
+                    bindings.push(binding);
+                }
+            }
+            Err(reason) => imported.skip(format!("`{}`: {reason}", line.trim())),
+        }
+    }
+
+    for binding in bindings {
+        let strokes = if binding.prefixed {
+            vec![escape, binding.stroke]
+        } else {
+            vec![binding.stroke]
+        };
+        let mut shortcut = Shortcut::from(KeyCombo::new(strokes), binding.command);
+        shortcut.set_context(binding.context.as_deref().unwrap_or_default());
+        imported.push(shortcut);
+    }
+}
+
+/// Parses what follows `bind`: `[-c class] key [command]`.
+fn parse_bind(text: &str) -> Result<Binding, String> {
+    let (mut key, mut rest) = split_word(text).ok_or("no key")?;
+    let mut context = Some(PREFIX_CONTEXT.to_owned());
+    if key == "-c" {
+        // keys of a command class are pressed after whatever command switched to it
+        let (class, after) = split_word(rest).ok_or("no command class")?;
+        context = Some(class);
+        (key, rest) = split_word(after).ok_or("no key")?;
+    }
+
+    let stroke = parse_key(&key).ok_or_else(|| format!("unknown key `{key}`"))?;
+    Ok(Binding {
+        prefixed: context.as_deref() == Some(PREFIX_CONTEXT),
+        context,
+        stroke,
+        command: rest.trim().to_owned(),
+    })
+}
+
+/// Parses what follows `bindkey`: `[-d] [-m] [-a] [[-k|-t] string [command]]`.
+fn parse_bindkey(text: &str) -> Result<Binding, String> {
+    let mut context = None;
+    let mut capability = false;
+    let mut rest = text;
+
+    let key = loop {
+        let (word, after) = split_word(rest).ok_or("no key")?;
+        rest = after;
+        match word.as_str() {
+            "-m" => context = Some(COPY_CONTEXT.to_owned()),
+            "-k" => capability = true,
+            "-d" | "-a" | "-t" => {}
+            _ => break word,
+        }
+    };
+
+    let stroke = if capability {
+        parse_capability(&key)
+    } else {
+        parse_sequence(&key)
+    }
+    .ok_or_else(|| format!("unknown key `{key}`"))?;
+
+    Ok(Binding {
+        context,
+        prefixed: false,
+        stroke,
+        command: rest.trim().to_owned(),
+    })
+}
+
+/// The escape key is the first of the two characters given to `escape`,
+/// e.g. `^Aa` or `` `` ``.
+fn parse_escape(keys: &str) -> Option<KeyStroke> {
+    match keys.strip_prefix('^') {
+        Some(rest) => parse_key(&format!("^{}", rest.chars().next()?)),
+        None => parse_key(&keys.chars().next()?.to_string()),
+    }
+}
+
+/// A `bind` key: a character, optionally written `^x` for control.
+fn parse_key(key: &str) -> Option<KeyStroke> {
+    let (ctrl, key) = match key.strip_prefix('^') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, key),
+    };
+    let mut chars = key.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+
+    let modifiers = Modifiers {
+        ctrl,
+        ..Modifiers::NONE
+    };
+    // control combos don't tell case apart, `^A` is `^a`
+    let c = if ctrl { c.to_ascii_lowercase() } else { c };
+    Some(KeyStroke::new(modifiers, Key::Char(c)))
+}
+
+/// A `bindkey` input sequence. Only those standing for a single key are
+/// understood: `x`, `^X`, and `^[x` for Meta+x.
+fn parse_sequence(sequence: &str) -> Option<KeyStroke> {
+    match sequence.strip_prefix("^[") {
+        Some(rest) if !rest.is_empty() => {
+            let mut stroke = parse_key(rest)?;
+            stroke.modifiers.alt = true;
+            Some(stroke)
+        }
+        _ => parse_key(sequence),
+    }
+}
+
+/// A termcap name as used with `bindkey -k`, e.g. `k1` for F1.
+fn parse_capability(name: &str) -> Option<KeyStroke> {
+    let key = match name {
+        "k;" => Key::F(10),
+        "F1" => Key::F(11),
+        "F2" => Key::F(12),
+        "kh" => Key::Home,
+        "@7" => Key::End,
+        "kD" => Key::Delete,
+        "kI" => Key::Insert,
+        "kP" => Key::PageUp,
+        "kN" => Key::PageDown,
+        "ku" => Key::Up,
+        "kd" => Key::Down,
+        "kl" => Key::Left,
+        "kr" => Key::Right,
+        "kb" => Key::Backspace,
+        _ => {
+            let number = name.strip_prefix('k')?.parse::<u8>().ok()?;
+            if !(1..=9).contains(&number) {
+                return None;
+            }
+            Key::F(number)
+        }
+    };
+    Some(KeyStroke::new(Modifiers::NONE, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_a_screenrc() {
+        let imported = import(
+            "# windows
+            bind c screen 1
+            bind ^k kill
+            bindkey -k k5 prev
+            bindkey ^[n next
+            bindkey -m ^f stuff ' '
+            bind -c resize j resize +1",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+A c = screen 1 [prefix]",
+                "Ctrl+A Ctrl+K = kill [prefix]",
+                "F5 = prev",
+                "Alt+N = next",
+                "Ctrl+F = stuff ' ' [copy]",
+                "j = resize +1 [resize]",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_escape_key_can_change() {
+        let imported = import("bind c screen\nescape ^Bb");
+        assert_eq!(imported.lines(), ["Ctrl+B c = screen [prefix]"]);
+
+        let imported = import("escape ``\nbind c screen");
+        assert_eq!(imported.lines(), ["` c = screen [prefix]"]);
+    }
+
+    #[test]
+    fn binding_nothing_removes_a_key() {
+        let imported = import("bind k kill\nbind x lockscreen\nbind k");
+        assert_eq!(imported.lines(), ["Ctrl+A x = lockscreen [prefix]"]);
+    }
+
+    #[test]
+    fn unknown_keys_are_skipped() {
+        let imported = import("bind ab foo\nbindkey -k zz bar\nbindkey ^[[A up\nescape");
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 4);
+    }
+}
@@ -0,0 +1,195 @@
+//! `sxhkdrc`: a hotkey on one line and the command to run indented on the
+//! next, e.g. `super + {h,j,k,l}` and `bspc node -f {west,south,north,east}`.
+//!
+//! Brace sequences expand into one binding per element, paired up between
+//! hotkey and command. `;` separates the strokes of a chord.
+
+use crate::{
+    importers::{apply_x_modifier, keysym_stroke, logical_lines, Imported},
+    shortcuts::{KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut hotkey: Option<String> = None;
+
+    for line in logical_lines(text) {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(hotkey) = hotkey.replace(line.trim().to_owned()) {
+                imported.skip(format!("`{hotkey}`: no command"));
+            }
+            continue;
+        }
+        let Some(hotkey) = hotkey.take() else {
+            continue;
+        };
+        bind(&hotkey, line.trim(), imported);
+    }
+
+    if let Some(hotkey) = hotkey {
+        imported.skip(format!("`{hotkey}`: no command"));
+    }
+}
+
+fn bind(hotkey: &str, command: &str, imported: &mut Imported) {
+    let hotkeys = expand(hotkey);
+    let commands = expand(command);
+    if commands.len() != 1 && commands.len() != hotkeys.len() {
+        imported.skip(format!(
+            "`{hotkey}`: {} hotkeys but {} commands",
+            hotkeys.len(),
+            commands.len()
+        ));
+        return;
+    }
+
+    for (i, hotkey) in hotkeys.iter().enumerate() {
+        let command = commands.get(i).unwrap_or(&commands[0]);
+        match parse_hotkey(hotkey) {
+            Some(key_combo) => imported.push(Shortcut::from(key_combo, command.trim().to_owned())),
+            None => imported.skip(format!("`{hotkey}` ({}): unknown key", command.trim())),
+        }
+    }
+}
+
+/// Expands the `{a,b}` sequences in `text`, in the order sxhkd pairs them
+/// up: `{_,shift + }{1-3}` is `1`, `2`, `3`, `shift + 1`, ... `_` stands
+/// for nothing, `a-z` and `1-9` for ranges.
+fn expand(text: &str) -> Vec<String> {
+    let Some(open) = text.find('{') else {
+        return vec![text.to_owned()];
+    };
+    let Some(close) = text[open..].find('}').map(|close| open + close) else {
+        return vec![text.to_owned()];
+    };
+
+    let (before, sequence, after) = (&text[..open], &text[open + 1..close], &text[close + 1..]);
+    let rest = expand(after);
+
+    let mut expanded = Vec::new();
+    for element in sequence.split(',').flat_map(expand_range) {
+        for tail in &rest {
+            expanded.push(format!("{before}{element}{tail}"));
+        }
+    }
+    expanded
+}
+
+/// `a-c` is `a`, `b`, `c`. `_` is empty, anything else is itself.
+fn expand_range(element: &str) -> Vec<String> {
+    let chars: Vec<char> = element.chars().collect();
+    match chars.as_slice() {
+        ['_'] => vec![String::new()],
+        &[first, '-', last] if first.is_ascii_alphanumeric() && first < last => {
+            (first..=last).map(String::from).collect()
+        }
+        _ => vec![element.to_owned()],
+    }
+}
+
+/// Parses `super + shift + Return` or the chord `super + w ; f`.
+fn parse_hotkey(hotkey: &str) -> Option<KeyCombo> {
+    let strokes = hotkey
+        .split([';', ':'])
+        .map(parse_stroke)
+        .collect::<Option<Vec<_>>>()?;
+    Some(KeyCombo::new(strokes))
+}
+
+fn parse_stroke(text: &str) -> Option<KeyStroke> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    // `@` binds the release and `~` passes the key on, it's the same key either way
+    let name = parts.pop()?.trim_start_matches(['@', '~']);
+
+    let mut modifiers = Modifiers::NONE;
+    for modifier in parts {
+        if !apply_x_modifier(modifier.trim_start_matches(['@', '~']), &mut modifiers) {
+            return None;
+        }
+    }
+    keysym_stroke(modifiers, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_hotkeys_and_commands() {
+        let imported = import(
+            "# terminal
+super + Return
+\turxvt
+
+super + alt + {q,r}
+\tbspc {quit,wm -r}
+
+super + w ; f
+\tfirefox",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Super+Enter = urxvt",
+                "Alt+Super+Q = bspc quit",
+                "Alt+Super+R = bspc wm -r",
+                "Super+W f = firefox",
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_sequences_and_ranges() {
+        assert_eq!(expand("{_,shift + }{1-3}").len(), 6);
+        assert_eq!(
+            expand("{_,shift + }{1-2}"),
+            ["1", "2", "shift + 1", "shift + 2"]
+        );
+        assert_eq!(
+            expand("super + {a-c}"),
+            ["super + a", "super + b", "super + c"]
+        );
+        assert_eq!(expand("no braces"), ["no braces"]);
+        assert_eq!(expand("unclosed {a,b"), ["unclosed {a,b"]);
+    }
+
+    #[test]
+    fn one_command_serves_every_hotkey() {
+        let imported = import("super + {h,l}\n  bspc node -f next");
+        assert_eq!(
+            imported.lines(),
+            ["Super+H = bspc node -f next", "Super+L = bspc node -f next"]
+        );
+    }
+
+    #[test]
+    fn mismatches_are_skipped() {
+        let imported = import(
+            "super + {a,b,c}
+  echo {1,2}
+super + x
+super + y
+  echo y
+hyperx + z
+  echo z
+super + q",
+        );
+        assert_eq!(imported.lines(), ["Super+Y = echo y"]);
+        assert_eq!(imported.skipped.len(), 4, "{:?}", imported.skipped);
+    }
+
+    #[test]
+    fn release_and_replay_prefixes() {
+        let imported = import("@super + ~Escape\n  pkill -USR1 -x sxhkd");
+        assert_eq!(imported.lines(), ["Super+Esc = pkill -USR1 -x sxhkd"]);
+    }
+}
@@ -0,0 +1,389 @@
+//! `.tmux.conf` and `tmux list-keys` output, which share the `bind-key`
+//! syntax: `bind-key [-nr] [-T table] [-N note] key command`.
+//!
+//! Each key table becomes a context. Bindings in the prefix table are
+//! pressed after the prefix key, so they become two-stroke chords.
+
+use crate::{
+    importers::{logical_lines, split_word, strip_comment, Imported},
+    shortcuts::{Key, KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+const PREFIX_TABLE: &str = "prefix";
+const ROOT_TABLE: &str = "root";
+
+/// A binding as read, turned into a shortcut once the final prefix is known.
+struct Binding {
+    table: String,
+    key: String,
+    stroke: KeyStroke,
+    note: Option<String>,
+    command: String,
+}
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut prefix = Some(KeyStroke::new(
+        Modifiers {
+            ctrl: true,
+            ..Modifiers::NONE
+        },
+        Key::Char('b'),
+    ));
+    let mut bindings: Vec<Binding> = Vec::new();
+
+    let lines = logical_lines(text);
+    let mut lines = lines.iter();
+    while let Some(line) = lines.next() {
+        let Some((command, rest)) = split_word(strip_comment(line)) else {
+            continue;
+        };
+
+        match command.as_str() {
+            "bind" | "bind-key" => {
+                let Some(mut binding) = parse_bind(rest, imported) else {
+                    continue;
+                };
+                // tmux 3 blocks: `bind x {` with the commands on the lines below
+                if binding.command == "{" {
+                    binding.command = block(&mut lines);
+                }
+                bindings.retain(|other| other.table != binding.table || other.key != binding.key);
+                bindings.push(binding);
+            }
+            "unbind" | "unbind-key" => {
+                let (table, key) = parse_unbind(rest);
+                match key {
+                    Some(key) => bindings.retain(|b| b.table != table || b.key != key),
+                    None => bindings.retain(|b| b.table != table),
+                }
+            }
+            "set" | "set-option" => {
+                if let Some(key) = option_value(rest, "prefix") {
+                    prefix = match key.as_str() {
+                        "None" => None,
+                        key => match parse_key(key) {
+                            Some(stroke) => Some(stroke),
+                            None => {
+                                imported.skip(format!("prefix `{key}`: unknown key"));
+                                prefix
+                            }
+                        },
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for binding in bindings {
+        let strokes = match prefix {
+            Some(prefix) if binding.table == PREFIX_TABLE => vec![prefix, binding.stroke],
+            _ => vec![binding.stroke],
+        };
+        let description = binding.note.unwrap_or_else(|| describe(&binding.command));
+        let mut shortcut = Shortcut::from(KeyCombo::new(strokes), description);
+        shortcut.set_context(&binding.table);
+        imported.push(shortcut);
+    }
+}
+
+/// Parses what follows `bind-key`. Returns `None` for bindings to skip.
+fn parse_bind(text: &str, imported: &mut Imported) -> Option<Binding> {
+    let mut table = PREFIX_TABLE.to_owned();
+    let mut note = None;
+    let mut rest = text;
+
+    let key = loop {
+        let (word, after) = split_word(rest)?;
+        rest = after;
+        let Some(flags) = word.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+            break word;
+        };
+
+        for (i, flag) in flags.char_indices() {
+            // a flag with a value takes the rest of the cluster or the next word
+            let mut value = || match &flags[i + 1..] {
+                "" => split_word(rest).map(|(value, after)| {
+                    rest = after;
+                    value
+                }),
+                attached => Some(attached.to_owned()),
+            };
+            match flag {
+                'n' => table = ROOT_TABLE.to_owned(),
+                'T' => {
+                    table = value()?;
+                    break;
+                }
+                'N' => {
+                    note = value();
+                    break;
+                }
+                _ => {}
+            }
+        }
+    };
+
+    // mouse events are bound like keys but can't be looked up as shortcuts
+    const MOUSE_EVENTS: [&str; 6] = ["Mouse", "Wheel", "Double", "Triple", "SecondClick", "Drag"];
+    if MOUSE_EVENTS.iter().any(|event| key.starts_with(event)) {
+        return None;
+    }
+
+    let command = rest.trim();
+    if command.is_empty() {
+        imported.skip(format!("`{key}` in {table}: no command"));
+        return None;
+    }
+    let Some(stroke) = parse_key(&key) else {
+        imported.skip(format!("`{key}` in {table}: unknown key"));
+        return None;
+    };
+
+    Some(Binding {
+        table,
+        key,
+        stroke,
+        note,
+        command: command.to_owned(),
+    })
+}
+
+/// Parses what follows `unbind-key` into the table and the key, `None` for
+/// `-a` which unbinds the whole table.
+fn parse_unbind(text: &str) -> (String, Option<String>) {
+    let mut table = PREFIX_TABLE.to_owned();
+    let mut rest = text;
+    let mut key = None;
+
+    while let Some((word, after)) = split_word(rest) {
+        rest = after;
+        match word.as_str() {
+            "-n" => table = ROOT_TABLE.to_owned(),
+            "-T" => {
+                if let Some((value, after)) = split_word(rest) {
+                    table = value;
+                    rest = after;
+                }
+            }
+            "-a" => return (table, None),
+            flag if flag.starts_with('-') && flag.len() > 1 => {}
+            _ => {
+                key = Some(word);
+                break;
+            }
+        }
+    }
+    (table, key)
+}
+
+/// The value `set-option` gives to `name`, if that's the option it sets.
+fn option_value(text: &str, name: &str) -> Option<String> {
+    let mut rest = text;
+    loop {
+        let (word, after) = split_word(rest)?;
+        rest = after;
+        if !word.starts_with('-') {
+            return (word == name).then(|| split_word(rest).map(|(value, _)| value))?;
+        }
+    }
+}
+
+/// Collects the lines of a `{ ... }` block into one command line.
+fn block<'a>(lines: &mut impl Iterator<Item = &'a String>) -> String {
+    let mut commands = Vec::new();
+    for line in lines {
+        let line = strip_comment(line).trim();
+        if line == "}" {
+            break;
+        }
+        if !line.is_empty() {
+            commands.push(line);
+        }
+    }
+    commands.join("; ")
+}
+
+/// Copy mode commands are sent with `send-keys -X`, the name alone says
+/// what they do.
+fn describe(command: &str) -> String {
+    for prefix in ["send-keys -X ", "send -X "] {
+        if let Some(copy_command) = command.strip_prefix(prefix) {
+            return copy_command.trim().to_owned();
+        }
+    }
+    command.to_owned()
+}
+
+/// Parses a key as tmux writes it: `C-b`, `M-Left`, `S-F5`, `BSpace`.
+fn parse_key(text: &str) -> Option<KeyStroke> {
+    let mut modifiers = Modifiers::NONE;
+    let mut rest = text;
+
+    loop {
+        if let Some(key) = rest.strip_prefix('^').filter(|key| !key.is_empty()) {
+            modifiers.ctrl = true;
+            rest = key;
+            continue;
+        }
+        let Some((modifier, key)) = rest.split_at_checked(2) else {
+            break;
+        };
+        if key.is_empty() {
+            break;
+        }
+        match modifier {
+            "C-" => modifiers.ctrl = true,
+            "M-" => modifiers.alt = true,
+            "S-" => modifiers.shift = true,
+            _ => break,
+        }
+        rest = key;
+    }
+
+    let mut chars = rest.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) => Key::Char(c),
+        _ => match rest {
+            "Enter" => Key::Enter,
+            "Escape" => Key::Esc,
+            "Space" => Key::Space,
+            "Tab" => Key::Tab,
+            "BTab" => {
+                modifiers.shift = true;
+                Key::Tab
+            }
+            "BSpace" => Key::Backspace,
+            "DC" => Key::Delete,
+            "IC" => Key::Insert,
+            "Home" => Key::Home,
+            "End" => Key::End,
+            "PPage" | "PageUp" | "PgUp" => Key::PageUp,
+            "NPage" | "PageDown" | "PgDn" => Key::PageDown,
+            "Up" => Key::Up,
+            "Down" => Key::Down,
+            "Left" => Key::Left,
+            "Right" => Key::Right,
+            "KPEnter" => Key::Enter,
+            _ => {
+                if let Some(keypad) = rest.strip_prefix("KP") {
+                    let mut chars = keypad.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => Key::Char(c),
+                        _ => return None,
+                    }
+                } else {
+                    let number = rest.strip_prefix('F')?.parse::<u8>().ok()?;
+                    if !(1..=24).contains(&number) {
+                        return None;
+                    }
+                    Key::F(number)
+                }
+            }
+        },
+    };
+
+    Some(KeyStroke::new(modifiers, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_a_config() {
+        let imported = import(
+            "# split panes
+            bind | split-window -h
+            bind-key -n M-Left select-pane -L
+            bind -r -T prefix C-Up resize-pane -U 5 # grow
+            bind -N 'Reload the config' r source-file ~/.tmux.conf
+            bind-key -T copy-mode-vi v send-keys -X begin-selection
+            bind -n MouseDown1Pane select-pane",
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+B | = split-window -h [prefix]",
+                "Alt+Left = select-pane -L [root]",
+                "Ctrl+B Ctrl+Up = resize-pane -U 5 [prefix]",
+                "Ctrl+B r = Reload the config [prefix]",
+                "v = begin-selection [copy-mode-vi]",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_list_keys_output() {
+        let imported = import(
+            r#"bind-key    -T prefix       c                    new-window
+bind-key    -T prefix       \"                   split-window
+bind-key    -T root         C-S-F5               refresh-client"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+B c = new-window [prefix]",
+                "Ctrl+B \" = split-window [prefix]",
+                "Ctrl+Shift+F5 = refresh-client [root]",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_prefix_can_change_or_go() {
+        let imported = import("set -g prefix C-a\nbind c new-window");
+        assert_eq!(imported.lines(), ["Ctrl+A c = new-window [prefix]"]);
+
+        let imported = import("bind c new-window\nset-option -g prefix None");
+        assert_eq!(imported.lines(), ["c = new-window [prefix]"]);
+    }
+
+    #[test]
+    fn unbinding_and_rebinding() {
+        let imported = import(
+            "bind c new-window
+            bind x kill-pane
+            bind x kill-window
+            unbind c
+            bind -n F1 a
+            bind -n F2 b
+            unbind -n -a",
+        );
+        assert_eq!(imported.lines(), ["Ctrl+B x = kill-window [prefix]"]);
+    }
+
+    #[test]
+    fn blocks_become_one_command() {
+        let imported = import("bind R {\n  source-file ~/.tmux.conf\n  display 'reloaded'\n}");
+        assert_eq!(
+            imported.lines(),
+            ["Ctrl+B Shift+R = source-file ~/.tmux.conf; display 'reloaded' [prefix]"]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_skipped() {
+        let imported = import("bind -n F30 a\nbind -n C-x");
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 2);
+    }
+
+    #[test]
+    fn keys() {
+        let key = |text| parse_key(text).map(|stroke| stroke.to_string());
+        assert_eq!(key("C-M-x").unwrap(), "Ctrl+Alt+X");
+        assert_eq!(key("^b").unwrap(), "Ctrl+B");
+        assert_eq!(key("BTab").unwrap(), "Shift+Tab");
+        assert_eq!(key("NPage").unwrap(), "PageDown");
+        assert_eq!(key("KP5").unwrap(), "5");
+        assert_eq!(key("C--").unwrap(), "Ctrl+-");
+        assert!(key("Nonsense").is_none());
+    }
+}
@@ -0,0 +1,299 @@
+//! VS Code's `keybindings.json`: a list of `{"key", "command", "when"}`
+//! objects, written as JSON with comments and trailing commas.
+
+use serde_json::Value;
+
+use crate::{
+    importers::{words, Imported},
+    shortcuts::{Key, KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+/// Segments of command IDs too generic to say what a command is about.
+const GENERIC_SEGMENTS: [&str; 4] = ["action", "actions", "editor", "workbench"];
+
+pub fn parse(text: &str, imported: &mut Imported) -> Result<(), String> {
+    let document: Value = serde_json::from_str(&strip_jsonc(text))
+        .map_err(|err| format!("not a valid keybindings file: {err}"))?;
+    let Value::Array(entries) = document else {
+        return Err("not a valid keybindings file: expected a list of keybindings".into());
+    };
+
+    for (number, entry) in entries.iter().enumerate().map(|(i, entry)| (i + 1, entry)) {
+        let field = |name| entry.get(name).and_then(Value::as_str);
+        let (Some(key), Some(command)) = (field("key"), field("command")) else {
+            imported.skip(format!("entry {number} has no key or command"));
+            continue;
+        };
+        let when = field("when").unwrap_or_default();
+
+        // `-command` removes a default binding, an empty command unbinds the key
+        if command.is_empty() || command.starts_with('-') {
+            continue;
+        }
+
+        let key_combo = match parse_key(key) {
+            Ok(key_combo) => key_combo,
+            Err(err) => {
+                imported.skip(format!("`{key}` ({command}): {err}"));
+                continue;
+            }
+        };
+
+        let mut shortcut = Shortcut::from(key_combo, describe(command));
+        shortcut.set_context(when);
+        imported.push(shortcut);
+    }
+
+    Ok(())
+}
+
+/// Parses a key such as `ctrl+shift+p` or the chord `ctrl+k ctrl+c`.
+fn parse_key(key: &str) -> Result<KeyCombo, String> {
+    let strokes = key
+        .split_whitespace()
+        .map(parse_stroke)
+        .collect::<Result<Vec<_>, _>>()?;
+    if strokes.is_empty() {
+        return Err("the key is empty".into());
+    }
+    Ok(KeyCombo::new(strokes))
+}
+
+fn parse_stroke(stroke: &str) -> Result<KeyStroke, String> {
+    let stroke = stroke.to_lowercase();
+    // the plus key itself is written `ctrl++`
+    let (modifier_names, key) = match stroke.strip_suffix("++") {
+        Some(modifier_names) => (modifier_names, "+"),
+        None => stroke.rsplit_once('+').unwrap_or(("", &stroke)),
+    };
+
+    let mut modifiers = Modifiers::NONE;
+    for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+        match name {
+            "ctrl" => modifiers.ctrl = true,
+            "shift" => modifiers.shift = true,
+            "alt" => modifiers.alt = true,
+            // the Windows key on Linux and Windows, Command on macOS
+            "meta" | "cmd" | "win" => modifiers.super_key = true,
+            other => return Err(format!("unknown modifier `{other}`")),
+        }
+    }
+
+    let key = parse_key_name(key).ok_or_else(|| format!("unknown key `{key}`"))?;
+    Ok(KeyStroke::new(modifiers, key))
+}
+
+/// Key names as VS Code writes them, already lowercased.
+fn parse_key_name(name: &str) -> Option<Key> {
+    // scan codes of the US layout: `[KeyA]`, `[Digit1]`
+    let name = match name
+        .strip_prefix('[')
+        .and_then(|code| code.strip_suffix(']'))
+    {
+        Some(code) => code
+            .strip_prefix("key")
+            .or_else(|| code.strip_prefix("digit"))
+            .unwrap_or(code),
+        None => name,
+    };
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c));
+    }
+
+    let key = match name {
+        "escape" => Key::Esc,
+        "enter" => Key::Enter,
+        "tab" => Key::Tab,
+        "backspace" => Key::Backspace,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "up" | "arrowup" => Key::Up,
+        "down" | "arrowdown" => Key::Down,
+        "left" | "arrowleft" => Key::Left,
+        "right" | "arrowright" => Key::Right,
+        "space" => Key::Space,
+        "numpad_add" => Key::Char('+'),
+        "numpad_subtract" => Key::Char('-'),
+        "numpad_multiply" => Key::Char('*'),
+        "numpad_divide" => Key::Char('/'),
+        "numpad_decimal" => Key::Char('.'),
+        "numpad_separator" => Key::Char(','),
+        _ => {
+            if let Some(digit) = name.strip_prefix("numpad") {
+                let digit = digit.parse::<u8>().ok().filter(|&digit| digit < 10)?;
+                return Some(Key::Char(char::from(b'0' + digit)));
+            }
+            let number = name.strip_prefix('f')?.parse::<u8>().ok()?;
+            if !(1..=24).contains(&number) {
+                return None;
+            }
+            Key::F(number)
+        }
+    };
+    Some(key)
+}
+
+/// A readable description for a command ID: `editor.action.commentLine`
+/// becomes "comment line", `workbench.action.terminal.focus` becomes
+/// "terminal: focus".
+fn describe(command: &str) -> String {
+    let mut segments = command.rsplit('.');
+    let name = words(segments.next().unwrap_or(command));
+
+    // a lone verb needs the segment before it to make sense
+    if !name.contains(' ') {
+        if let Some(area) = segments.next().filter(|s| !GENERIC_SEGMENTS.contains(s)) {
+            return format!("{}: {name}", words(area));
+        }
+    }
+    name
+}
+
+/// Turns JSON with comments into plain JSON: drops `//` and `/* */`
+/// comments and commas right before a closing bracket. Line breaks are kept
+/// so that errors point at the right line.
+fn strip_jsonc(text: &str) -> String {
+    let mut json = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            if c == '\\' {
+                json.extend(chars.next());
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    if c == '\n' {
+                        json.push('\n');
+                    }
+                    previous = c;
+                }
+            }
+            ('}' | ']', _) => {
+                let end = json.trim_end().len();
+                if json[..end].ends_with(',') {
+                    json.remove(end - 1);
+                }
+                json.push(c);
+            }
+            _ => json.push(c),
+        }
+    }
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported).unwrap();
+        imported
+    }
+
+    #[test]
+    fn reads_keybindings_with_comments_and_trailing_commas() {
+        let imported = import(
+            r#"// Place your key bindings in this file
+            [
+                { "key": "ctrl+shift+p", "command": "workbench.action.showCommands" },
+                /* a chord */
+                { "key": "ctrl+k ctrl+c", "command": "editor.action.addCommentLine",
+                  "when": "editorTextFocus && !editorReadonly" },
+                { "key": "ctrl+/", "command": "url // not a comment" },
+            ]"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+Shift+P = show commands",
+                "Ctrl+K Ctrl+C = add comment line [editorTextFocus && !editorReadonly]",
+                "Ctrl+/ = url // not a comment",
+            ]
+        );
+        assert!(imported.skipped.is_empty());
+    }
+
+    #[test]
+    fn keys() {
+        let combo = |key| parse_key(key).map(|combo| combo.to_string());
+        assert_eq!(combo("ctrl++").unwrap(), "Ctrl++");
+        assert_eq!(combo("cmd+[KeyA]").unwrap(), "Super+A");
+        assert_eq!(combo("shift+numpad5").unwrap(), "Shift+5");
+        assert_eq!(combo("alt+f12").unwrap(), "Alt+F12");
+        assert_eq!(combo("ArrowUp").unwrap(), "Up");
+        assert!(combo("hyper+a").is_err());
+        assert!(combo("f25").is_err());
+        assert!(combo("").is_err());
+    }
+
+    #[test]
+    fn descriptions() {
+        assert_eq!(describe("editor.action.commentLine"), "comment line");
+        assert_eq!(
+            describe("workbench.action.terminal.focus"),
+            "terminal: focus"
+        );
+        assert_eq!(describe("workbench.action.files.save"), "files: save");
+        assert_eq!(describe("cursorUndo"), "cursor undo");
+    }
+
+    #[test]
+    fn removals_are_ignored_and_broken_entries_skipped() {
+        let imported = import(
+            r#"[
+                { "key": "ctrl+b", "command": "-workbench.action.toggleSidebarVisibility" },
+                { "key": "ctrl+e", "command": "" },
+                { "key": "ctrl+q" },
+                { "key": "hyper+x", "command": "quit" }
+            ]"#,
+        );
+        assert!(imported.shortcuts.is_empty());
+        assert_eq!(imported.skipped.len(), 2);
+        assert!(imported.skipped[1].contains("unknown modifier `hyper`"));
+    }
+
+    #[test]
+    fn the_last_binding_of_a_key_wins() {
+        let imported = import(
+            r#"[
+                { "key": "ctrl+s", "command": "workbench.action.files.save" },
+                { "key": "ctrl+s", "command": "workbench.action.files.saveAll" }
+            ]"#,
+        );
+        assert_eq!(imported.lines(), ["Ctrl+S = save all"]);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        let mut imported = Imported::default();
+        assert!(parse("{}", &mut imported).is_err());
+        assert!(parse("[", &mut imported).is_err());
+    }
+}
@@ -0,0 +1,594 @@
+//! `wezterm.lua`: the `keys` and `key_tables` of the config where they're
+//! written out as tables, e.g.
+//! `config.keys = { { key = 't', mods = 'CTRL', action = act.SpawnWindow } }`.
+//!
+//! `LEADER` in `mods` stands for the `leader` key, so those bindings become
+//! chords, and every one of the `key_tables` is a context. Bindings that Lua
+//! code builds, in loops or with `table.insert`, aren't seen.
+
+use crate::{
+    importers::{apply_modifier, key_name_stroke, words, Imported},
+    shortcuts::{KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+/// A Lua value as written. Anything but strings and tables is kept as the
+/// source text of the expression.
+enum Value {
+    String(String),
+    Table(Vec<(Option<String>, Value)>),
+    Expression(String),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Table(fields) => fields
+                .iter()
+                .find(|(name, _)| name.as_deref() == Some(key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(text: &str, imported: &mut Imported) -> Result<(), String> {
+    let mut parser = Parser::new(text);
+    let (mut keys, mut key_tables, mut leader) = (None, None, None);
+
+    // the last assignment of each wins, as when the config runs
+    while let Some((name, table)) = parser.next_assignment()? {
+        match name.as_str() {
+            "keys" => keys = Some(table),
+            "key_tables" => key_tables = Some(table),
+            _ => leader = Some(table),
+        }
+    }
+    if keys.is_none() && key_tables.is_none() {
+        return Err("no `keys` or `key_tables` table".into());
+    }
+
+    let leader = match &leader {
+        Some(leader) => {
+            let stroke = parse_stroke(leader).map(|(stroke, _)| stroke);
+            if stroke.is_none() {
+                imported.skip("the leader key: unknown key".into());
+            }
+            stroke
+        }
+        None => None,
+    };
+
+    if let Some(Value::Table(entries)) = &keys {
+        for (_, entry) in entries {
+            bind(entry, leader, "", imported);
+        }
+    }
+    if let Some(Value::Table(tables)) = &key_tables {
+        for (name, table) in tables {
+            let (Some(name), Value::Table(entries)) = (name, table) else {
+                continue;
+            };
+            for (_, entry) in entries {
+                bind(entry, leader, name, imported);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn bind(entry: &Value, leader: Option<KeyStroke>, context: &str, imported: &mut Imported) {
+    let key = entry.get("key").and_then(Value::as_str).unwrap_or_default();
+    let mods = entry
+        .get("mods")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let written = if mods.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{mods}+{key}")
+    };
+
+    let Some((stroke, uses_leader)) = parse_stroke(entry) else {
+        imported.skip(format!("`{written}`: unknown key"));
+        return;
+    };
+    let strokes = match (uses_leader, leader) {
+        (false, _) => vec![stroke],
+        (true, Some(leader)) => vec![leader, stroke],
+        (true, None) => {
+            imported.skip(format!("`{written}`: there's no leader key"));
+            return;
+        }
+    };
+    let key_combo = KeyCombo::new(strokes);
+
+    let Some(action) = entry.get("action") else {
+        imported.skip(format!("`{written}`: no action"));
+        return;
+    };
+    let description = describe(action);
+    // takes away one of WezTerm's own bindings rather than adding one
+    if description == "disable default assignment" {
+        imported.shortcuts.retain(|shortcut| {
+            shortcut.get_key_combo() != &key_combo
+                || shortcut.get_context().unwrap_or_default() != context
+        });
+        return;
+    }
+
+    let mut shortcut = Shortcut::from(key_combo, description);
+    shortcut.set_context(context);
+    imported.push(shortcut);
+}
+
+/// The `key` and `mods` of a binding table, and whether `mods` includes
+/// `LEADER`.
+fn parse_stroke(entry: &Value) -> Option<(KeyStroke, bool)> {
+    let key = entry.get("key")?.as_str()?;
+    let mods = entry
+        .get("mods")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    let mut modifiers = Modifiers::NONE;
+    let mut uses_leader = false;
+    for modifier in mods.split('|').map(str::trim) {
+        match modifier {
+            "" | "NONE" => {}
+            "LEADER" => uses_leader = true,
+            _ if apply_modifier(modifier, &mut modifiers) => {}
+            _ => return None,
+        }
+    }
+
+    // `phys:` and `mapped:` pick how the key is matched, not which one it is
+    let key = key
+        .strip_prefix("phys:")
+        .or_else(|| key.strip_prefix("mapped:"))
+        .unwrap_or(key);
+    Some((key_name_stroke(modifiers, key)?, uses_leader))
+}
+
+/// Describes `act.SpawnTab 'CurrentPaneDomain'` as "spawn tab
+/// CurrentPaneDomain", `wezterm.action.ActivateTab(0)` as "activate tab 0".
+fn describe(action: &Value) -> String {
+    let expression = match action {
+        Value::String(name) => return words(name),
+        Value::Table(_) => return "custom action".into(),
+        Value::Expression(expression) => expression.as_str(),
+    };
+    if expression.starts_with("wezterm.action_callback") {
+        return "custom action".into();
+    }
+
+    let expression = ["wezterm.action.", "act.", "action."]
+        .iter()
+        .find_map(|prefix| expression.strip_prefix(prefix))
+        .unwrap_or(expression);
+    let name_len = expression
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(expression.len());
+    let (name, arguments) = expression.split_at(name_len);
+
+    let arguments = arguments.replace(['\'', '"', '(', ')', '{', '}'], " ");
+    let arguments = arguments.split_whitespace().collect::<Vec<_>>().join(" ");
+    let arguments = arguments.replace(" ,", ",");
+    let name = words(name);
+    match arguments.trim_end_matches(',') {
+        "" => name,
+        arguments => format!("{name} {arguments}"),
+    }
+}
+
+/// Reads just enough Lua to pick table constructors out of a config.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        let end = self.position.min(self.chars.len());
+        let line = 1 + self.chars[..end].iter().filter(|&&c| c == '\n').count();
+        format!("line {line}: {message}")
+    }
+
+    /// Finds the next `keys = {`, `key_tables = {` or `leader = {`, also as
+    /// a field like `config.keys`, and reads the table.
+    fn next_assignment(&mut self) -> Result<Option<(String, Value)>, String> {
+        loop {
+            self.skip_blank()?;
+            let Some(c) = self.peek() else {
+                return Ok(None);
+            };
+
+            if c == '\'' || c == '"' || self.long_bracket_level().is_some() {
+                self.string()?;
+                continue;
+            }
+            if !(c.is_alphabetic() || c == '_') {
+                self.position += 1;
+                continue;
+            }
+
+            let name = self.identifier();
+            let name = name.rsplit('.').next().unwrap_or_default().to_owned();
+            if !matches!(name.as_str(), "keys" | "key_tables" | "leader") {
+                continue;
+            }
+            self.skip_blank()?;
+            if self.peek() != Some('=') || self.peek_at(1) == Some('=') {
+                continue;
+            }
+            self.position += 1;
+            self.skip_blank()?;
+            if self.peek() == Some('{') {
+                return Ok(Some((name, self.value()?)));
+            }
+        }
+    }
+
+    /// Reads a dotted name such as `wezterm.action`.
+    fn identifier(&mut self) -> String {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    /// Skips whitespace and `--` comments, including `--[[ long ]]` ones.
+    fn skip_blank(&mut self) -> Result<(), String> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.position += 1,
+                Some('-') if self.peek_at(1) == Some('-') => {
+                    self.position += 2;
+                    if self.long_bracket_level().is_some() {
+                        self.string()?;
+                    } else {
+                        while self.peek().is_some_and(|c| c != '\n') {
+                            self.position += 1;
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// The number of `=` in a `[[` or `[==[` starting here.
+    fn long_bracket_level(&self) -> Option<usize> {
+        if self.peek() != Some('[') {
+            return None;
+        }
+        let level = self.chars[self.position + 1..]
+            .iter()
+            .take_while(|&&c| c == '=')
+            .count();
+        (self.peek_at(level + 1) == Some('[')).then_some(level)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_blank()?;
+        match self.peek() {
+            Some('{') => self.table(),
+            Some('\'' | '"') => Ok(Value::String(self.string()?)),
+            Some('[') if self.long_bracket_level().is_some() => Ok(Value::String(self.string()?)),
+            _ => Ok(Value::Expression(self.expression()?)),
+        }
+    }
+
+    fn table(&mut self) -> Result<Value, String> {
+        self.position += 1;
+        let mut fields = Vec::new();
+
+        loop {
+            self.skip_blank()?;
+            match self.peek() {
+                None => return Err(self.error("unterminated table")),
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Table(fields));
+                }
+                _ => {}
+            }
+
+            let start = self.position;
+            let mut name = None;
+            if self.peek() == Some('[') && self.long_bracket_level().is_none() {
+                // `["key"] = value`
+                self.position += 1;
+                let key = self.value()?;
+                self.skip_blank()?;
+                if self.peek() != Some(']') {
+                    return Err(self.error("expected `]`"));
+                }
+                self.position += 1;
+                self.skip_blank()?;
+                if self.peek() != Some('=') {
+                    return Err(self.error("expected `=`"));
+                }
+                self.position += 1;
+                name = key.as_str().map(str::to_owned);
+            } else if self.peek().is_some_and(|c| c.is_alphabetic() || c == '_') {
+                let identifier = self.identifier();
+                self.skip_blank()?;
+                if self.peek() == Some('=') && self.peek_at(1) != Some('=') {
+                    self.position += 1;
+                    name = Some(identifier);
+                } else {
+                    self.position = start;
+                }
+            }
+
+            let value_start = self.position;
+            let mut value = self.value()?;
+            self.skip_blank()?;
+            // `'a' .. 'b'` and other expressions that start like a value
+            if !matches!(self.peek(), Some(',' | ';' | '}') | None) {
+                self.position = value_start;
+                value = Value::Expression(self.expression()?);
+            }
+            fields.push((name, value));
+
+            self.skip_blank()?;
+            if matches!(self.peek(), Some(',' | ';')) {
+                self.position += 1;
+            }
+        }
+    }
+
+    /// Reads the source text of an expression, up to the `,`, `;` or `}`
+    /// that ends it.
+    fn expression(&mut self) -> Result<String, String> {
+        let start = self.position;
+        let mut depth = 0usize;
+
+        loop {
+            match self.peek() {
+                None => break,
+                Some(',' | ';' | '}' | ')' | ']') if depth == 0 => break,
+                Some('{' | '(' | '[') if self.long_bracket_level().is_none() => {
+                    depth += 1;
+                    self.position += 1;
+                }
+                Some('}' | ')' | ']') => {
+                    depth -= 1;
+                    self.position += 1;
+                }
+                Some('\'' | '"' | '[') => {
+                    self.string()?;
+                }
+                Some('-') if self.peek_at(1) == Some('-') => self.skip_blank()?,
+                Some(_) => self.position += 1,
+            }
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+        Ok(text.trim().to_owned())
+    }
+
+    /// Reads a quoted or long-bracket string.
+    fn string(&mut self) -> Result<String, String> {
+        if let Some(level) = self.long_bracket_level() {
+            self.position += level + 2;
+            let close: Vec<char> = std::iter::once(']')
+                .chain(std::iter::repeat_n('=', level))
+                .chain(std::iter::once(']'))
+                .collect();
+            let start = self.position;
+            while !self.chars[self.position..].starts_with(&close) {
+                if self.position >= self.chars.len() {
+                    return Err(self.error("unterminated long string"));
+                }
+                self.position += 1;
+            }
+            let text = self.chars[start..self.position].iter().collect();
+            self.position += close.len();
+            return Ok(text);
+        }
+
+        let Some(quote) = self.peek() else {
+            return Err(self.error("expected a string"));
+        };
+        self.position += 1;
+        let mut text = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += 1;
+            match c {
+                c if c == quote => return Ok(text),
+                '\n' => return Err(self.error("unterminated string")),
+                '\\' => {
+                    let Some(escaped) = self.peek() else {
+                        return Err(self.error("unterminated string"));
+                    };
+                    self.position += 1;
+                    text.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'a' => '\x07',
+                        'b' => '\x08',
+                        'f' => '\x0c',
+                        'v' => '\x0b',
+                        other => other,
+                    });
+                }
+                c => text.push(c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Result<Imported, String> {
+        let mut imported = Imported::default();
+        parse(text, &mut imported)?;
+        Ok(imported)
+    }
+
+    #[test]
+    fn reads_keys() {
+        let imported = import(
+            r#"local wezterm = require 'wezterm'
+            local act = wezterm.action
+            local config = {}
+            config.font_size = 12
+            config.keys = {
+              -- tabs
+              { key = 't', mods = 'CTRL|SHIFT', action = act.SpawnTab 'CurrentPaneDomain' },
+              { key = '1', mods = 'ALT', action = wezterm.action.ActivateTab(0) },
+              { key = "Enter", mods = "SUPER", action = "ToggleFullScreen" },
+              { key = 'phys:Space', mods = 'CTRL', action = act.QuickSelect },
+              {
+                key = 'k',
+                mods = 'CMD',
+                action = act.Multiple { act.ClearScrollback 'ScrollbackAndViewport' },
+              },
+              {
+                key = 'e',
+                mods = 'CTRL',
+                action = wezterm.action_callback(function(window, pane)
+                  window:perform_action(act.EmitEvent 'edit', pane)
+                end),
+              },
+            }
+            return config"#,
+        )
+        .unwrap();
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+Shift+T = spawn tab CurrentPaneDomain",
+                "Alt+1 = activate tab 0",
+                "Super+Enter = toggle full screen",
+                "Ctrl+Space = quick select",
+                "Super+K = multiple act.ClearScrollback ScrollbackAndViewport",
+                "Ctrl+E = custom action",
+            ]
+        );
+    }
+
+    #[test]
+    fn leader_bindings_become_chords() {
+        let imported = import(
+            r#"config.leader = { key = 'a', mods = 'CTRL', timeout_milliseconds = 1000 }
+            config.keys = {
+              {
+                key = '|',
+                mods = 'LEADER|SHIFT',
+                action = act.SplitHorizontal { domain = 'CurrentPaneDomain' },
+              },
+              { key = 'a', mods = 'LEADER|CTRL', action = act.SendKey { key = 'a', mods = 'CTRL' }},
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+A Shift+| = split horizontal domain = CurrentPaneDomain",
+                "Ctrl+A Ctrl+A = send key key = a, mods = CTRL",
+            ]
+        );
+
+        let imported =
+            import("config.keys = { { key = 'c', mods = 'LEADER', action = act.Copy } }");
+        assert!(imported.unwrap().shortcuts.is_empty());
+    }
+
+    #[test]
+    fn key_tables_become_contexts() {
+        let imported = import(
+            r#"config.key_tables = {
+              resize_pane = {
+                { key = 'LeftArrow', action = act.AdjustPaneSize { 'Left', 1 } },
+                { key = 'Escape', action = 'PopKeyTable' },
+              },
+              ["copy_mode"] = {
+                { key = 'y', mods = 'NONE', action = act.CopyTo 'Clipboard' },
+              },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            imported.lines(),
+            [
+                "Left = adjust pane size Left, 1 [resize_pane]",
+                "Esc = pop key table [resize_pane]",
+                "y = copy to Clipboard [copy_mode]",
+            ]
+        );
+    }
+
+    #[test]
+    fn disabling_removes_a_binding() {
+        let imported = import(
+            r#"config.keys = {
+              { key = 'w', mods = 'CTRL', action = act.CloseCurrentTab { confirm = true } },
+              { key = 'n', mods = 'CTRL', action = act.SpawnWindow },
+              { key = 'w', mods = 'CTRL', action = act.DisableDefaultAssignment },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(imported.lines(), ["Ctrl+N = spawn window"]);
+    }
+
+    #[test]
+    fn the_last_assignment_wins() {
+        let imported = import(
+            r#"config.keys = { { key = 'a', mods = 'CTRL', action = act.SpawnWindow } }
+            -- config.keys = { { key = 'b', action = act.Nope } }
+            --[[ config.keys = { { key = 'c', action = act.Nope } } ]]
+            local s = "keys = {"
+            config.keys = { { key = 'd', mods = 'CTRL', action = act.SpawnWindow } }"#,
+        )
+        .unwrap();
+        assert_eq!(imported.lines(), ["Ctrl+D = spawn window"]);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = |text| import(text).err().unwrap_or_default();
+        assert_eq!(
+            error("local config = {}"),
+            "no `keys` or `key_tables` table"
+        );
+        assert_eq!(
+            error("config.keys = {\n  { key = 'a' "),
+            "line 2: unterminated table"
+        );
+        assert_eq!(error("config.keys = { 'a }"), "line 1: unterminated string");
+    }
+}
@@ -0,0 +1,494 @@
+//! Zellij's `config.kdl`: the `keybinds` node has a child per input mode,
+//! each holding `bind "Ctrl g" { SwitchToMode "locked"; }` nodes.
+//!
+//! Every mode becomes a context. Bindings in `shared` work in all modes and
+//! get none, `shared_among "a" "b"` gives a shortcut per mode. Tmux mode is
+//! entered with a prefix key like tmux itself, so its bindings become chords.
+
+use std::{iter::Peekable, str::Chars, vec::IntoIter};
+
+use crate::{
+    importers::{words, Imported},
+    shortcuts::{Key, KeyCombo, KeyStroke, Modifiers, Shortcut},
+};
+
+const TMUX_MODE: &str = "tmux";
+
+/// A binding as read, turned into a shortcut once the tmux prefix is known.
+struct Binding {
+    context: Option<String>,
+    stroke: KeyStroke,
+    description: String,
+    enters_tmux_mode: bool,
+}
+
+pub fn parse(text: &str, imported: &mut Imported) -> Result<(), String> {
+    let document = parse_document(text)?;
+    let mut bindings = Vec::new();
+
+    for keybinds in document.iter().filter(|node| node.name == "keybinds") {
+        for mode in &keybinds.children {
+            let contexts: Vec<Option<String>> = match mode.name.as_str() {
+                "shared" => vec![None],
+                "shared_except" => {
+                    let except = mode.args.join(", ").to_lowercase();
+                    vec![Some(format!("all except {except}"))]
+                }
+                "shared_among" => mode.args.iter().map(|m| Some(m.to_lowercase())).collect(),
+                // only undoes default bindings, which aren't imported anyway
+                "unbind" => continue,
+                name => vec![Some(name.to_lowercase())],
+            };
+
+            for bind in mode.children.iter().filter(|node| node.name == "bind") {
+                let description = describe(&bind.children);
+                let enters_tmux_mode = bind.children.iter().any(|action| {
+                    action.name == "SwitchToMode"
+                        && action
+                            .args
+                            .first()
+                            .is_some_and(|m| m.eq_ignore_ascii_case(TMUX_MODE))
+                });
+
+                for key in &bind.args {
+                    let Some(stroke) = parse_key(key) else {
+                        imported.skip(format!("`{key}` in {}: unknown key", mode.name));
+                        continue;
+                    };
+                    if description.is_empty() {
+                        imported.skip(format!("`{key}` in {}: no actions", mode.name));
+                        continue;
+                    }
+                    for context in &contexts {
+                        bindings.push(Binding {
+                            context: context.clone(),
+                            stroke,
+                            description: description.clone(),
+                            enters_tmux_mode,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let prefix = bindings
+        .iter()
+        .find(|binding| binding.enters_tmux_mode && binding.context.as_deref() != Some(TMUX_MODE))
+        .map(|binding| binding.stroke);
+
+    for binding in bindings {
+        let strokes = match prefix {
+            Some(prefix) if binding.context.as_deref() == Some(TMUX_MODE) => {
+                vec![prefix, binding.stroke]
+            }
+            _ => vec![binding.stroke],
+        };
+        let mut shortcut = Shortcut::from(KeyCombo::new(strokes), binding.description);
+        shortcut.set_context(binding.context.as_deref().unwrap_or_default());
+        imported.push(shortcut);
+    }
+
+    Ok(())
+}
+
+/// Describes the actions of a binding: `NewPane; SwitchToMode "Normal";`
+/// becomes "new pane". Going back to normal mode after the real action
+/// goes without saying.
+fn describe(actions: &[Node]) -> String {
+    let is_back_to_normal = |action: &Node| {
+        action.name == "SwitchToMode"
+            && action
+                .args
+                .first()
+                .is_some_and(|m| m.eq_ignore_ascii_case("normal"))
+    };
+    let only_mode_switch = actions.iter().all(is_back_to_normal);
+
+    actions
+        .iter()
+        .filter(|action| only_mode_switch || !is_back_to_normal(action))
+        .map(|action| {
+            let mut description = words(&action.name);
+            for arg in &action.args {
+                description.push(' ');
+                description.push_str(arg);
+            }
+            description
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parses a key as Zellij writes it: `Ctrl g`, `Alt Shift Left`, `F1`.
+fn parse_key(text: &str) -> Option<KeyStroke> {
+    let mut parts: Vec<&str> = text.split_whitespace().collect();
+    let key = parts.pop()?;
+
+    let mut modifiers = Modifiers::NONE;
+    for modifier in parts {
+        match modifier.to_lowercase().as_str() {
+            "ctrl" => modifiers.ctrl = true,
+            "alt" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            "super" => modifiers.super_key = true,
+            _ => return None,
+        }
+    }
+
+    let mut chars = key.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) => Key::Char(c),
+        _ => match key.to_lowercase().as_str() {
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            "tab" => Key::Tab,
+            "backtab" => {
+                modifiers.shift = true;
+                Key::Tab
+            }
+            "esc" => Key::Esc,
+            "enter" => Key::Enter,
+            "backspace" => Key::Backspace,
+            "delete" => Key::Delete,
+            "insert" => Key::Insert,
+            "space" => Key::Space,
+            lower => {
+                let number = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+                if !(1..=24).contains(&number) {
+                    return None;
+                }
+                Key::F(number)
+            }
+        },
+    };
+
+    Some(KeyStroke::new(modifiers, key))
+}
+
+/// A KDL node with its arguments and children. Properties and type
+/// annotations aren't needed for keybindings and are dropped.
+struct Node {
+    name: String,
+    args: Vec<String>,
+    children: Vec<Node>,
+}
+
+enum Token {
+    /// A bare identifier, a string or a number.
+    Value(String),
+    /// The `name=` of a property, followed by its value.
+    Property,
+    Open,
+    Close,
+    /// A line break or `;`.
+    End,
+    /// `/-`, which comments out the next node, argument or children block.
+    SlashDash,
+}
+
+fn parse_document(text: &str) -> Result<Vec<Node>, String> {
+    let mut tokens = tokenize(text)?.into_iter().peekable();
+    parse_nodes(&mut tokens, false)
+}
+
+fn parse_nodes(tokens: &mut Peekable<IntoIter<Token>>, nested: bool) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+    loop {
+        match tokens.next() {
+            None if nested => return Err("a `{` is never closed".into()),
+            None => return Ok(nodes),
+            Some(Token::Close) if nested => return Ok(nodes),
+            Some(Token::Close) => return Err("unexpected `}`".into()),
+            Some(Token::End) => {}
+            Some(Token::SlashDash) => {
+                if let Some(Token::Value(name)) = tokens.next() {
+                    parse_node(name, tokens)?;
+                }
+            }
+            Some(Token::Value(name)) => nodes.push(parse_node(name, tokens)?),
+            Some(Token::Property | Token::Open) => return Err("expected a node name".into()),
+        }
+    }
+}
+
+fn parse_node(name: String, tokens: &mut Peekable<IntoIter<Token>>) -> Result<Node, String> {
+    let mut node = Node {
+        name,
+        args: Vec::new(),
+        children: Vec::new(),
+    };
+
+    loop {
+        // the closing brace of the parent ends this node too
+        if matches!(tokens.peek(), None | Some(Token::Close)) {
+            return Ok(node);
+        }
+        match tokens.next() {
+            Some(Token::End) | None => return Ok(node),
+            Some(Token::Value(value)) => node.args.push(value),
+            Some(Token::Property) => {
+                tokens.next();
+            }
+            Some(Token::Open) => node.children.extend(parse_nodes(tokens, true)?),
+            Some(Token::SlashDash) => match tokens.next() {
+                Some(Token::Open) => {
+                    parse_nodes(tokens, true)?;
+                }
+                Some(Token::Property) => {
+                    tokens.next();
+                }
+                _ => {}
+            },
+            Some(Token::Close) => unreachable!("peeked above"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                line += 1;
+                tokens.push(Token::End);
+            }
+            ';' => tokens.push(Token::End),
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            // a line continuation
+            '\\' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                chars.next();
+                line += 1;
+            }
+            '/' => match chars.next() {
+                Some('/') => while chars.next_if(|&c| c != '\n').is_some() {},
+                Some('*') => line += skip_block_comment(&mut chars),
+                Some('-') => tokens.push(Token::SlashDash),
+                _ => return Err(format!("line {line}: unexpected `/`")),
+            },
+            // a type annotation such as `(u8)`
+            '(' => while chars.next_if(|&c| c != ')').is_some() {},
+            ')' => {}
+            '"' => {
+                let value = read_string(&mut chars)
+                    .ok_or_else(|| format!("line {line}: a string is never closed"))?;
+                line += value.matches('\n').count();
+                push_value(&mut tokens, value, &mut chars);
+            }
+            'r' if matches!(chars.peek(), Some('"' | '#')) => {
+                let value = read_raw_string(&mut chars)
+                    .ok_or_else(|| format!("line {line}: a raw string is never closed"))?;
+                line += value.matches('\n').count();
+                push_value(&mut tokens, value, &mut chars);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut value = String::from(c);
+                while let Some(c) = chars.next_if(|&c| !is_delimiter(c)) {
+                    value.push(c);
+                }
+                push_value(&mut tokens, value, &mut chars);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "{}();=\"\\/".contains(c)
+}
+
+/// Pushes a value, or a property if it's followed by `=`.
+fn push_value(tokens: &mut Vec<Token>, value: String, chars: &mut Peekable<Chars>) {
+    if chars.next_if_eq(&'=').is_some() {
+        tokens.push(Token::Property);
+    } else {
+        tokens.push(Token::Value(value));
+    }
+}
+
+/// Reads a string after its opening quote, `None` if it doesn't end.
+fn read_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'u' => {
+                    chars.next_if_eq(&'{');
+                    let mut hex = String::new();
+                    while let Some(c) = chars.next_if(|&c| c != '}') {
+                        hex.push(c);
+                    }
+                    chars.next();
+                    value.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+                }
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+/// Reads `r"..."` or `r#"..."#` after the `r`.
+fn read_raw_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut hashes = 0;
+    while chars.next_if_eq(&'#').is_some() {
+        hashes += 1;
+    }
+    chars.next_if_eq(&'"')?;
+
+    let closing = format!("\"{}", "#".repeat(hashes));
+    let mut value = String::new();
+    loop {
+        value.push(chars.next()?);
+        if value.ends_with(&closing) {
+            value.truncate(value.len() - closing.len());
+            return Some(value);
+        }
+    }
+}
+
+/// Skips a `/* */` comment, which may be nested, and returns how many lines
+/// it spanned.
+fn skip_block_comment(chars: &mut Peekable<Chars>) -> usize {
+    let mut depth = 1;
+    let mut lines = 0;
+    while depth > 0 {
+        match chars.next() {
+            None => break,
+            Some('\n') => lines += 1,
+            Some('*') if chars.next_if_eq(&'/').is_some() => depth -= 1,
+            Some('/') if chars.next_if_eq(&'*').is_some() => depth += 1,
+            Some(_) => {}
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported).unwrap();
+        imported
+    }
+
+    #[test]
+    fn reads_modes_as_contexts() {
+        let imported = import(
+            r#"
+            keybinds clear-defaults=true {
+                normal {
+                    bind "Alt n" { NewPane; }
+                }
+                pane {
+                    // back to normal goes without saying
+                    bind "x" { CloseFocus; SwitchToMode "Normal"; }
+                    bind "Esc" { SwitchToMode "Normal"; }
+                }
+                shared_except "locked" {
+                    bind "Ctrl g" "Ctrl l" { SwitchToMode "Locked"; }
+                }
+                shared {
+                    bind "Alt Left" { MoveFocusOrTab "Left"; }
+                }
+            }"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Alt+N = new pane [normal]",
+                "x = close focus [pane]",
+                "Esc = switch to mode Normal [pane]",
+                "Ctrl+G = switch to mode Locked [all except locked]",
+                "Ctrl+L = switch to mode Locked [all except locked]",
+                "Alt+Left = move focus or tab Left",
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_among_gives_one_per_mode() {
+        let imported = import(
+            r#"keybinds {
+                shared_among "pane" "tab" { bind "Ctrl p" { SwitchToMode "Normal"; } }
+            }"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+P = switch to mode Normal [pane]",
+                "Ctrl+P = switch to mode Normal [tab]",
+            ]
+        );
+    }
+
+    #[test]
+    fn tmux_mode_bindings_follow_the_prefix() {
+        let imported = import(
+            r#"keybinds {
+                normal { bind "Ctrl b" { SwitchToMode "Tmux"; } }
+                tmux { bind "c" { NewTab; SwitchToMode "Normal"; } }
+            }"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+B = switch to mode Tmux [normal]",
+                "Ctrl+B c = new tab [tmux]",
+            ]
+        );
+    }
+
+    #[test]
+    fn kdl_details() {
+        let imported = import(
+            r##"keybinds {
+                /- normal { bind "a" { NewPane; } }
+                normal {
+                    /* a block
+                       comment */
+                    bind (key)"F5" r#"F6"# { Run "cargo" "test" cwd="~"; }
+                    bind "Hyper z" { Quit; }
+                    bind "q" {}
+                }
+            }"##,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "F5 = run cargo test [normal]",
+                "F6 = run cargo test [normal]"
+            ]
+        );
+        assert_eq!(imported.skipped.len(), 2);
+    }
+
+    #[test]
+    fn rejects_broken_documents() {
+        let mut imported = Imported::default();
+        assert!(parse("keybinds {", &mut imported).is_err());
+        assert!(parse("}", &mut imported).is_err());
+        assert!(parse(r#"keybinds { bind "a }"#, &mut imported).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! zsh key bindings: the output of `bindkey -L`, or the `bindkey` lines of
+//! a `.zshrc`. `bindkey -M vicmd "^[[A" up-line-or-history` binds a widget,
+//! `bindkey -s` types a string.
+//!
+//! The emacs, viins and vicmd keymaps become the emacs, vi-insert and
+//! vi-command contexts, the same as for readline. `main` is whichever of
+//! them `bindkey -e`, `-v` or `-A` last selected.
+
+use crate::importers::{
+    logical_lines,
+    readline::{bind, unescape, Action},
+    strip_comment, Imported,
+};
+
+pub fn parse(text: &str, imported: &mut Imported) {
+    let mut main = "emacs".to_owned();
+
+    for line in logical_lines(text) {
+        let line = strip_comment(&line).trim();
+        let words = shell_words(line);
+        let Some((command, args)) = words.split_first() else {
+            continue;
+        };
+        if command != "bindkey" {
+            continue;
+        }
+
+        let mut keymap: Option<String> = None;
+        let mut string = false;
+        let mut other = false;
+        let mut args = args.iter();
+        let mut positional = Vec::new();
+
+        while let Some(arg) = args.next() {
+            let Some(flags) = arg.strip_prefix('-').filter(|_| positional.is_empty()) else {
+                positional.push(arg.as_str());
+                continue;
+            };
+            for flag in flags.chars() {
+                match flag {
+                    'M' => keymap = args.next().cloned(),
+                    'a' => keymap = Some("vicmd".into()),
+                    'e' | 'v' => {
+                        main = if flag == 'e' { "emacs" } else { "viins" }.into();
+                        keymap = Some(main.clone());
+                    }
+                    's' => string = true,
+                    // aliases are only interesting when they pick the main keymap
+                    'A' => {
+                        if let (Some(old), Some(new)) = (args.next(), args.next()) {
+                            if new == "main" {
+                                main = old.clone();
+                            }
+                        }
+                    }
+                    // ranges of self-insert, removals, listings, keymap management
+                    _ => other = true,
+                }
+            }
+        }
+
+        let ([sequence, value], false) = (&positional[..], other) else {
+            continue;
+        };
+        let keymap = keymap
+            .filter(|keymap| keymap != "main")
+            .unwrap_or_else(|| main.clone());
+        // `"${terminfo[kcuu1]}"` and the like only have a value in the shell
+        if sequence.starts_with('$') || sequence.contains("${") {
+            imported.skip(format!("`{sequence}`: depends on shell variables"));
+            continue;
+        }
+
+        let action = if string {
+            Action::Macro(value)
+        } else {
+            Action::Function(value)
+        };
+        bind(
+            sequence,
+            unescape(sequence, true),
+            action,
+            context(&keymap),
+            imported,
+        );
+    }
+}
+
+/// The context for a zsh keymap, named like readline's where there's one.
+fn context(keymap: &str) -> &str {
+    match keymap {
+        "emacs" => "emacs",
+        "viins" => "vi-insert",
+        "vicmd" => "vi-command",
+        other => other,
+    }
+}
+
+/// Splits a command line into words the way zsh does, leaving backslash
+/// escapes that quotes don't remove for `bindkey` to decode: `"\e[A"` is
+/// still `\e[A`.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if quote.is_none() && c.is_whitespace() {
+            words.extend(word.take());
+            continue;
+        }
+        let word = word.get_or_insert_with(String::new);
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.push(c),
+            (Some(_), '\\') => match chars.next() {
+                Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c),
+                Some(c) => {
+                    word.push('\\');
+                    word.push(c);
+                }
+                None => word.push('\\'),
+            },
+            (None, '\\') => word.extend(chars.next()),
+            (_, c) => word.push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Imported {
+        let mut imported = Imported::default();
+        parse(text, &mut imported);
+        imported
+    }
+
+    #[test]
+    fn reads_bindkey_lines() {
+        let imported = import(
+            r#"# history
+            bindkey '^R' history-incremental-search-backward
+            bindkey "\e[1;5D" backward-word # words
+            bindkey -M vicmd k up-line-or-history
+            bindkey -a j down-line-or-history
+            bindkey -s '^Xg' 'git status\n'
+            bindkey '^I' \
+                expand-or-complete
+            alias ll='ls -l'"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+R = search history backwards [emacs]",
+                "Ctrl+Left = backward word [emacs]",
+                "k = previous line or command in history [vi-command]",
+                "j = next line or command in history [vi-command]",
+                "Ctrl+X g = types git status\\n [emacs]",
+                "Tab = complete [emacs]",
+            ]
+        );
+    }
+
+    #[test]
+    fn main_follows_the_selected_keymap() {
+        let imported = import(
+            r#"bindkey -v
+            bindkey '^A' beginning-of-line
+            bindkey -M main '^E' end-of-line
+            bindkey -A emacs main
+            bindkey '^K' kill-line
+            bindkey -M menuselect '^M' accept-line"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+A = beginning of line [vi-insert]",
+                "Ctrl+E = end of line [vi-insert]",
+                "Ctrl+K = cut to end of line [emacs]",
+                "Enter = run the command [menuselect]",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_bindkey_listings() {
+        let imported = import(
+            r#"bindkey "^@" set-mark-command
+            bindkey "^[[3~" delete-char
+            bindkey -R " "-"~" self-insert
+            bindkey "a" self-insert"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            [
+                "Ctrl+Space = set mark [emacs]",
+                "Delete = delete char [emacs]",
+            ]
+        );
+    }
+
+    #[test]
+    fn shell_variables_are_skipped() {
+        let imported = import(
+            r#"bindkey "${terminfo[kcuu1]}" up-line-or-search
+            bindkey -r '^S'
+            bindkey '\M-\C-x' kill-word"#,
+        );
+        assert_eq!(
+            imported.lines(),
+            ["Ctrl+Alt+X = cut to end of word [emacs]"]
+        );
+        assert_eq!(imported.skipped.len(), 1);
+    }
+
+    #[test]
+    fn splits_shell_words() {
+        assert_eq!(
+            shell_words(r#"bindkey -s '\e' "a\"b\\c\d" x\ y"#),
+            ["bindkey", "-s", r"\e", r#"a"b\c\d"#, "x y"]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{KeyEvent, KeyEventKind};
+
+use crate::shortcuts::{Key, KeyCombo, KeyStroke};
+
+/// How long to wait for another stroke before a recorded sequence is done.
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Records key presses as [`KeyStroke`]s, for entering a shortcut by pressing it.
+///
+/// A sequence like `Ctrl+K Ctrl+C` is recorded one stroke at a time. It is
+/// done when no stroke arrived for the sequence timeout, see [`Self::is_timed_out`],
+/// or when the owner decides so, e.g. on a confirm key.
+pub struct KeyCapture {
+    strokes: Vec<KeyStroke>,
+    last_press: Option<Instant>,
+    timeout: Duration,
+}
+
+impl KeyCapture {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            strokes: Vec::new(),
+            last_press: None,
+            timeout,
+        }
+    }
+
+    /// Records the key press, returns `false` if it can't be part of a shortcut.
+    ///
+    /// Key repeats from holding a key down are ignored.
+    pub fn push(&mut self, key_event: &KeyEvent) -> bool {
+        if key_event.kind == KeyEventKind::Repeat {
+            return false;
+        }
+
+        let Some(stroke) = KeyStroke::from_key_event(key_event) else {
+            return false;
+        };
+
+        self.strokes.push(stroke);
+        self.last_press = Some(Instant::now());
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strokes.is_empty()
+    }
+
+    pub fn strokes(&self) -> &[KeyStroke] {
+        &self.strokes
+    }
+
+    /// Forgets the recorded strokes, to capture the next sequence.
+    pub fn clear(&mut self) {
+        self.strokes.clear();
+        self.last_press = None;
+    }
+
+    /// Whether at least one stroke was recorded and the timeout has passed since the last one.
+    pub fn is_timed_out(&self) -> bool {
+        self.last_press
+            .is_some_and(|last_press| last_press.elapsed() >= self.timeout)
+    }
+
+    /// The strokes recorded so far as a combo, `None` if nothing was recorded.
+    pub fn combo(&self) -> Option<KeyCombo> {
+        if self.strokes.is_empty() {
+            None
+        } else {
+            Some(KeyCombo::new(self.strokes.clone()))
+        }
+    }
+}
+
+impl Default for KeyCapture {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEQUENCE_TIMEOUT)
+    }
+}
+
+/// Whether a terminal can report every stroke of `combo` as its own key press.
+///
+/// Without the kitty keyboard protocol (`keyboard_enhancement`), terminals
+/// send many combos as the same bytes as another key: Ctrl+I is Tab, Ctrl+M
+/// is Enter, Ctrl+Shift+P is Ctrl+P, and Super never arrives at all.
+pub fn can_deliver(combo: &KeyCombo, keyboard_enhancement: bool) -> bool {
+    combo.is_parsed()
+        && combo
+            .strokes()
+            .iter()
+            .all(|stroke| can_deliver_stroke(stroke, keyboard_enhancement))
+}
+
+fn can_deliver_stroke(stroke: &KeyStroke, keyboard_enhancement: bool) -> bool {
+    let modifiers = stroke.modifiers;
+
+    // the shift is folded into the character, `Shift+1` arrives as `!`
+    if let Key::Char(c) = stroke.key {
+        if modifiers.shift && !c.is_alphabetic() {
+            return false;
+        }
+    }
+    if keyboard_enhancement {
+        return true;
+    }
+    if modifiers.super_key {
+        return false;
+    }
+
+    match stroke.key {
+        Key::Char(c) if modifiers.ctrl => {
+            // control characters collide with Tab, Enter, Backspace and Esc
+            let letter = c.is_ascii_alphabetic() && !matches!(c, 'i' | 'm' | 'h');
+            !modifiers.shift && (letter || matches!(c, '4'..='7'))
+        }
+        Key::Char(_) => true,
+        Key::Space => !modifiers.shift,
+        Key::Enter | Key::Esc | Key::Backspace => modifiers.is_empty(),
+        Key::Tab => !modifiers.ctrl && !modifiers.alt,
+        Key::F(n) => n <= 12,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+
+    use super::*;
+
+    fn press(capture: &mut KeyCapture, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        capture.push(&KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn records_a_sequence() {
+        let mut capture = KeyCapture::default();
+        assert!(capture.is_empty());
+        assert_eq!(capture.combo(), None);
+
+        assert!(press(
+            &mut capture,
+            KeyCode::Char('k'),
+            KeyModifiers::CONTROL
+        ));
+        assert!(press(
+            &mut capture,
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL
+        ));
+        // media keys and lone modifiers aren't strokes
+        assert!(!press(&mut capture, KeyCode::CapsLock, KeyModifiers::NONE));
+        assert_eq!(capture.combo(), Some("Ctrl+K Ctrl+C".parse().unwrap()));
+
+        capture.clear();
+        assert!(capture.is_empty());
+        assert!(!capture.is_timed_out());
+    }
+
+    #[test]
+    fn key_repeats_are_ignored() {
+        let mut capture = KeyCapture::default();
+        let mut event = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert!(capture.push(&event));
+        event.kind = KeyEventKind::Repeat;
+        assert!(!capture.push(&event));
+        assert_eq!(capture.strokes().len(), 1);
+    }
+
+    #[test]
+    fn sequence_times_out_after_the_last_stroke() {
+        let mut capture = KeyCapture::default();
+        // nothing recorded, nothing to finish
+        assert!(!capture.is_timed_out());
+
+        press(&mut capture, KeyCode::Char('g'), KeyModifiers::NONE);
+        assert!(!capture.is_timed_out());
+
+        let now = Instant::now();
+        capture.last_press = Some(now - Duration::from_millis(1499));
+        assert!(!capture.is_timed_out());
+        capture.last_press = Some(now - DEFAULT_SEQUENCE_TIMEOUT);
+        assert!(capture.is_timed_out());
+
+        // another stroke restarts the wait
+        press(&mut capture, KeyCode::Char('g'), KeyModifiers::NONE);
+        assert!(!capture.is_timed_out());
+    }
+
+    fn deliverable(text: &str, keyboard_enhancement: bool) -> bool {
+        can_deliver(&text.parse().unwrap(), keyboard_enhancement)
+    }
+
+    #[test]
+    fn plain_terminals_deliver_distinct_keys() {
+        for text in [
+            "Ctrl+S",
+            "Ctrl+K Ctrl+C",
+            "Alt+F",
+            "Shift+G",
+            "F5",
+            "Shift+Tab",
+            "g g",
+        ] {
+            assert!(deliverable(text, false), "{text}");
+        }
+    }
+
+    #[test]
+    fn plain_terminals_merge_some_keys() {
+        for text in [
+            // the same bytes as Tab, Enter and Backspace
+            "Ctrl+I",
+            "Ctrl+M",
+            "Ctrl+H",
+            // arrives as Ctrl+P
+            "Ctrl+Shift+P",
+            "Ctrl+Enter",
+            "Ctrl+Tab",
+            "F13",
+            "Super+S",
+            // a single bad stroke spoils the sequence
+            "Ctrl+K Super+S",
+        ] {
+            assert!(!deliverable(text, false), "{text}");
+        }
+    }
+
+    #[test]
+    fn keyboard_enhancement_delivers_modified_keys() {
+        for text in [
+            "Ctrl+I",
+            "Ctrl+Shift+P",
+            "Ctrl+Enter",
+            "Super+S",
+            "Ctrl+K Super+S",
+        ] {
+            assert!(deliverable(text, true), "{text}");
+        }
+        // the shift is still folded into the character it types
+        assert!(!deliverable("Shift+1", true));
+        assert!(!deliverable("Ctrl+Shift+1", false));
+    }
+
+    #[test]
+    fn unparsed_combos_are_never_delivered() {
+        let combo: KeyCombo = serde_json::from_str(r#""Ctrl + scroll wheel""#).unwrap();
+        assert!(!combo.is_parsed());
+        assert!(!can_deliver(&combo, true));
+    }
+}
@@ -1,5 +1,10 @@
 mod app;
 mod app_state;
+mod commands;
+mod data_location;
+mod fuzzy;
+mod importers;
+mod key_capture;
 mod screen;
 mod screens;
 mod shortcuts;
@@ -7,37 +12,219 @@ mod style;
 mod utils;
 mod widgets;
 
-use std::{error::Error, io};
+use std::{
+    env,
+    error::Error,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+    time::Duration,
+};
 
 use ratatui::{
     crossterm::{
+        event::{
+            DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags,
+            PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        },
         execute,
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+        terminal::{
+            disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+            LeaveAlternateScreen,
+        },
     },
     prelude::CrosstermBackend,
     Terminal,
 };
 
-use crate::{app::App, app_state::AppState, shortcuts::Shortcuts};
+use crate::{
+    app::App,
+    app_state::AppState,
+    commands::{CommandError, Invocation},
+    data_location::LocationOptions,
+    screens::QuizScreen,
+    shortcuts::{LoadError, Shortcuts},
+};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (location, invocation) = match commands::parse(&args) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("shotty: {err}");
+            return err.exit_code();
+        }
+    };
+
+    if let Invocation::Help = invocation {
+        return match writeln!(io::stdout(), "{}", commands::USAGE) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => report(err.into()),
+        };
+    }
+
+    let shortcuts = match locate_shortcuts(&location)
+        .and_then(load_shortcuts)
+        .and_then(configure)
+    {
+        Ok(shortcuts) => shortcuts,
+        Err(err) => {
+            eprintln!("shotty: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut app_state = AppState::new(shortcuts);
+
+    let app = match invocation {
+        Invocation::Run(command) => {
+            return match command.execute(&mut app_state) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(err) => report(err),
+            }
+        }
+        Invocation::Quiz => {
+            let quiz = QuizScreen::new(&app_state);
+            App::with_screen(app_state, Box::new(quiz))
+        }
+        Invocation::Tui | Invocation::Help => App::new(app_state),
+    };
+
+    match run_tui(app) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("shotty: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints why a command failed, unless it was only stopped from writing by a
+/// closed pipe as `shotty list | head` does, and returns its exit code.
+fn report(err: CommandError) -> ExitCode {
+    if !err.is_quiet() {
+        eprintln!("shotty: {err}");
+    }
+    err.exit_code()
+}
+
+/// Finds the shortcuts file, first moving over one left in the working
+/// directory by older versions when using the default location.
+fn locate_shortcuts(location: &LocationOptions) -> Result<PathBuf, String> {
+    let path = data_location::resolve(location)?;
+    if !data_location::is_default(location) {
+        return Ok(path);
+    }
+
+    match data_location::migrate_legacy_file(&path) {
+        Ok(Some(migration)) => eprintln!("shotty: {migration}"),
+        Ok(None) => {}
+        Err(err) => {
+            return Err(format!(
+                "could not move shortcuts to {}: {err}",
+                path.display()
+            ))
+        }
+    }
+    Ok(path)
+}
 
-fn main() -> Result<(), Box<dyn Error>> {
+/// Loads the shortcuts file, starting with an empty one if it doesn't exist yet.
+fn load_shortcuts(path: PathBuf) -> Result<Shortcuts, String> {
+    match Shortcuts::load_from_file(path.clone()) {
+        Ok((shortcuts, migration)) => {
+            if let Some(migration) = migration {
+                eprintln!(
+                    "shotty: upgraded {} from format {} to {}, the original was kept as {}",
+                    path.display(),
+                    migration.from,
+                    shortcuts::FILE_VERSION,
+                    migration.original.display()
+                );
+            }
+            Ok(shortcuts)
+        }
+        Err(LoadError::Io(err)) if err.kind() == io::ErrorKind::NotFound => {
+            let mut shortcuts = Shortcuts::new();
+            shortcuts.set_save_path(path);
+            Ok(shortcuts)
+        }
+        Err(err) => Err(format!("could not load {}: {err}", path.display())),
+    }
+}
+
+/// Applies the settings from the environment:
+///
+/// - `$SHOTTY_BACKUPS`, the number of backups to keep
+/// - `$SHOTTY_HALF_LIFE_DAYS`, the half-life of lookups in the frecency ranking
+/// - `$SHOTTY_UNDO_DEPTH`, how many changes can be undone
+/// - `$SHOTTY_UNDO_JOURNAL`, whether the undo history survives restarts
+fn configure(mut shortcuts: Shortcuts) -> Result<Shortcuts, String> {
+    if let Some(count) = env_number::<usize>("SHOTTY_BACKUPS")? {
+        shortcuts.set_backup_count(count);
+    }
+    if let Some(days) = env_number::<f64>("SHOTTY_HALF_LIFE_DAYS")? {
+        if !(days > 0.0 && days.is_finite()) {
+            return Err(format!(
+                "SHOTTY_HALF_LIFE_DAYS must be positive, got {days}"
+            ));
+        }
+        shortcuts.set_half_life(Duration::from_secs_f64(days * 24.0 * 60.0 * 60.0));
+    }
+    if let Some(depth) = env_number::<usize>("SHOTTY_UNDO_DEPTH")? {
+        shortcuts.set_undo_depth(depth);
+    }
+    let journal = env::var("SHOTTY_UNDO_JOURNAL").unwrap_or_default();
+    if matches!(journal.to_lowercase().as_str(), "1" | "true" | "yes" | "on") {
+        shortcuts
+            .enable_journal()
+            .map_err(|err| format!("could not read the undo journal: {err}"))?;
+    }
+    Ok(shortcuts)
+}
+
+/// The number in environment variable `name`, `None` if it is unset or empty.
+fn env_number<T: FromStr>(name: &str) -> Result<Option<T>, String> {
+    match env::var_os(name).filter(|value| !value.is_empty()) {
+        None => Ok(None),
+        Some(value) => value
+            .to_str()
+            .and_then(|value| value.parse().ok())
+            .map(Some)
+            .ok_or_else(|| format!("{name} must be a number, got {value:?}")),
+    }
+}
+
+fn run_tui(mut app: App) -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+
+    // lets terminals that speak the kitty keyboard protocol report keys like
+    // Ctrl+I and Tab, or Ctrl+M and Enter, as different keys
+    let keyboard_enhancement = supports_keyboard_enhancement().unwrap_or(false);
+    app.state.keyboard_enhancement = keyboard_enhancement;
+    if keyboard_enhancement {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let shortcuts = match Shortcuts::load_from_file("shortcuts.json".into()) {
-        Ok(shortcuts) => shortcuts,
-        Err(_) => Shortcuts::new(),
-    };
-    let app_state = AppState::new(shortcuts);
-    let mut app = App::new(app_state);
     let _ = app.run(&mut terminal);
 
+    if keyboard_enhancement {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
 
     Ok(())
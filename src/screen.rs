@@ -1,11 +1,25 @@
 #![allow(dead_code)]
-use ratatui::{crossterm::event::KeyEvent, Frame};
+use ratatui::{
+    crossterm::event::{KeyEvent, MouseEvent},
+    Frame,
+};
 
 use crate::app_state::AppState;
 
 pub trait Screen {
     fn draw(&mut self, frame: &mut Frame, state: &AppState);
     fn handle_event(&mut self, key_event: KeyEvent, state: &mut AppState) -> ScreenCommand;
+
+    /// Mouse clicks and scrolling, ignored unless a screen opts in.
+    fn handle_mouse(&mut self, _mouse_event: MouseEvent, _state: &mut AppState) -> ScreenCommand {
+        ScreenCommand::None
+    }
+
+    /// Called when the screen on top of this one closed and this one is on top again.
+    fn on_resume(&mut self, _state: &mut AppState) {}
+
+    /// Called regularly while no key is pressed, for screens with timeouts.
+    fn on_tick(&mut self, _state: &mut AppState) {}
 }
 
 pub enum ScreenCommand {
@@ -0,0 +1,194 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    layout::Position,
+    prelude::*,
+    style::{palette::tailwind, Color},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::{
+    app_state::AppState,
+    screen::{Screen, ScreenCommand},
+    utils,
+    widgets::{button::Button, Widget},
+};
+
+/// Runs when the user confirms. An error keeps the dialog open and shows the message,
+/// and confirming again runs the action again.
+pub type ConfirmAction = Box<dyn FnMut(&mut AppState) -> Result<(), String>>;
+
+const BG_COLOR: Color = tailwind::GRAY.c700;
+const ERROR_COLOR: Color = tailwind::RED.c400;
+
+/// Height of the popup: two lines of message, the error line, the buttons and the border.
+const POPUP_HEIGHT: u16 = 2 + 1 + 3 + 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Choice {
+    Yes,
+    No,
+}
+
+/// A modal Yes/No dialog guarding a destructive action.
+///
+/// `y`/`n`, Left/Right/Tab with Enter, Esc and mouse clicks on the buttons
+/// all work. "No" is focused first so a stray Enter doesn't confirm.
+pub struct ConfirmScreen {
+    title: String,
+    message: String,
+    yes_button: Button,
+    no_button: Button,
+    focused: Choice,
+
+    on_confirm: ConfirmAction,
+
+    /// Why the action failed, if it did.
+    error: Option<String>,
+
+    /// Where the buttons were drawn last, for mouse clicks.
+    yes_area: Rect,
+    no_area: Rect,
+}
+
+impl ConfirmScreen {
+    pub fn new(title: &str, message: &str, on_confirm: ConfirmAction) -> Self {
+        Self {
+            title: title.to_owned(),
+            message: message.to_owned(),
+            yes_button: Button::new("Yes").color(tailwind::RED.c400),
+            no_button: Button::new("No"),
+            focused: Choice::No,
+            on_confirm,
+            error: None,
+            yes_area: Rect::default(),
+            no_area: Rect::default(),
+        }
+    }
+
+    /// Changes the button labels, e.g. to "Delete" and "Keep".
+    pub fn labels(mut self, yes: &str, no: &str) -> Self {
+        self.yes_button = Button::new(yes).color(tailwind::RED.c400);
+        self.no_button = Button::new(no);
+        self
+    }
+
+    fn choose(&mut self, choice: Choice, state: &mut AppState) -> ScreenCommand {
+        match choice {
+            Choice::No => ScreenCommand::Close,
+            Choice::Yes => match (self.on_confirm)(state) {
+                Ok(()) => ScreenCommand::Close,
+                Err(error) => {
+                    self.error = Some(error);
+                    self.focused = Choice::No;
+                    ScreenCommand::None
+                }
+            },
+        }
+    }
+}
+
+impl Screen for ConfirmScreen {
+    fn draw(&mut self, frame: &mut Frame, _state: &AppState) {
+        let area = utils::centered_rect_with_height(50, POPUP_HEIGHT, frame.area());
+
+        let title = Span::styled(
+            self.title.as_str(),
+            Style::default().add_modifier(Modifier::BOLD),
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .bg(BG_COLOR)
+            .title(title);
+
+        let inner = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [message, error, buttons] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ])
+        .areas(inner);
+
+        frame.render_widget(
+            Paragraph::new(self.message.as_str())
+                .centered()
+                .wrap(Wrap { trim: true }),
+            message,
+        );
+
+        if let Some(text) = &self.error {
+            frame.render_widget(
+                Paragraph::new(text.as_str()).fg(ERROR_COLOR).centered(),
+                error,
+            );
+        }
+
+        let [_, yes, no, _] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Min(0),
+        ])
+        .spacing(2)
+        .areas(buttons);
+
+        self.yes_button.set_focused(self.focused == Choice::Yes);
+        self.yes_button.render(yes, frame);
+        self.no_button.set_focused(self.focused == Choice::No);
+        self.no_button.render(no, frame);
+
+        self.yes_area = yes;
+        self.no_area = no;
+    }
+
+    fn handle_event(&mut self, key_event: KeyEvent, state: &mut AppState) -> ScreenCommand {
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => self.choose(Choice::Yes, state),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+                self.choose(Choice::No, state)
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.focused = match self.focused {
+                    Choice::Yes => Choice::No,
+                    Choice::No => Choice::Yes,
+                };
+                ScreenCommand::None
+            }
+            _ => {
+                let pressed = match self.focused {
+                    Choice::Yes => self.yes_button.handle_event(key_event),
+                    Choice::No => self.no_button.handle_event(key_event),
+                };
+                if pressed {
+                    self.choose(self.focused, state)
+                } else {
+                    ScreenCommand::None
+                }
+            }
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent, state: &mut AppState) -> ScreenCommand {
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let choice = if self.yes_area.contains(position) {
+            Choice::Yes
+        } else if self.no_area.contains(position) {
+            Choice::No
+        } else {
+            return ScreenCommand::None;
+        };
+
+        match mouse_event.kind {
+            MouseEventKind::Moved => {
+                self.focused = choice;
+                ScreenCommand::None
+            }
+            MouseEventKind::Down(MouseButton::Left) => self.choose(choice, state),
+            _ => ScreenCommand::None,
+        }
+    }
+}
@@ -0,0 +1,628 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    prelude::*,
+    style::palette::tailwind,
+    widgets::{Block, BorderType, Cell, Clear, Paragraph, Row, Table, Wrap},
+};
+
+use crate::{
+    app_state::AppState,
+    key_capture::{self, KeyCapture},
+    screen::{Screen, ScreenCommand},
+    shortcuts::{Key, KeyCombo, Shortcuts},
+};
+
+const BG_COLOR: Color = tailwind::SLATE.c950;
+const FG_COLOR: Color = tailwind::SLATE.c200;
+const MUTED_COLOR: Color = tailwind::SLATE.c400;
+const HIT_COLOR: Color = tailwind::EMERALD.c400;
+const MISS_COLOR: Color = tailwind::RED.c400;
+const BORDER_COLOR: Color = tailwind::AMBER.c400;
+
+/// Shortcuts per session.
+const SESSION_LENGTH: usize = 10;
+
+/// How long the verdict stays up before the next shortcut. A miss stays
+/// longer, to read the right combo.
+const HIT_PAUSE: Duration = Duration::from_millis(700);
+const MISS_PAUSE: Duration = Duration::from_millis(2000);
+
+const DRILL_KEYS: &str = "press the shortcut | (Esc) stop";
+const DONE_KEYS: &str = "(any key) close";
+
+/// One attempt of the session.
+struct Attempt {
+    index: usize,
+    /// Time until the right combo was complete, `None` for a miss.
+    time: Option<Duration>,
+    /// What was pressed instead, for misses.
+    pressed: Option<KeyCombo>,
+    personal_best: bool,
+}
+
+enum Phase {
+    /// Waiting for the user to press the shortcut, timed from `started`.
+    Pressing {
+        started: Instant,
+    },
+    /// Showing whether the last attempt was right until `until`.
+    Verdict {
+        until: Instant,
+    },
+    Done,
+}
+
+/// Muscle memory practice: shows a description and times how long it takes
+/// to press the shortcut.
+///
+/// Shortcuts the terminal can't report, see [`key_capture::can_deliver`],
+/// are left out, and so are those starting with Esc, which stops the drill.
+/// Statistics are saved after every attempt.
+pub struct DrillScreen {
+    queue: Vec<usize>,
+    position: usize,
+    capture: KeyCapture,
+    phase: Phase,
+    attempts: Vec<Attempt>,
+
+    /// How many shortcuts were left out because they can't be drilled here.
+    excluded: usize,
+
+    error: Option<String>,
+}
+
+impl DrillScreen {
+    /// Drills up to [`SESSION_LENGTH`] of the shortcuts at `indexes`, the
+    /// least practiced and slowest first.
+    pub fn new(indexes: &[usize], state: &AppState) -> Self {
+        let shortcuts = state.shortcuts.get_all_shortcuts();
+        let (mut queue, excluded): (Vec<usize>, Vec<usize>) = indexes.iter().partition(|&&index| {
+            can_drill(shortcuts[index].get_key_combo(), state.keyboard_enhancement)
+        });
+
+        queue.sort_by_key(|&index| {
+            let stats = shortcuts[index].get_drill_stats();
+            (
+                stats.hits > 0,
+                stats.accuracy().map(|accuracy| (accuracy * 100.0) as u32),
+                std::cmp::Reverse(stats.average()),
+            )
+        });
+        queue.truncate(SESSION_LENGTH);
+
+        let phase = if queue.is_empty() {
+            Phase::Done
+        } else {
+            Phase::Pressing {
+                started: Instant::now(),
+            }
+        };
+
+        Self {
+            queue,
+            position: 0,
+            capture: KeyCapture::default(),
+            phase,
+            attempts: Vec::new(),
+            excluded: excluded.len(),
+            error: None,
+        }
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.queue.get(self.position).copied()
+    }
+
+    /// Compares what was pressed so far with the current shortcut, and
+    /// finishes the attempt once it is clearly right or wrong.
+    fn check(&mut self, state: &mut AppState) {
+        let (Some(index), Phase::Pressing { started }) = (self.current(), &self.phase) else {
+            return;
+        };
+        let elapsed = started.elapsed();
+
+        let target = state.shortcuts.get_all_shortcuts()[index].get_key_combo();
+        let pressed = self.capture.strokes();
+        if pressed == target.strokes() {
+            self.finish(index, Some(elapsed), state);
+        } else if !target.strokes().starts_with(pressed) {
+            self.finish(index, None, state);
+        }
+    }
+
+    fn finish(&mut self, index: usize, time: Option<Duration>, state: &mut AppState) {
+        let pressed = if time.is_none() {
+            self.capture.combo()
+        } else {
+            None
+        };
+        self.capture.clear();
+
+        let previous = state.shortcuts.record_drill(index, time);
+        // beating an earlier time, not just setting the first one
+        let best_ms = state.shortcuts.get_all_shortcuts()[index]
+            .get_drill_stats()
+            .best_ms;
+        let personal_best = previous
+            .is_some_and(|previous| previous.best_ms.is_some() && previous.best_ms != best_ms);
+
+        if let Err(err) = state.shortcuts.save() {
+            if let Some(previous) = previous {
+                state.shortcuts.set_drill_stats(index, previous);
+            }
+            self.error = Some(format!("Could not save statistics: {err}"));
+        }
+
+        self.attempts.push(Attempt {
+            index,
+            time,
+            pressed,
+            personal_best,
+        });
+        let pause = if time.is_some() {
+            HIT_PAUSE
+        } else {
+            MISS_PAUSE
+        };
+        self.phase = Phase::Verdict {
+            until: Instant::now() + pause,
+        };
+    }
+
+    fn next(&mut self) {
+        self.position += 1;
+        self.phase = if self.position < self.queue.len() {
+            Phase::Pressing {
+                started: Instant::now(),
+            }
+        } else {
+            Phase::Done
+        };
+    }
+
+    fn card_lines(&self, index: usize, shortcuts: &Shortcuts) -> Vec<Line<'static>> {
+        let shortcut = &shortcuts.get_all_shortcuts()[index];
+        let mut lines = vec![
+            Line::styled(shortcut.get_app().to_owned(), Style::new().fg(MUTED_COLOR)),
+            Line::default(),
+            Line::styled(
+                shortcut.get_description().to_owned(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::default(),
+        ];
+
+        match (&self.phase, self.attempts.last()) {
+            (Phase::Verdict { .. }, Some(attempt)) => match attempt.time {
+                Some(time) => {
+                    let mut verdict = format!("✓ {}", format_time(time));
+                    if attempt.personal_best {
+                        verdict.push_str("  new best!");
+                    }
+                    lines.push(Line::styled(verdict, Style::new().fg(HIT_COLOR)));
+                }
+                None => {
+                    let pressed = attempt
+                        .pressed
+                        .as_ref()
+                        .map_or_else(|| "nothing".to_owned(), KeyCombo::to_string);
+                    lines.push(Line::styled(
+                        format!("✗ {pressed}"),
+                        Style::new().fg(MISS_COLOR),
+                    ));
+                    lines.push(Line::styled(
+                        format!("it's {}", shortcut.get_key_combo()),
+                        Style::new().fg(HIT_COLOR).add_modifier(Modifier::BOLD),
+                    ));
+                }
+            },
+            _ => {
+                let pressed: Vec<String> = self
+                    .capture
+                    .strokes()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                lines.push(Line::styled(
+                    pressed.join(" "),
+                    Style::new().fg(MUTED_COLOR),
+                ));
+            }
+        }
+        lines
+    }
+
+    fn draw_summary(&self, frame: &mut Frame, area: Rect, shortcuts: &Shortcuts) {
+        let hits: Vec<Duration> = self
+            .attempts
+            .iter()
+            .filter_map(|attempt| attempt.time)
+            .collect();
+        let mut summary = if self.attempts.is_empty() {
+            "Nothing drilled.".to_owned()
+        } else {
+            let mut summary = format!("{} of {} right", hits.len(), self.attempts.len());
+            if !hits.is_empty() {
+                let average = hits.iter().sum::<Duration>() / hits.len() as u32;
+                summary.push_str(&format!(", {} on average", format_time(average)));
+            }
+            let bests = self
+                .attempts
+                .iter()
+                .filter(|attempt| attempt.personal_best)
+                .count();
+            if bests > 0 {
+                summary.push_str(&format!(", {bests} new personal best(s)"));
+            }
+            summary
+        };
+        if self.excluded > 0 {
+            summary.push_str(&format!(
+                "\n{} shortcut(s) left out, the terminal can't report them or they start with Esc",
+                self.excluded
+            ));
+        }
+
+        let [summary_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        frame.render_widget(
+            Paragraph::new(summary).centered().wrap(Wrap { trim: true }),
+            summary_area,
+        );
+
+        let header = [
+            "Shortcut",
+            "Description",
+            "Time",
+            "Best",
+            "Average",
+            "Accuracy",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::new().add_modifier(Modifier::BOLD));
+
+        let rows = self.attempts.iter().map(|attempt| {
+            let shortcut = &shortcuts.get_all_shortcuts()[attempt.index];
+            let stats = shortcut.get_drill_stats();
+            let (time, color) = match attempt.time {
+                Some(time) => (format_time(time), HIT_COLOR),
+                None => ("miss".to_owned(), MISS_COLOR),
+            };
+            Row::new(vec![
+                Cell::from(shortcut.get_key_combo().to_string()),
+                Cell::from(shortcut.get_description().to_owned()),
+                Cell::from(time).fg(color),
+                Cell::from(stats.best().map_or_else(String::new, format_time)),
+                Cell::from(stats.average().map_or_else(String::new, format_time)),
+                Cell::from(
+                    stats
+                        .accuracy()
+                        .map_or_else(String::new, |accuracy| format!("{:.0}%", accuracy * 100.0)),
+                ),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(16),
+                Constraint::Min(10),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .column_spacing(2);
+        frame.render_widget(table, table_area);
+    }
+}
+
+impl Screen for DrillScreen {
+    fn draw(&mut self, frame: &mut Frame, state: &AppState) {
+        let area = frame.area();
+
+        let title = match self.phase {
+            Phase::Done => " Drill ".to_owned(),
+            _ => format!(" Drill: {} of {} ", self.position + 1, self.queue.len()),
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .border_style(Style::new().fg(BORDER_COLOR))
+            .title(title.bold())
+            .style(Style::new().fg(FG_COLOR).bg(BG_COLOR));
+        let inner = block.inner(area).inner(Margin::new(2, 1));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [main_area, error_area, keys_area] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let keys = match (matches!(self.phase, Phase::Done), self.current()) {
+            (false, Some(index)) => {
+                let [_, card_area] =
+                    Layout::vertical([Constraint::Percentage(30), Constraint::Min(0)])
+                        .areas(main_area);
+                frame.render_widget(
+                    Paragraph::new(self.card_lines(index, &state.shortcuts))
+                        .centered()
+                        .wrap(Wrap { trim: true }),
+                    card_area,
+                );
+                DRILL_KEYS
+            }
+            _ => {
+                self.draw_summary(frame, main_area, &state.shortcuts);
+                DONE_KEYS
+            }
+        };
+
+        if let Some(error) = &self.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str()).fg(MISS_COLOR).centered(),
+                error_area,
+            );
+        }
+        frame.render_widget(Paragraph::new(keys).fg(MUTED_COLOR).centered(), keys_area);
+    }
+
+    fn handle_event(&mut self, key_event: KeyEvent, state: &mut AppState) -> ScreenCommand {
+        match self.phase {
+            Phase::Done => ScreenCommand::Close,
+            // don't let a late key count for the next shortcut
+            Phase::Verdict { .. } => ScreenCommand::None,
+            Phase::Pressing { .. } => {
+                if key_event.code == KeyCode::Esc
+                    && key_event.modifiers == KeyModifiers::NONE
+                    && self.capture.is_empty()
+                {
+                    self.phase = Phase::Done;
+                    return ScreenCommand::None;
+                }
+
+                if self.capture.push(&key_event) {
+                    self.check(state);
+                }
+                ScreenCommand::None
+            }
+        }
+    }
+
+    fn on_tick(&mut self, state: &mut AppState) {
+        match self.phase {
+            Phase::Verdict { until } if Instant::now() >= until => self.next(),
+            // the first strokes of a sequence were right but the rest never came
+            Phase::Pressing { .. } if self.capture.is_timed_out() => {
+                if let Some(index) = self.current() {
+                    self.finish(index, None, state);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether `combo` can be practiced: the terminal has to report it, and its
+/// first stroke can't be the Esc that stops the drill.
+fn can_drill(combo: &KeyCombo, keyboard_enhancement: bool) -> bool {
+    key_capture::can_deliver(combo, keyboard_enhancement)
+        && combo
+            .strokes()
+            .first()
+            .is_none_or(|stroke| stroke.key != Key::Esc || !stroke.modifiers.is_empty())
+}
+
+/// "840 ms", "1.25 s"
+fn format_time(time: Duration) -> String {
+    if time < Duration::from_secs(1) {
+        format!("{} ms", time.as_millis())
+    } else {
+        format!("{:.2} s", time.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::Shortcut;
+
+    fn state(combos: &[&str], keyboard_enhancement: bool) -> AppState {
+        let mut shortcuts = Shortcuts::new();
+        for combo in combos {
+            shortcuts.add_shortcut(Shortcut::from(combo.parse().unwrap(), combo.to_string()));
+        }
+        let mut state = AppState::new(shortcuts);
+        state.keyboard_enhancement = keyboard_enhancement;
+        state
+    }
+
+    fn drill(state: &AppState) -> DrillScreen {
+        let indexes: Vec<usize> = (0..state.shortcuts.get_all_shortcuts().len()).collect();
+        DrillScreen::new(&indexes, state)
+    }
+
+    fn press(
+        screen: &mut DrillScreen,
+        state: &mut AppState,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) {
+        screen.handle_event(KeyEvent::new(code, modifiers), state);
+    }
+
+    /// What was drilled so far: the description and the time or what was
+    /// pressed instead.
+    fn results(screen: &DrillScreen, state: &AppState) -> Vec<String> {
+        screen
+            .attempts
+            .iter()
+            .map(|attempt| {
+                let shortcut = &state.shortcuts.get_all_shortcuts()[attempt.index];
+                let result = match (&attempt.time, &attempt.pressed) {
+                    (Some(_), _) => "hit".to_owned(),
+                    (None, Some(pressed)) => format!("pressed {pressed}"),
+                    (None, None) => "nothing".to_owned(),
+                };
+                format!("{}: {result}", shortcut.get_description())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_single_strokes_and_sequences() {
+        let mut state = state(&["Ctrl+S", "Ctrl+K Ctrl+C", "Shift+G"], false);
+        let mut screen = drill(&state);
+
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('s'),
+            KeyModifiers::CONTROL,
+        );
+        // keys during the verdict don't count for the next shortcut
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('k'),
+            KeyModifiers::CONTROL,
+        );
+        screen.next();
+
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('k'),
+            KeyModifiers::CONTROL,
+        );
+        assert_eq!(
+            screen.attempts.len(),
+            1,
+            "waits for the rest of the sequence"
+        );
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+        );
+        screen.next();
+
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('G'),
+            KeyModifiers::SHIFT,
+        );
+        screen.next();
+
+        assert_eq!(
+            results(&screen, &state),
+            ["Ctrl+S: hit", "Ctrl+K Ctrl+C: hit", "Shift+G: hit"]
+        );
+        assert!(matches!(screen.phase, Phase::Done));
+    }
+
+    #[test]
+    fn a_wrong_stroke_is_a_miss() {
+        let mut state = state(&["Ctrl+K Ctrl+C", "Ctrl+S"], false);
+        let mut screen = drill(&state);
+
+        // right first stroke, wrong second one
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('k'),
+            KeyModifiers::CONTROL,
+        );
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('x'),
+            KeyModifiers::CONTROL,
+        );
+        screen.next();
+        // the modifier counts
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('s'),
+            KeyModifiers::ALT,
+        );
+
+        assert_eq!(
+            results(&screen, &state),
+            [
+                "Ctrl+K Ctrl+C: pressed Ctrl+K Ctrl+X",
+                "Ctrl+S: pressed Alt+S"
+            ]
+        );
+    }
+
+    #[test]
+    fn esc_stops_unless_a_sequence_was_started() {
+        let mut state = state(&["Ctrl+K Ctrl+C", "Ctrl+S"], false);
+        let mut screen = drill(&state);
+
+        press(
+            &mut screen,
+            &mut state,
+            KeyCode::Char('k'),
+            KeyModifiers::CONTROL,
+        );
+        press(&mut screen, &mut state, KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(
+            results(&screen, &state),
+            ["Ctrl+K Ctrl+C: pressed Ctrl+K Esc"]
+        );
+
+        screen.next();
+        press(&mut screen, &mut state, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(matches!(screen.phase, Phase::Done));
+        assert_eq!(screen.attempts.len(), 1);
+    }
+
+    #[test]
+    fn leaves_out_what_the_terminal_cant_report() {
+        let combos = [
+            "Ctrl+S",
+            "Ctrl+I",
+            "Ctrl+Shift+P",
+            "Super+S",
+            "Esc :",
+            "Alt+Esc",
+        ];
+
+        let state = state(&combos, false);
+        let screen = drill(&state);
+        let queued: Vec<_> = screen.queue.iter().map(|&i| combos[i]).collect();
+        assert_eq!(queued, ["Ctrl+S"]);
+        assert_eq!(screen.excluded, 5);
+
+        let state = self::state(&combos, true);
+        let screen = drill(&state);
+        let queued: Vec<_> = screen.queue.iter().map(|&i| combos[i]).collect();
+        assert_eq!(
+            queued,
+            ["Ctrl+S", "Ctrl+I", "Ctrl+Shift+P", "Super+S", "Alt+Esc"]
+        );
+        assert_eq!(screen.excluded, 1);
+    }
+
+    #[test]
+    fn nothing_to_drill_is_done_at_once() {
+        let state = state(&["Esc"], true);
+        let screen = drill(&state);
+        assert!(screen.queue.is_empty());
+        assert!(matches!(screen.phase, Phase::Done));
+    }
+}
@@ -0,0 +1,80 @@
+use ratatui::{crossterm::event::KeyEvent, Frame};
+
+use crate::{
+    app_state::AppState,
+    screen::{Screen, ScreenCommand},
+    screens::shortcut_form::{FormAction, ShortcutForm},
+    shortcuts::Shortcut,
+};
+
+pub struct EditShortcutScreen {
+    /// Index of the shortcut being edited.
+    index: usize,
+    form: ShortcutForm,
+}
+
+impl EditShortcutScreen {
+    pub fn new(index: usize, shortcut: &Shortcut) -> Self {
+        Self {
+            index,
+            form: ShortcutForm::with_shortcut("Edit shortcut", shortcut),
+        }
+    }
+
+    /// Writes the form back to the shortcut and saves, keeping its lookup
+    /// statistics. The previous version is restored if saving fails.
+    fn submit(&mut self, state: &mut AppState) -> ScreenCommand {
+        let Some(shortcut) = self.form.validate(state, Some(self.index)) else {
+            return ScreenCommand::None;
+        };
+
+        // there's nothing to save, or to roll back if saving failed
+        let unchanged = state
+            .shortcuts
+            .get_all_shortcuts()
+            .get(self.index)
+            .is_some_and(|current| current.has_same_details(&shortcut));
+        if unchanged {
+            state.selected_shortcut = Some(self.index);
+            return ScreenCommand::Close;
+        }
+
+        if state.shortcuts.update(self.index, shortcut).is_none() {
+            self.form
+                .set_error("This shortcut no longer exists".to_owned());
+            return ScreenCommand::None;
+        }
+
+        if let Err(err) = state.shortcuts.save() {
+            state.shortcuts.rollback();
+            self.form
+                .set_error(format!("Could not save shortcuts: {err}"));
+            return ScreenCommand::None;
+        }
+
+        state.selected_shortcut = Some(self.index);
+        ScreenCommand::Close
+    }
+}
+
+impl Screen for EditShortcutScreen {
+    fn draw(&mut self, frame: &mut Frame, _state: &AppState) {
+        self.form.draw(frame);
+    }
+
+    fn handle_event(
+        &mut self,
+        key_event: KeyEvent,
+        state: &mut AppState,
+    ) -> crate::screen::ScreenCommand {
+        match self.form.handle_event(key_event) {
+            FormAction::None => ScreenCommand::None,
+            FormAction::Submit => self.submit(state),
+            FormAction::Close => ScreenCommand::Close,
+        }
+    }
+
+    fn on_tick(&mut self, _state: &mut AppState) {
+        self.form.tick();
+    }
+}
@@ -1,20 +1,31 @@
-use std::{cmp::max, vec};
+use std::cmp::max;
 
 use ratatui::{
-    crossterm::event::{KeyCode, KeyEvent},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
     prelude::*,
-    widgets::{Cell, Row, Table, TableState},
+    widgets::{Block, BorderType, Cell, Paragraph, Row, Table, TableState},
 };
 use style::palette::tailwind;
 
 use crate::{
     app_state::AppState,
     screen::{Screen, ScreenCommand},
-    screens::AddShortcutScreen,
-    shortcuts::Shortcut,
+    screens::{AddShortcutScreen, ConfirmScreen, DrillScreen, EditShortcutScreen, QuizScreen},
+    shortcuts::Shortcuts,
 };
 
-const INFO_TEXT: [&str; 2] = ["(Esc\\q) quit | (j) move down | (k) move up", ""];
+const INFO_TEXT: &[&str] = &[
+    "(Esc\\q) quit | (j/k ↓/↑) move | (PgDn/PgUp) page",
+    "(Ctrl+D/Ctrl+U) half page | (g/G Home/End) first/last",
+    "(Enter) look up | (/) search | (n) new | (e) edit | (d) delete",
+    "(u/Ctrl+R) undo/redo | (r) quiz | (p) drill",
+    "(a/A) next/previous app | (t/T) next/previous tag",
+];
+
+const SEARCH_INFO_TEXT: &[&str] = &[
+    "(Esc) cancel search | (Enter) look up | (↓/↑) move | (PgDn/PgUp) page",
+    "type to filter by shortcut and description",
+];
 
 const PALETTES: [tailwind::Palette; 4] = [
     tailwind::BLUE,
@@ -28,9 +39,8 @@ struct TableColors {
     header_bg: Color,
     header_fg: Color,
     row_fg: Color,
-    selected_row_style_fg: Color,
-    selected_column_style_fg: Color,
     selected_cell_style_fg: Color,
+    search_match_fg: Color,
     normal_row_color: Color,
     alt_row_color: Color,
     footer_border_color: Color,
@@ -43,9 +53,8 @@ impl TableColors {
             header_bg: color.c900,
             header_fg: tailwind::SLATE.c200,
             row_fg: tailwind::SLATE.c200,
-            selected_row_style_fg: color.c400,
-            selected_column_style_fg: color.c400,
             selected_cell_style_fg: color.c600,
+            search_match_fg: tailwind::AMBER.c400,
             normal_row_color: tailwind::SLATE.c950,
             alt_row_color: tailwind::SLATE.c900,
             footer_border_color: color.c400,
@@ -56,34 +65,256 @@ impl TableColors {
 pub struct MainScreen {
     colors: TableColors,
     table_state: TableState,
+
+    /// Only show shortcuts of this application, `None` shows all of them.
+    app_filter: Option<String>,
+
+    /// Only show shortcuts carrying this tag, combined with the app filter.
+    tag_filter: Option<String>,
+
+    /// The fuzzy search query typed after `/`, `None` when not searching.
+    search: Option<String>,
+
+    /// Whether key presses currently go into the search query.
+    search_typing: bool,
+
+    /// A one-off message for the user, e.g. a failed save.
+    status: Option<String>,
+
+    /// How many rows fit in the table when it was last drawn, used for paging.
+    page_height: usize,
+}
+
+/// A table row: the shortcut it shows and which characters matched the search.
+struct VisibleRow {
+    index: usize,
+    combo_matches: Vec<usize>,
+    description_matches: Vec<usize>,
 }
 
 impl Screen for MainScreen {
     fn draw(&mut self, frame: &mut ratatui::Frame, state: &AppState) {
-        self.render_table(frame, state);
+        let [table_area, status_area, footer_area] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            // the text plus the border above and below
+            Constraint::Length(self.info_text().len() as u16 + 2),
+        ])
+        .areas(frame.area());
+
+        self.render_table(frame, table_area, state);
+        self.render_status_line(frame, status_area);
+        self.render_footer(frame, footer_area);
     }
 
     fn handle_event(
         &mut self,
         key_event: KeyEvent,
-        _state: &mut AppState,
+        state: &mut AppState,
     ) -> crate::screen::ScreenCommand {
+        self.status = None;
+
+        if self.search_typing {
+            self.handle_search_event(key_event, state);
+            return ScreenCommand::None;
+        }
+
+        let row_count = self.visible_rows(&state.shortcuts).len();
+        let half_page = (self.page_height / 2).max(1) as isize;
+        let page = self.page_height.max(1) as isize;
+
         match key_event {
+            KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.move_selection(half_page, row_count);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.move_selection(-half_page, row_count);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.undo(state, true);
+                ScreenCommand::None
+            }
+            // leave the search before leaving the app
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } if self.search.is_some() => {
+                self.search = None;
+                self.table_state.select(Some(0));
+                ScreenCommand::None
+            }
             // q or esc
             KeyEvent {
                 code: KeyCode::Char('q'),
+                modifiers: KeyModifiers::NONE,
                 ..
             }
             | KeyEvent {
                 code: KeyCode::Esc, ..
             } => ScreenCommand::Quit(true),
+            KeyEvent {
+                code: KeyCode::Char('j') | KeyCode::Down,
+                ..
+            } => {
+                self.move_selection(1, row_count);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('k') | KeyCode::Up,
+                ..
+            } => {
+                self.move_selection(-1, row_count);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::PageDown,
+                ..
+            } => {
+                self.move_selection(page, row_count);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::PageUp,
+                ..
+            } => {
+                self.move_selection(-page, row_count);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('g') | KeyCode::Home,
+                ..
+            } => {
+                self.table_state.select(Some(0));
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('G') | KeyCode::End,
+                ..
+            } => {
+                self.table_state.select(Some(row_count.saturating_sub(1)));
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => {
+                self.look_up_selected(state);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('/'),
+                ..
+            } => {
+                self.search.get_or_insert_with(String::new);
+                self.search_typing = true;
+                ScreenCommand::None
+            }
             KeyEvent {
                 code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::NONE,
                 ..
             } => ScreenCommand::Push(Box::new(AddShortcutScreen::new())),
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.undo(state, false);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => ScreenCommand::Push(Box::new(QuizScreen::new(state))),
+            // drill what the table shows, so filters and searches pick the shortcuts
+            KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                let indexes: Vec<usize> = self
+                    .visible_rows(&state.shortcuts)
+                    .iter()
+                    .map(|row| row.index)
+                    .collect();
+                ScreenCommand::Push(Box::new(DrillScreen::new(&indexes, state)))
+            }
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => match self.selected_index(&state.shortcuts) {
+                Some(index) => {
+                    let shortcut = &state.shortcuts.get_all_shortcuts()[index];
+                    ScreenCommand::Push(Box::new(EditShortcutScreen::new(index, shortcut)))
+                }
+                None => ScreenCommand::None,
+            },
+            KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => match self.selected_index(&state.shortcuts) {
+                Some(index) => ScreenCommand::Push(Box::new(delete_dialog(index, state))),
+                None => ScreenCommand::None,
+            },
+            KeyEvent {
+                code: KeyCode::Char('a'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.cycle_app_filter(&state.shortcuts, true);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('A'),
+                ..
+            } => {
+                self.cycle_app_filter(&state.shortcuts, false);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.cycle_tag_filter(&state.shortcuts, true);
+                ScreenCommand::None
+            }
+            KeyEvent {
+                code: KeyCode::Char('T'),
+                ..
+            } => {
+                self.cycle_tag_filter(&state.shortcuts, false);
+                ScreenCommand::None
+            }
             _ => ScreenCommand::None,
         }
     }
+
+    fn on_resume(&mut self, state: &mut AppState) {
+        if let Some(index) = state.selected_shortcut.take() {
+            self.select_shortcut(index, &state.shortcuts);
+        } else {
+            // keep the selection on the table if rows went away
+            let row_count = self.visible_rows(&state.shortcuts).len();
+            self.move_selection(0, row_count);
+        }
+    }
 }
 
 impl MainScreen {
@@ -91,87 +322,474 @@ impl MainScreen {
         Self {
             colors: TableColors::new(&PALETTES[0]),
             table_state: TableState::default().with_selected(0),
+            app_filter: None,
+            tag_filter: None,
+            search: None,
+            search_typing: false,
+            status: None,
+            page_height: 0,
+        }
+    }
+
+    /// Selects the row showing the shortcut at `index`, clearing the filters
+    /// and the search if they hide it.
+    fn select_shortcut(&mut self, index: usize, shortcuts: &Shortcuts) {
+        let mut position = self
+            .visible_rows(shortcuts)
+            .iter()
+            .position(|row| row.index == index);
+
+        if position.is_none() {
+            self.app_filter = None;
+            self.tag_filter = None;
+            self.search = None;
+            self.search_typing = false;
+            position = self
+                .visible_rows(shortcuts)
+                .iter()
+                .position(|row| row.index == index);
+        }
+
+        self.table_state.select(position.or(Some(0)));
+    }
+
+    /// Moves the selection by `delta` rows, stopping at the first and last row.
+    fn move_selection(&mut self, delta: isize, row_count: usize) {
+        let Some(last) = row_count.checked_sub(1) else {
+            self.table_state.select(Some(0));
+            return;
+        };
+
+        let current = self.table_state.selected().unwrap_or(0).min(last);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.table_state.select(Some(next));
+    }
+
+    fn handle_search_event(&mut self, key_event: KeyEvent, state: &mut AppState) {
+        let Some(query) = &mut self.search else {
+            self.search_typing = false;
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.search = None;
+                self.search_typing = false;
+                self.table_state.select(Some(0));
+            }
+            KeyCode::Enter => {
+                self.search_typing = false;
+                self.look_up_selected(state);
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                self.table_state.select(Some(0));
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                self.table_state.select(Some(0));
+            }
+            KeyCode::Down | KeyCode::Up | KeyCode::PageDown | KeyCode::PageUp => {
+                let row_count = self.visible_rows(&state.shortcuts).len();
+                let page = self.page_height.max(1) as isize;
+                let delta = match key_event.code {
+                    KeyCode::Down => 1,
+                    KeyCode::Up => -1,
+                    KeyCode::PageDown => page,
+                    _ => -page,
+                };
+                self.move_selection(delta, row_count);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the index of the shortcut in the selected row.
+    fn selected_index(&self, shortcuts: &Shortcuts) -> Option<usize> {
+        let rows = self.visible_rows(shortcuts);
+        self.table_state
+            .selected()
+            .and_then(|selected| rows.get(selected))
+            .map(|row| row.index)
+    }
+
+    /// Counts a lookup of the selected shortcut and saves it, keeping the
+    /// selection on the same shortcut even if the ranking changes.
+    fn look_up_selected(&mut self, state: &mut AppState) {
+        let Some(index) = self.selected_index(&state.shortcuts) else {
+            return;
+        };
+
+        state.shortcuts.increment_lookup_count(index, 1);
+        if let Err(err) = state.shortcuts.save() {
+            self.status = Some(format!("Could not save shortcuts: {err}"));
+        }
+
+        let position = self
+            .visible_rows(&state.shortcuts)
+            .iter()
+            .position(|row| row.index == index);
+        self.table_state.select(position);
+    }
+
+    /// Undoes the last change, or redoes the last undone one, and saves.
+    fn undo(&mut self, state: &mut AppState, redo: bool) {
+        let operation = if redo {
+            state.shortcuts.redo()
+        } else {
+            state.shortcuts.undo()
+        };
+        let Some(operation) = operation else {
+            let what = if redo { "redo" } else { "undo" };
+            self.status = Some(format!("Nothing to {what}"));
+            return;
+        };
+
+        if let Err(err) = state.shortcuts.save() {
+            // put things back the way they are on disk
+            if redo {
+                state.shortcuts.undo();
+            } else {
+                state.shortcuts.redo();
+            }
+            self.status = Some(format!("Could not save shortcuts: {err}"));
+            return;
+        }
+
+        let done = if redo { "Redid" } else { "Undid" };
+        self.status = Some(format!("{done} {}", operation.label));
+
+        match operation.index() {
+            Some(index) if index < state.shortcuts.get_all_shortcuts().len() => {
+                self.select_shortcut(index, &state.shortcuts)
+            }
+            _ => {
+                let row_count = self.visible_rows(&state.shortcuts).len();
+                self.move_selection(0, row_count);
+            }
+        }
+    }
+
+    fn cycle_app_filter(&mut self, shortcuts: &Shortcuts, forward: bool) {
+        let apps = shortcuts.get_apps();
+        self.app_filter = cycle_filter(self.app_filter.as_deref(), &apps, forward);
+        self.table_state.select(Some(0));
+    }
+
+    fn cycle_tag_filter(&mut self, shortcuts: &Shortcuts, forward: bool) {
+        let tags: Vec<&str> = shortcuts.get_tag_counts().into_keys().collect();
+        self.tag_filter = cycle_filter(self.tag_filter.as_deref(), &tags, forward);
+        self.table_state.select(Some(0));
+    }
+
+    /// Returns the shortcuts that pass the current filters, in the order they
+    /// are shown in the table.
+    ///
+    /// While searching, rows are ranked by their match score combined with
+    /// how often they were looked up.
+    fn visible_rows(&self, shortcuts: &Shortcuts) -> Vec<VisibleRow> {
+        let indexes =
+            shortcuts.filter_indexes(self.app_filter.as_deref(), self.tag_filter.as_deref());
+
+        match self.search.as_deref() {
+            Some(query) if !query.trim().is_empty() => shortcuts
+                .search(query, &indexes)
+                .into_iter()
+                .map(|result| VisibleRow {
+                    index: result.index,
+                    combo_matches: result.combo_matches,
+                    description_matches: result.description_matches,
+                })
+                .collect(),
+            _ => indexes
+                .into_iter()
+                .map(|index| VisibleRow {
+                    index,
+                    combo_matches: Vec::new(),
+                    description_matches: Vec::new(),
+                })
+                .collect(),
         }
     }
-    fn render_table(&mut self, frame: &mut Frame, state: &AppState) {
+
+    fn render_table(&mut self, frame: &mut Frame, area: Rect, state: &AppState) {
         let header_style = Style::default()
             .fg(self.colors.header_fg)
             .bg(self.colors.header_bg);
         let selected_row_style = Style::default()
             .add_modifier(Modifier::REVERSED)
             .fg(self.colors.selected_cell_style_fg);
+        let match_style = Style::default()
+            .fg(self.colors.search_match_fg)
+            .add_modifier(Modifier::BOLD);
+
+        // the app column is only useful when more than one app is shown
+        let show_app = self.app_filter.is_none();
+
+        let all_shortcuts = state.shortcuts.get_all_shortcuts();
+        let visible_rows = self.visible_rows(&state.shortcuts);
+
+        // as is the context column, e.g. for imported VS Code keybindings
+        let show_context = visible_rows
+            .iter()
+            .any(|row| all_shortcuts[row.index].get_context().is_some());
+
+        let mut header_text = vec!["Lookup count", "Shortcut", "Description", "Tags"];
+        if show_app {
+            header_text.push("App");
+        }
+        if show_context {
+            header_text.push("Context");
+        }
+        let cells: Vec<Vec<String>> = visible_rows
+            .iter()
+            .map(|row| {
+                let shortcut = &all_shortcuts[row.index];
+                let mut cells = vec![
+                    shortcut.get_lookup_count().to_string(),
+                    shortcut.get_key_combo().to_string(),
+                    shortcut.get_description().to_owned(),
+                    shortcut
+                        .get_tags()
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ];
+                if show_app {
+                    cells.push(shortcut.get_app().to_owned());
+                }
+                if show_context {
+                    cells.push(shortcut.get_context().unwrap_or_default().to_owned());
+                }
+                cells
+            })
+            .collect();
 
-        let header_text = ["Lookup count", "Shortcut", "Description"];
         let header = header_text
-            .clone()
-            .into_iter()
+            .iter()
+            .copied()
             .map(Cell::from)
             .collect::<Row>()
             .style(header_style)
             .height(1);
 
-        let longest_item_lens =
-            calc_longest_lens(state.shortcuts.get_all_shortcuts(), &header_text);
+        let longest_item_lens = calc_longest_lens(&cells, &header_text);
 
-        let rows = state
-            .shortcuts
-            .get_all_shortcuts()
-            .iter()
+        let rows = cells
+            .into_iter()
+            .zip(&visible_rows)
             .enumerate()
-            .map(|(i, shortcut)| {
+            .map(|(i, (cells, row))| {
                 let color = match i % 2 {
                     0 => self.colors.normal_row_color,
                     _ => self.colors.alt_row_color,
                 };
-                Row::new(vec![
-                    Cell::new(shortcut.get_lookup_count().to_string()),
-                    Cell::new(shortcut.get_key_combo()),
-                    Cell::new(shortcut.get_description()),
-                ])
-                .style(Style::new().fg(self.colors.row_fg).bg(color))
+                cells
+                    .into_iter()
+                    .enumerate()
+                    .map(|(column, text)| match column {
+                        1 => highlighted_cell(text, &row.combo_matches, match_style),
+                        2 => highlighted_cell(text, &row.description_matches, match_style),
+                        _ => Cell::new(text),
+                    })
+                    .collect::<Row>()
+                    .style(Style::new().fg(self.colors.row_fg).bg(color))
             });
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(longest_item_lens[0] as u16),
-                Constraint::Min(longest_item_lens[1] as u16),
-                Constraint::Min(longest_item_lens[2] as u16),
-            ],
-        )
-        .header(header)
-        .row_highlight_style(selected_row_style);
-        frame.render_stateful_widget(table, frame.area(), &mut self.table_state);
+
+        let widths = longest_item_lens
+            .iter()
+            .enumerate()
+            .map(|(i, &len)| match i {
+                0 => Constraint::Length(len as u16),
+                _ => Constraint::Min(len as u16),
+            });
+
+        let mut title = match &self.app_filter {
+            Some(app) => format!(" {app} "),
+            None => " All apps ".to_owned(),
+        };
+        if let Some(tag) = &self.tag_filter {
+            title.push_str(&format!("#{tag} "));
+        }
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::new().title(title))
+            .row_highlight_style(selected_row_style)
+            .bg(self.colors.buffer_bg);
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+
+        // the title and the header take up a line each
+        self.page_height = area.height.saturating_sub(2) as usize;
+    }
+
+    fn info_text(&self) -> &'static [&'static str] {
+        if self.search_typing {
+            SEARCH_INFO_TEXT
+        } else {
+            INFO_TEXT
+        }
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let info_footer = Paragraph::new(Text::from_iter(self.info_text().iter().copied()))
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(info_footer, area);
+    }
+
+    fn render_status_line(&self, frame: &mut Frame, area: Rect) {
+        if let Some(status) = &self.status {
+            frame.render_widget(Paragraph::new(status.as_str()), area);
+            return;
+        }
+
+        if let Some(query) = &self.search {
+            let line = Line::from(vec![Span::raw("/"), Span::raw(query.as_str())]);
+            frame.render_widget(Paragraph::new(line), area);
+            if self.search_typing {
+                let cursor_x = area.x + 1 + query.chars().count() as u16;
+                frame.set_cursor_position((cursor_x.min(area.right().saturating_sub(1)), area.y));
+            }
+        }
     }
 }
 
-fn calc_longest_lens(shortcuts: &[Shortcut], headers: &[&str]) -> Vec<usize> {
-    vec![
-        max(
-            shortcuts
-                .iter()
-                .map(Shortcut::get_lookup_count)
-                .map(|a| a.to_string().len())
-                .max()
-                .unwrap_or(0),
-            headers[0].len(),
-        ),
-        max(
-            shortcuts
-                .iter()
-                .map(Shortcut::get_key_combo)
-                .map(|a| a.to_string().len())
-                .max()
-                .unwrap_or(0),
-            headers[1].len(),
-        ),
-        max(
-            shortcuts
-                .iter()
-                .map(Shortcut::get_description)
-                .map(|a| a.to_string().len())
-                .max()
-                .unwrap_or(0),
-            headers[2].len(),
-        ),
-    ]
+/// Asks whether to delete the shortcut at `index`, and deletes and saves it if so.
+fn delete_dialog(index: usize, state: &AppState) -> ConfirmScreen {
+    let shortcut = &state.shortcuts.get_all_shortcuts()[index];
+    let message = format!(
+        "Delete {} ({}) from {}?",
+        shortcut.get_key_combo(),
+        shortcut.get_description(),
+        shortcut.get_app()
+    );
+
+    ConfirmScreen::new(
+        "Delete shortcut",
+        &message,
+        Box::new(move |state: &mut AppState| {
+            if state.shortcuts.remove_at(index).is_none() {
+                return Ok(());
+            }
+            state.shortcuts.save().map_err(|err| {
+                state.shortcuts.rollback();
+                format!("Could not save shortcuts: {err}")
+            })
+        }),
+    )
+    .labels("Delete", "Keep")
+}
+
+/// Builds a cell where the chars at `matches` are drawn with `match_style`.
+fn highlighted_cell(text: String, matches: &[usize], match_style: Style) -> Cell<'static> {
+    if matches.is_empty() {
+        return Cell::new(text);
+    }
+
+    let spans: Vec<Span> = text
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match matches.binary_search(&i) {
+            Ok(_) => Span::styled(c.to_string(), match_style),
+            Err(_) => Span::raw(c.to_string()),
+        })
+        .collect();
+
+    Cell::new(Line::from(spans))
+}
+
+/// Returns the option after (or before) `current` in `options`, passing
+/// through `None` ("no filter") once after the last option.
+fn cycle_filter(current: Option<&str>, options: &[&str], forward: bool) -> Option<String> {
+    // position 0 is "no filter", the options follow at 1..=len
+    let position = current
+        .and_then(|current| options.iter().position(|&option| option == current))
+        .map_or(0, |i| i + 1);
+    let count = options.len() + 1;
+    let next = if forward {
+        (position + 1) % count
+    } else {
+        (position + count - 1) % count
+    };
+
+    next.checked_sub(1).map(|i| options[i].to_owned())
+}
+
+fn calc_longest_lens(rows: &[Vec<String>], headers: &[&str]) -> Vec<usize> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(column, header)| {
+            max(
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0),
+                header.len(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::Shortcut;
+
+    fn state() -> AppState {
+        let mut shortcuts = Shortcuts::new();
+        let mut copy = Shortcut::from("Ctrl+C".parse().unwrap(), "copy".to_owned());
+        copy.set_app("vscode");
+        shortcuts.add_shortcut(copy);
+        AppState::new(shortcuts)
+    }
+
+    /// What pressing `key` with `modifiers` on a fresh screen does, and the
+    /// app filter afterwards.
+    fn outcome(key: char, modifiers: KeyModifiers) -> (&'static str, Option<String>) {
+        let (mut screen, mut state) = (MainScreen::new(), state());
+        let command = screen.handle_event(KeyEvent::new(KeyCode::Char(key), modifiers), &mut state);
+        let command = match command {
+            ScreenCommand::None => "none",
+            ScreenCommand::Quit(_) => "quit",
+            ScreenCommand::Push(_) => "push",
+            _ => "other",
+        };
+        (command, screen.app_filter)
+    }
+
+    #[test]
+    fn plain_letters_act() {
+        assert_eq!(outcome('q', KeyModifiers::NONE).0, "quit");
+        assert_eq!(outcome('d', KeyModifiers::NONE).0, "push");
+        assert_eq!(outcome('e', KeyModifiers::NONE).0, "push");
+        assert_eq!(
+            outcome('a', KeyModifiers::NONE).1.as_deref(),
+            Some("vscode")
+        );
+    }
+
+    #[test]
+    fn modified_letters_do_nothing() {
+        for modifiers in [KeyModifiers::ALT, KeyModifiers::CONTROL | KeyModifiers::ALT] {
+            for key in ['q', 'd', 'e', 'n', 'p', 'r', 'u'] {
+                assert_eq!(outcome(key, modifiers).0, "none", "{modifiers:?} {key}");
+            }
+            assert_eq!(outcome('a', modifiers).1, None);
+        }
+        assert_eq!(outcome('q', KeyModifiers::CONTROL).0, "none");
+        assert_eq!(outcome('e', KeyModifiers::CONTROL).0, "none");
+        assert_eq!(outcome('a', KeyModifiers::CONTROL).1, None);
+    }
 }
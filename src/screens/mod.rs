@@ -1,5 +1,14 @@
 mod add_screen;
+mod confirm_screen;
+mod drill_screen;
+mod edit_screen;
 mod main_screen;
+mod quiz_screen;
+mod shortcut_form;
 
 pub use add_screen::AddShortcutScreen;
+pub use confirm_screen::ConfirmScreen;
+pub use drill_screen::DrillScreen;
+pub use edit_screen::EditShortcutScreen;
 pub use main_screen::MainScreen;
+pub use quiz_screen::QuizScreen;
@@ -0,0 +1,217 @@
+use std::{collections::VecDeque, time::Duration};
+
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    prelude::*,
+    style::palette::tailwind,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+};
+
+use crate::{
+    app_state::AppState,
+    screen::{Screen, ScreenCommand},
+    shortcuts::Grade,
+};
+
+const BG_COLOR: Color = tailwind::SLATE.c950;
+const FG_COLOR: Color = tailwind::SLATE.c200;
+const MUTED_COLOR: Color = tailwind::SLATE.c400;
+const ANSWER_COLOR: Color = tailwind::EMERALD.c400;
+const BORDER_COLOR: Color = tailwind::INDIGO.c400;
+const ERROR_COLOR: Color = tailwind::RED.c400;
+
+const QUESTION_KEYS: &str = "(Space/Enter) show shortcut | (Esc/q) stop";
+const ANSWER_KEYS: &str = "(1) again | (2) hard | (3/Space/Enter) good | (4) easy | (Esc/q) stop";
+const DONE_KEYS: &str = "(any key) close";
+
+/// Flashcards for the shortcuts that are due: shows a description, reveals
+/// the combo on request and schedules the next review from the self-grade.
+///
+/// Every grade is saved right away, so stopping halfway loses nothing.
+pub struct QuizScreen {
+    /// Shortcut indexes still to quiz. Cards graded "again" go to the back.
+    queue: VecDeque<usize>,
+    revealed: bool,
+
+    /// Cards answered this session, counting repeats.
+    answered: usize,
+    /// Of those, the ones graded "again".
+    forgotten: usize,
+
+    /// Time until the next review after this session, shown when nothing is left.
+    next_due: Option<Duration>,
+
+    error: Option<String>,
+}
+
+impl QuizScreen {
+    /// Starts a session with everything due now.
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            queue: state.shortcuts.get_due_indexes().into(),
+            revealed: false,
+            answered: 0,
+            forgotten: 0,
+            next_due: state.shortcuts.get_next_due(),
+            error: None,
+        }
+    }
+
+    fn grade(&mut self, grade: Grade, state: &mut AppState) {
+        let Some(&index) = self.queue.front() else {
+            return;
+        };
+        let Some(previous) = state.shortcuts.grade_review(index, grade) else {
+            return;
+        };
+
+        if let Err(err) = state.shortcuts.save() {
+            // keep the card so the grade can be retried
+            state.shortcuts.set_review(index, previous);
+            self.error = Some(format!("Could not save shortcuts: {err}"));
+            return;
+        }
+
+        self.error = None;
+        self.queue.pop_front();
+        self.revealed = false;
+        self.answered += 1;
+        if grade == Grade::Again {
+            self.forgotten += 1;
+            self.queue.push_back(index);
+        }
+        self.next_due = state.shortcuts.get_next_due();
+    }
+
+    fn card_lines(&self, index: usize, state: &AppState) -> Vec<Line<'static>> {
+        let shortcut = &state.shortcuts.get_all_shortcuts()[index];
+
+        let mut context = shortcut.get_app().to_owned();
+        for tag in shortcut.get_tags() {
+            context.push_str(&format!(" #{tag}"));
+        }
+
+        let answer = if self.revealed {
+            Span::styled(
+                shortcut.get_key_combo().to_string(),
+                Style::new().fg(ANSWER_COLOR).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled("?", Style::new().fg(MUTED_COLOR))
+        };
+
+        vec![
+            Line::styled(context, Style::new().fg(MUTED_COLOR)),
+            Line::default(),
+            Line::styled(
+                shortcut.get_description().to_owned(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::default(),
+            Line::from(answer),
+        ]
+    }
+
+    fn summary_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        if self.answered == 0 {
+            lines.push(Line::from("Nothing to review right now."));
+        } else {
+            lines.push(Line::from(format!(
+                "Done! {} answered, {} to practice again.",
+                self.answered, self.forgotten
+            )));
+        }
+
+        if let Some(wait) = self.next_due {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                format!("Next review {}.", format_wait(wait)),
+                Style::new().fg(MUTED_COLOR),
+            ));
+        }
+        lines
+    }
+}
+
+impl Screen for QuizScreen {
+    fn draw(&mut self, frame: &mut Frame, state: &AppState) {
+        let area = frame.area();
+
+        let title = match self.queue.len() {
+            0 => " Quiz ".to_owned(),
+            left => format!(" Quiz: {left} left "),
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .border_style(Style::new().fg(BORDER_COLOR))
+            .title(title.bold())
+            .style(Style::new().fg(FG_COLOR).bg(BG_COLOR));
+        let inner = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [_, card_area, error_area, keys_area] = Layout::vertical([
+            Constraint::Percentage(30),
+            Constraint::Min(5),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let (lines, keys) = match self.queue.front() {
+            Some(&index) => (
+                self.card_lines(index, state),
+                if self.revealed {
+                    ANSWER_KEYS
+                } else {
+                    QUESTION_KEYS
+                },
+            ),
+            None => (self.summary_lines(), DONE_KEYS),
+        };
+
+        frame.render_widget(
+            Paragraph::new(lines).centered().wrap(Wrap { trim: true }),
+            card_area,
+        );
+        if let Some(error) = &self.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str()).fg(ERROR_COLOR).centered(),
+                error_area,
+            );
+        }
+        frame.render_widget(Paragraph::new(keys).fg(MUTED_COLOR).centered(), keys_area);
+    }
+
+    fn handle_event(&mut self, key_event: KeyEvent, state: &mut AppState) -> ScreenCommand {
+        if self.queue.is_empty() {
+            return ScreenCommand::Close;
+        }
+
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => return ScreenCommand::Close,
+            KeyCode::Char(' ') | KeyCode::Enter if !self.revealed => self.revealed = true,
+            KeyCode::Char('1') if self.revealed => self.grade(Grade::Again, state),
+            KeyCode::Char('2') if self.revealed => self.grade(Grade::Hard, state),
+            KeyCode::Char('3') | KeyCode::Char(' ') | KeyCode::Enter if self.revealed => {
+                self.grade(Grade::Good, state)
+            }
+            KeyCode::Char('4') if self.revealed => self.grade(Grade::Easy, state),
+            _ => {}
+        }
+        ScreenCommand::None
+    }
+}
+
+/// "in 3 days", "in 5 hours", ...
+fn format_wait(wait: Duration) -> String {
+    let (amount, unit) = match wait.as_secs() {
+        s if s >= 2 * 86_400 => (s / 86_400, "days"),
+        s if s >= 2 * 3600 => (s / 3600, "hours"),
+        s if s >= 2 * 60 => (s / 60, "minutes"),
+        _ => return "in a minute".to_owned(),
+    };
+    format!("in {amount} {unit}")
+}
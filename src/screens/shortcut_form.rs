@@ -0,0 +1,506 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    prelude::*,
+    style::{
+        palette::tailwind::{self},
+        Color, Style,
+    },
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    app_state::AppState,
+    key_capture::KeyCapture,
+    shortcuts::{KeyCombo, Shortcut},
+    utils,
+    widgets::{button::Button, input::Input, Widget},
+};
+
+#[derive(Debug, PartialEq, Default, Hash, Eq, Clone, Copy)]
+enum FocusedWidget {
+    #[default]
+    ShortcutInput,
+    DescriptionInput,
+    AppInput,
+    ContextInput,
+    TagsInput,
+    SaveButton,
+    CancelButton,
+}
+
+impl FocusedWidget {
+    const ORDER: [FocusedWidget; 7] = [
+        FocusedWidget::ShortcutInput,
+        FocusedWidget::DescriptionInput,
+        FocusedWidget::AppInput,
+        FocusedWidget::ContextInput,
+        FocusedWidget::TagsInput,
+        FocusedWidget::SaveButton,
+        FocusedWidget::CancelButton,
+    ];
+
+    fn next(self) -> Self {
+        let i = Self::ORDER.iter().position(|&w| w == self).unwrap_or(0);
+        Self::ORDER[(i + 1) % Self::ORDER.len()]
+    }
+
+    fn previous(self) -> Self {
+        let i = Self::ORDER.iter().position(|&w| w == self).unwrap_or(0);
+        Self::ORDER[(i + Self::ORDER.len() - 1) % Self::ORDER.len()]
+    }
+}
+
+/// What the screen owning a [`ShortcutForm`] should do after a key press.
+pub enum FormAction {
+    None,
+    /// The user wants to save, see [`ShortcutForm::validate`].
+    Submit,
+    /// The user cancelled and confirmed discarding any changes.
+    Close,
+}
+
+/// The popup with the shortcut fields shared by the add and edit screens.
+pub struct ShortcutForm {
+    title: String,
+    shortcut_input: Input,
+    description_input: Input,
+    app_input: Input,
+    context_input: Input,
+    tags_input: Input,
+    save_button: Button,
+    cancel_button: Button,
+    focused_widget: Option<FocusedWidget>,
+
+    /// The field values the form was opened with, to detect unsaved changes.
+    initial_values: [String; 5],
+
+    /// Why the last submit was rejected.
+    error: Option<String>,
+
+    /// Whether the "discard changes?" prompt is showing.
+    confirming_discard: bool,
+
+    /// Records the shortcut by pressing it, `Some` while recording.
+    capture: Option<KeyCapture>,
+}
+
+const BG_COLOR: Color = tailwind::GRAY.c700;
+const ERROR_COLOR: Color = tailwind::RED.c400;
+const WARNING_COLOR: Color = tailwind::AMBER.c400;
+
+/// Height of the popup: five inputs, the message line, the buttons and the border.
+const POPUP_HEIGHT: u16 = 5 * 3 + 1 + 3 + 2;
+
+impl ShortcutForm {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            shortcut_input: Input::new(
+                "Shortcut",
+                "e.g. Ctrl+Shift+P or C-x C-s, (Ctrl+R) record by pressing it",
+            ),
+            description_input: Input::new("Description", "e.g. open the command palette"),
+            app_input: Input::new("App", "e.g. vim, tmux (optional)"),
+            context_input: Input::new(
+                "Context",
+                "where in the app, e.g. insert mode, editorTextFocus (optional)",
+            ),
+            tags_input: Input::new("Tags", "comma separated, e.g. git, navigation (optional)"),
+            save_button: Button::new("Save").color(tailwind::GREEN.c500),
+            cancel_button: Button::new("Cancel"),
+            focused_widget: Some(FocusedWidget::ShortcutInput),
+            initial_values: Default::default(),
+            error: None,
+            confirming_discard: false,
+            capture: None,
+        }
+    }
+
+    /// Creates a form pre-filled with the fields of `shortcut`.
+    pub fn with_shortcut(title: &str, shortcut: &Shortcut) -> Self {
+        let mut form = Self::new(title);
+        form.shortcut_input
+            .set_value(&shortcut.get_key_combo().to_string());
+        form.description_input.set_value(shortcut.get_description());
+        form.app_input.set_value(shortcut.get_app());
+        form.tags_input.set_value(
+            &shortcut
+                .get_tags()
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        form.context_input
+            .set_value(shortcut.get_context().unwrap_or_default());
+        form.initial_values = form.values();
+        form
+    }
+
+    fn values(&self) -> [String; 5] {
+        [
+            &self.shortcut_input,
+            &self.description_input,
+            &self.app_input,
+            &self.context_input,
+            &self.tags_input,
+        ]
+        .map(|input| input.get_value().to_owned())
+    }
+
+    /// Whether any field differs from what the form was opened with.
+    pub fn is_dirty(&self) -> bool {
+        self.values() != self.initial_values
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let area = utils::centered_rect_with_height(60, POPUP_HEIGHT, frame.area());
+
+        let title = Span::styled(
+            self.title.as_str(),
+            Style::default().add_modifier(Modifier::BOLD),
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .bg(BG_COLOR)
+            .title(title);
+
+        let inner = block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [shortcut, desc, app, context, tags, message, buttons] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ])
+        .areas(inner);
+
+        self.render_input(FocusedWidget::ShortcutInput, shortcut, frame);
+        self.render_input(FocusedWidget::DescriptionInput, desc, frame);
+        self.render_input(FocusedWidget::AppInput, app, frame);
+        self.render_input(FocusedWidget::ContextInput, context, frame);
+        self.render_input(FocusedWidget::TagsInput, tags, frame);
+        self.render_message(message, frame);
+        self.render_buttons(buttons, frame);
+    }
+
+    fn render_input(&mut self, widget: FocusedWidget, area: Rect, frame: &mut Frame) {
+        let focused = self.focused_widget == Some(widget) && !self.confirming_discard;
+        let input = match widget {
+            FocusedWidget::ShortcutInput => &mut self.shortcut_input,
+            FocusedWidget::DescriptionInput => &mut self.description_input,
+            FocusedWidget::AppInput => &mut self.app_input,
+            FocusedWidget::ContextInput => &mut self.context_input,
+            FocusedWidget::TagsInput => &mut self.tags_input,
+            FocusedWidget::SaveButton | FocusedWidget::CancelButton => return,
+        };
+        input.set_focused(focused);
+        input.render(area, frame);
+    }
+
+    fn render_buttons(&mut self, area: Rect, frame: &mut Frame) {
+        let [_, save, cancel, _] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Min(0),
+        ])
+        .spacing(2)
+        .areas(area);
+
+        self.save_button
+            .set_focused(self.focused_widget == Some(FocusedWidget::SaveButton));
+        self.save_button.render(save, frame);
+
+        self.cancel_button
+            .set_focused(self.focused_widget == Some(FocusedWidget::CancelButton));
+        self.cancel_button.render(cancel, frame);
+    }
+
+    fn render_message(&self, area: Rect, frame: &mut Frame) {
+        let paragraph = if let Some(capture) = &self.capture {
+            let text = if capture.is_empty() {
+                "Press the shortcut to record it | (Esc) cancel"
+            } else {
+                "Recording, press the next key | (Enter) done or wait a moment"
+            };
+            Paragraph::new(text).fg(WARNING_COLOR).bold()
+        } else if self.confirming_discard {
+            Paragraph::new("Discard unsaved changes? (y) discard | (n) keep editing")
+                .fg(WARNING_COLOR)
+                .bold()
+        } else if let Some(error) = &self.error {
+            Paragraph::new(error.as_str()).fg(ERROR_COLOR)
+        } else {
+            return;
+        };
+
+        frame.render_widget(paragraph.centered(), area);
+    }
+
+    pub fn handle_event(&mut self, key_event: KeyEvent) -> FormAction {
+        if let Some(capture) = &mut self.capture {
+            match key_event.code {
+                KeyCode::Enter if !capture.is_empty() => self.finish_capture(),
+                KeyCode::Esc if capture.is_empty() => self.capture = None,
+                _ => {
+                    if capture.push(&key_event) {
+                        if let Some(combo) = capture.combo() {
+                            self.shortcut_input.set_value(&combo.to_string());
+                        }
+                    }
+                }
+            }
+            return FormAction::None;
+        }
+
+        if self.confirming_discard {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => return FormAction::Close,
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.confirming_discard = false;
+                }
+                _ => {}
+            }
+            return FormAction::None;
+        }
+
+        match key_event.code {
+            KeyCode::Char('q') if self.focused_widget.is_none() => {
+                return self.cancel();
+            }
+            KeyCode::Tab => {
+                self.focused_widget = Some(
+                    self.focused_widget
+                        .map_or_else(FocusedWidget::default, FocusedWidget::next),
+                );
+            }
+            KeyCode::BackTab => {
+                self.focused_widget = Some(
+                    self.focused_widget
+                        .map_or_else(FocusedWidget::default, FocusedWidget::previous),
+                );
+            }
+            KeyCode::Esc if self.focused_widget.is_none() => {
+                return self.cancel();
+            }
+            KeyCode::Esc => {
+                self.focused_widget = None;
+            }
+            KeyCode::Char('r')
+                if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.focused_widget == Some(FocusedWidget::ShortcutInput) =>
+            {
+                self.error = None;
+                self.capture = Some(KeyCapture::default());
+            }
+            _ => {
+                // Forward input to focused widget
+                if let Some(focused) = &self.focused_widget {
+                    match focused {
+                        FocusedWidget::ShortcutInput => {
+                            self.shortcut_input.handle_event(key_event);
+                        }
+                        FocusedWidget::DescriptionInput => {
+                            self.description_input.handle_event(key_event);
+                        }
+                        FocusedWidget::AppInput => {
+                            self.app_input.handle_event(key_event);
+                        }
+                        FocusedWidget::ContextInput => {
+                            self.context_input.handle_event(key_event);
+                        }
+                        FocusedWidget::TagsInput => {
+                            self.tags_input.handle_event(key_event);
+                        }
+                        FocusedWidget::SaveButton => {
+                            if self.save_button.handle_event(key_event) {
+                                return FormAction::Submit;
+                            }
+                        }
+                        FocusedWidget::CancelButton => {
+                            if self.cancel_button.handle_event(key_event) {
+                                return self.cancel();
+                            }
+                        }
+                    }
+                }
+
+                // Enter in any of the inputs submits the form
+                if key_event.code == KeyCode::Enter
+                    && !matches!(
+                        self.focused_widget,
+                        Some(FocusedWidget::SaveButton | FocusedWidget::CancelButton)
+                    )
+                {
+                    return FormAction::Submit;
+                }
+            }
+        }
+        FormAction::None
+    }
+
+    /// Ends a recording that got no new stroke for a while.
+    pub fn tick(&mut self) {
+        if self.capture.as_ref().is_some_and(KeyCapture::is_timed_out) {
+            self.finish_capture();
+        }
+    }
+
+    fn finish_capture(&mut self) {
+        if let Some(combo) = self.capture.take().and_then(|capture| capture.combo()) {
+            self.shortcut_input.set_value(&combo.to_string());
+        }
+    }
+
+    /// Closes right away when nothing changed, otherwise asks first.
+    fn cancel(&mut self) -> FormAction {
+        if self.is_dirty() {
+            self.confirming_discard = true;
+            FormAction::None
+        } else {
+            FormAction::Close
+        }
+    }
+
+    /// Builds the shortcut from the inputs.
+    ///
+    /// `editing` is the index of the shortcut being edited, which is allowed to
+    /// keep its own combo. If a field is invalid the reason is shown, that
+    /// field is focused and `None` is returned.
+    pub fn validate(&mut self, state: &AppState, editing: Option<usize>) -> Option<Shortcut> {
+        match self.build_shortcut(state, editing) {
+            Ok(shortcut) => Some(shortcut),
+            Err((error, field)) => {
+                self.error = Some(error);
+                self.focused_widget = Some(field);
+                None
+            }
+        }
+    }
+
+    fn build_shortcut(
+        &self,
+        state: &AppState,
+        editing: Option<usize>,
+    ) -> Result<Shortcut, (String, FocusedWidget)> {
+        let combo_text = self.shortcut_input.get_value().trim();
+        if combo_text.is_empty() {
+            return Err((
+                "Enter a shortcut, e.g. Ctrl+S".to_owned(),
+                FocusedWidget::ShortcutInput,
+            ));
+        }
+
+        let key_combo: KeyCombo = combo_text.parse().map_err(|err| {
+            (
+                format!("Invalid shortcut: {err}"),
+                FocusedWidget::ShortcutInput,
+            )
+        })?;
+
+        let description = self.description_input.get_value().trim();
+        if description.is_empty() {
+            return Err((
+                "Enter a description".to_owned(),
+                FocusedWidget::DescriptionInput,
+            ));
+        }
+
+        let mut shortcut = Shortcut::from(key_combo, description.to_owned());
+        shortcut.set_app(self.app_input.get_value());
+        shortcut.set_tags(self.tags_input.get_value());
+        shortcut.set_context(self.context_input.get_value());
+
+        let existing = state.shortcuts.find(
+            shortcut.get_key_combo(),
+            shortcut.get_app(),
+            shortcut.get_context(),
+        );
+        if existing.is_some() && existing != editing {
+            return Err((
+                format!(
+                    "{} already exists in {}",
+                    shortcut.get_key_combo(),
+                    shortcut.get_app()
+                ),
+                FocusedWidget::ShortcutInput,
+            ));
+        }
+
+        Ok(shortcut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn press(form: &mut ShortcutForm, code: KeyCode, modifiers: KeyModifiers) -> FormAction {
+        form.handle_event(KeyEvent::new(code, modifiers))
+    }
+
+    fn recording_form() -> ShortcutForm {
+        let mut form = ShortcutForm::new("Add");
+        press(&mut form, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert!(form.capture.is_some());
+        form
+    }
+
+    #[test]
+    fn esc_before_any_stroke_stops_recording() {
+        let mut form = recording_form();
+        let action = press(&mut form, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(matches!(action, FormAction::None));
+        assert!(form.capture.is_none());
+        assert_eq!(form.shortcut_input.get_value(), "");
+    }
+
+    #[test]
+    fn enter_and_esc_are_recorded_until_enter_ends_a_sequence() {
+        let mut form = recording_form();
+        // nothing recorded yet, so Enter is the shortcut's first key
+        press(&mut form, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(form.shortcut_input.get_value(), "Enter");
+        // and Esc no longer cancels
+        press(&mut form, KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(form.shortcut_input.get_value(), "Enter Esc");
+        assert!(form.capture.is_some());
+
+        let action = press(&mut form, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(
+            matches!(action, FormAction::None),
+            "finishing doesn't submit"
+        );
+        assert!(form.capture.is_none());
+        assert_eq!(form.shortcut_input.get_value(), "Enter Esc");
+    }
+
+    #[test]
+    fn recording_ends_when_the_sequence_times_out() {
+        let mut form = recording_form();
+        press(&mut form, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        form.tick();
+        assert!(form.capture.is_some(), "still waiting for a second stroke");
+
+        form.capture = Some(KeyCapture::new(Duration::ZERO));
+        press(&mut form, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        press(&mut form, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        form.tick();
+        assert!(form.capture.is_none());
+        assert_eq!(form.shortcut_input.get_value(), "Ctrl+K Ctrl+C");
+    }
+}
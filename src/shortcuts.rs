@@ -1,22 +1,197 @@
 use std::{
     cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
     fmt::{
         self,
     },
-    fs::File,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
 use serde::{
     Deserialize,
     Serialize,
 };
+use serde_json::{Map, Value};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::fuzzy::fuzzy_match;
+
+mod drill;
+mod history;
+mod key_combo;
+mod review;
+mod storage;
+mod undo;
+
+pub use drill::DrillStats;
+use history::LookupHistory;
+pub use key_combo::{Key, KeyCombo, KeyStroke, Modifiers};
+pub use review::{Grade, Review};
+pub use storage::{Backup, LoadError, SaveError};
+pub use undo::Operation;
+use undo::{Change, UndoLog};
+
+/// Version of the file format written by this build. Older files are
+/// upgraded on load by [`MIGRATIONS`].
+pub const FILE_VERSION: u32 = 6;
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` document to version `n + 2`.
+///
+/// Files from before the format was versioned have no `version` and count as
+/// version 1. Steps work on the raw JSON, because older documents don't
+/// deserialize into the current types.
+const MIGRATIONS: [fn(&mut Value); FILE_VERSION as usize - 1] = [
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+];
+
+/// Version 2 writes `app` and `tags` for every shortcut, version 1 files
+/// may lack them.
+fn migrate_v1_to_v2(document: &mut Value) {
+    for shortcut in shortcuts_in(document) {
+        shortcut
+            .entry("app")
+            .or_insert_with(|| Value::from(UNASSIGNED_APP));
+        shortcut
+            .entry("tags")
+            .or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+/// Version 3 adds timestamps and the lookup history. When older shortcuts
+/// were created or looked up is unknown, so they start without any.
+fn migrate_v2_to_v3(document: &mut Value) {
+    for shortcut in shortcuts_in(document) {
+        for field in ["created_at", "updated_at", "last_looked_up"] {
+            shortcut.entry(field).or_insert(Value::Null);
+        }
+        shortcut
+            .entry("history")
+            .or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+/// Version 4 adds the spaced repetition schedule, every shortcut starts as
+/// a new card.
+fn migrate_v3_to_v4(document: &mut Value) {
+    let review = serde_json::to_value(Review::default()).expect("a review serializes");
+    for shortcut in shortcuts_in(document) {
+        shortcut.entry("review").or_insert_with(|| review.clone());
+    }
+}
+
+/// Version 5 adds timing statistics from drills.
+fn migrate_v4_to_v5(document: &mut Value) {
+    let drill = serde_json::to_value(DrillStats::default()).expect("drill stats serialize");
+    for shortcut in shortcuts_in(document) {
+        shortcut.entry("drill").or_insert_with(|| drill.clone());
+    }
+}
+
+/// Version 6 lets shortcuts apply to part of an app only, none of the older
+/// ones do.
+fn migrate_v5_to_v6(document: &mut Value) {
+    for shortcut in shortcuts_in(document) {
+        shortcut.entry("context").or_insert(Value::Null);
+    }
+}
+
+/// The shortcut objects of a document, skipping anything malformed so that
+/// deserialization can report it.
+fn shortcuts_in(document: &mut Value) -> impl Iterator<Item = &mut Map<String, Value>> {
+    document
+        .get_mut("shortcuts")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+/// Upgrades `document` to [`FILE_VERSION`] and returns the version it had.
+fn migrate(document: &mut Value) -> Result<u32, LoadError> {
+    let version = match document.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|&version| version >= 1)
+            .ok_or(LoadError::BadVersion)?,
+    };
+    if version > FILE_VERSION {
+        return Err(LoadError::TooNew { version });
+    }
+
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(document);
+    }
+    if let Some(document) = document.as_object_mut() {
+        document.insert("version".into(), Value::from(FILE_VERSION));
+    }
+    Ok(version)
+}
+
+/// How many backups of the shortcuts file are kept unless configured otherwise.
+pub const DEFAULT_BACKUP_COUNT: usize = 10;
+
+/// The application shortcuts belong to when none was chosen, e.g. entries
+/// stored before shortcuts could be scoped to an application.
+pub const UNASSIGNED_APP: &str = "Unassigned";
+
+/// How much a shortcut's lookup count weighs against its search match score.
+const LOOKUP_WEIGHT: f64 = 4.0;
+
+/// How long it takes for a lookup to count half as much in the frecency
+/// ranking, unless configured otherwise.
+pub const DEFAULT_HALF_LIFE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// At most this many never reviewed shortcuts are quizzed per session, so a
+/// big import doesn't turn into one endless quiz.
+pub const NEW_CARDS_PER_SESSION: usize = 20;
+
+/// How [`Shortcuts::get_sorted_indexes`] orders shortcuts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ranking {
+    /// Often and recently looked up first, see [`Shortcuts::get_frecency`].
+    #[default]
+    Frecency,
+    /// Most looked up of all time first.
+    Lookups,
+}
+
+fn default_app() -> String {
+    UNASSIGNED_APP.to_owned()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Shortcut {
     lookup_count: u32,
-    key_combo: String,
+    key_combo: KeyCombo,
     description: String,
+
+    /// The application or context the shortcut works in, e.g. "vim" or "tmux".
+    app: String,
+
+    /// Where within the app the shortcut applies, e.g. a VS Code `when`
+    /// clause or a tmux key table. `None` means everywhere.
+    context: Option<String>,
+
+    /// Free-form labels such as "navigation" or "git", stored lowercase.
+    tags: BTreeSet<String>,
+
+    /// Seconds since the Unix epoch, `None` for shortcuts from before
+    /// these were recorded.
+    created_at: Option<u64>,
+    updated_at: Option<u64>,
+    last_looked_up: Option<u64>,
+
+    history: LookupHistory,
+
+    review: Review,
+
+    drill: DrillStats,
 }
 
 impl fmt::Display for Shortcut {
@@ -29,11 +204,21 @@ impl fmt::Display for Shortcut {
     }
 }
 impl Shortcut {
-    pub fn from(key_combo: String, description: String) -> Shortcut {
+    pub fn from(key_combo: KeyCombo, description: String) -> Shortcut {
+        let now = history::now();
         Shortcut {
             lookup_count: 0,
             key_combo,
             description,
+            app: default_app(),
+            context: None,
+            tags: BTreeSet::new(),
+            created_at: Some(now),
+            updated_at: Some(now),
+            last_looked_up: None,
+            history: LookupHistory::default(),
+            review: Review::default(),
+            drill: DrillStats::default(),
         }
     }
 
@@ -41,21 +226,145 @@ impl Shortcut {
         self.lookup_count
     }
 
-    pub fn get_key_combo(&self) -> &str {
+    pub fn get_created_at(&self) -> Option<u64> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> Option<u64> {
+        self.updated_at
+    }
+
+    pub fn get_last_looked_up(&self) -> Option<u64> {
+        self.last_looked_up
+    }
+
+    pub fn get_drill_stats(&self) -> &DrillStats {
+        &self.drill
+    }
+
+    pub fn get_key_combo(&self) -> &KeyCombo {
         &self.key_combo
     }
 
     pub fn get_description(&self) -> &str {
         &self.description
     }
+
+    pub fn set_description(&mut self, description: &str) {
+        self.description = description.trim().to_owned();
+    }
+
+    pub fn get_app(&self) -> &str {
+        &self.app
+    }
+
+    /// Sets the application the shortcut belongs to. A blank name puts it
+    /// back into the [`UNASSIGNED_APP`] group.
+    pub fn set_app(&mut self, app: &str) {
+        let app = app.trim();
+        self.app = if app.is_empty() {
+            default_app()
+        } else {
+            app.to_owned()
+        };
+    }
+
+    pub fn get_context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Limits the shortcut to part of its app, a blank context lifts the limit.
+    pub fn set_context(&mut self, context: &str) {
+        let context = context.trim();
+        self.context = (!context.is_empty()).then(|| context.to_owned());
+    }
+
+    /// Whether the combo, description, app, context and tags are those of
+    /// `other`, the parts [`Shortcuts::update`] changes.
+    pub fn has_same_details(&self, other: &Shortcut) -> bool {
+        self.key_combo == other.key_combo
+            && self.description == other.description
+            && self.app == other.app
+            && self.context == other.context
+            && self.tags == other.tags
+    }
+
+    pub fn get_tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&normalize_tag(tag))
+    }
+
+    /// Adds a tag, returns `false` if it was blank or already present.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        !tag.is_empty() && self.tags.insert(tag)
+    }
+
+    /// Replaces all tags with the ones from a comma or whitespace separated list.
+    pub fn set_tags(&mut self, tags: &str) {
+        self.tags.clear();
+        for tag in tags.split(|c: char| c == ',' || c.is_whitespace()) {
+            self.add_tag(tag);
+        }
+    }
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// An upgrade of the shortcuts file done by [`Shortcuts::load_from_file`].
+pub struct Migration {
+    /// The format version the file had.
+    pub from: u32,
+    /// Where the file was copied to before it was rewritten.
+    pub original: PathBuf,
+}
+
+/// A shortcut found by [`Shortcuts::search`].
+pub struct SearchResult {
+    pub index: usize,
+    /// Char indices of the matched characters in the combo's standard notation.
+    pub combo_matches: Vec<usize>,
+    /// Char indices of the matched characters in the description.
+    pub description_matches: Vec<usize>,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct Shortcuts {
+    /// Always [`FILE_VERSION`] once loaded, written so future versions know
+    /// how to migrate the file.
+    version: u32,
+
     shortcuts: Vec<Shortcut>,
 
     #[serde(skip_serializing)]
     save_path: Option<PathBuf>,
+
+    #[serde(skip, default = "default_backup_count")]
+    backup_count: usize,
+
+    #[serde(skip, default = "default_half_life")]
+    half_life: Duration,
+
+    #[serde(skip)]
+    undo_log: UndoLog,
+
+    /// Whether the undo log is kept in a file next to the shortcuts, so it
+    /// survives restarts.
+    #[serde(skip)]
+    journal: bool,
+}
+
+fn default_backup_count() -> usize {
+    DEFAULT_BACKUP_COUNT
+}
+
+fn default_half_life() -> Duration {
+    DEFAULT_HALF_LIFE
 }
 
 impl Default for Shortcuts {
@@ -67,8 +376,13 @@ impl Default for Shortcuts {
 impl Shortcuts {
     pub fn new() -> Self {
         Self {
+            version: FILE_VERSION,
             shortcuts: Vec::new(),
             save_path: None,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            half_life: DEFAULT_HALF_LIFE,
+            undo_log: UndoLog::default(),
+            journal: false,
         }
     }
 
@@ -76,65 +390,953 @@ impl Shortcuts {
         self.save_path = Some(new_save_path);
     }
 
+    /// Sets how many backups of the file are kept, 0 turns them off.
+    pub fn set_backup_count(&mut self, backup_count: usize) {
+        self.backup_count = backup_count;
+    }
+
+    /// Sets how quickly old lookups stop counting in the frecency ranking.
+    pub fn set_half_life(&mut self, half_life: Duration) {
+        self.half_life = half_life;
+    }
+
     pub fn get_all_shortcuts(&self) -> &[Shortcut] {
         self.shortcuts.as_ref()
     }
 
-    pub fn add_shortcut(&mut self, shortcut: Shortcut) {
+    /// Returns the names of all applications that have at least one shortcut,
+    /// sorted alphabetically.
+    pub fn get_apps(&self) -> Vec<&str> {
+        self.group_by_app().into_keys().collect()
+    }
+
+    /// Groups the indices of all shortcuts by the application they belong to.
+    pub fn group_by_app(&self) -> BTreeMap<&str, Vec<usize>> {
+        let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (i, shortcut) in self.shortcuts.iter().enumerate() {
+            groups.entry(shortcut.get_app()).or_default().push(i);
+        }
+        groups
+    }
+
+    /// Returns the indices of the shortcuts that belong to `app`.
+    pub fn get_indexes_for_app(&self, app: &str) -> Vec<usize> {
+        self.shortcuts
+            .iter()
+            .enumerate()
+            .filter(|(_, shortcut)| shortcut.get_app() == app)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the indices of the shortcuts in `app` and tagged with `tag`,
+    /// where `None` doesn't filter on that field.
+    pub fn filter_indexes(&self, app: Option<&str>, tag: Option<&str>) -> Vec<usize> {
+        let mut indexes = match app {
+            Some(app) => self.get_indexes_for_app(app),
+            None => (0..self.shortcuts.len()).collect(),
+        };
+
+        if let Some(tag) = tag {
+            let tagged = self.get_indexes_with_tag(tag);
+            indexes.retain(|i| tagged.binary_search(i).is_ok());
+        }
+
+        indexes
+    }
+
+    /// Fuzzy searches the combos and descriptions of the shortcuts at `indexes`.
+    ///
+    /// Results are ranked by their match score combined with how often they
+    /// were looked up, best first. Only the better matching of the two fields
+    /// reports its matched characters.
+    pub fn search(&self, query: &str, indexes: &[usize]) -> Vec<SearchResult> {
+        let mut ranked: Vec<(f64, SearchResult)> = indexes
+            .iter()
+            .filter_map(|&index| {
+                let shortcut = &self.shortcuts[index];
+                let combo = fuzzy_match(query, &shortcut.key_combo.to_string());
+                let description = fuzzy_match(query, &shortcut.description);
+
+                let (score, combo_matches, description_matches) = match (combo, description) {
+                    (None, None) => return None,
+                    (Some(combo), Some(description)) if description.score > combo.score => {
+                        (description.score, Vec::new(), description.positions)
+                    }
+                    (Some(combo), _) => (combo.score, combo.positions, Vec::new()),
+                    (None, Some(description)) => {
+                        (description.score, Vec::new(), description.positions)
+                    }
+                };
+
+                let lookups = f64::from(shortcut.lookup_count);
+                let rank = score as f64 + LOOKUP_WEIGHT * lookups.ln_1p();
+                Some((
+                    rank,
+                    SearchResult {
+                        index,
+                        combo_matches,
+                        description_matches,
+                    },
+                ))
+            })
+            .collect();
+
+        ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        ranked.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Returns the indices of the shortcuts tagged with `tag`.
+    pub fn get_indexes_with_tag(&self, tag: &str) -> Vec<usize> {
+        self.shortcuts
+            .iter()
+            .enumerate()
+            .filter(|(_, shortcut)| shortcut.has_tag(tag))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns every tag in use together with the number of shortcuts that
+    /// carry it, sorted alphabetically by tag.
+    pub fn get_tag_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in self.shortcuts.iter().flat_map(|s| s.get_tags()) {
+            *counts.entry(tag.as_str()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Returns the index of the shortcut with the given combo in `app` and
+    /// `context`, if any.
+    pub fn find(&self, key_combo: &KeyCombo, app: &str, context: Option<&str>) -> Option<usize> {
+        self.shortcuts.iter().position(|shortcut| {
+            shortcut.key_combo == *key_combo
+                && shortcut.app == app
+                && shortcut.context.as_deref() == context
+        })
+    }
+
+    /// Adds a shortcut and returns its index.
+    pub fn add_shortcut(&mut self, shortcut: Shortcut) -> usize {
+        let index = self.shortcuts.len();
+        self.record(
+            format!("add {}", shortcut.key_combo),
+            Change::Insert {
+                index,
+                shortcut: shortcut.clone(),
+            },
+        );
         self.shortcuts.push(shortcut);
+        index
+    }
+
+    /// Replaces the combo, description, app, context and tags of the shortcut
+    /// at `index` with those of `edited`, keeping its lookup statistics.
+    ///
+    /// Returns the shortcut as it was before, or `None` if `index` is out of range.
+    /// Nothing is recorded when all of these are unchanged.
+    pub fn update(&mut self, index: usize, edited: Shortcut) -> Option<Shortcut> {
+        let shortcut = self.shortcuts.get_mut(index)?;
+        let previous = shortcut.clone();
+        if previous.has_same_details(&edited) {
+            return Some(previous);
+        }
+
+        shortcut.key_combo = edited.key_combo;
+        shortcut.description = edited.description;
+        shortcut.app = edited.app;
+        shortcut.context = edited.context;
+        shortcut.tags = edited.tags;
+        shortcut.updated_at = Some(history::now());
+
+        let after = Box::new(shortcut.clone());
+        self.record(
+            format!("edit {}", previous.key_combo),
+            Change::Replace {
+                index,
+                before: Box::new(previous.clone()),
+                after,
+            },
+        );
+        Some(previous)
     }
 
+    /// Counts `amount` lookups of the shortcut at `index`, happening now.
     pub fn increment_lookup_count(&mut self, index: usize, amount: u32) {
+        let Some(shortcut) = self.shortcuts.get_mut(index) else {
+            return;
+        };
+        let change = Change::Lookup {
+            index,
+            amount: amount.min(u32::MAX - shortcut.lookup_count),
+            at: history::now(),
+            previous: shortcut.last_looked_up,
+        };
+        let label = format!("look up {}", shortcut.key_combo);
+
+        self.apply(&change, false);
+        self.record(label, change);
+    }
+
+    /// Shortcuts to quiz now: the overdue ones, longest overdue first, then
+    /// up to [`NEW_CARDS_PER_SESSION`] never reviewed ones.
+    pub fn get_due_indexes(&self) -> Vec<usize> {
+        let now = history::now();
+        let mut due: Vec<usize> = (0..self.shortcuts.len())
+            .filter(|&i| {
+                let review = &self.shortcuts[i].review;
+                !review.is_new() && review.is_due(now)
+            })
+            .collect();
+        due.sort_by_key(|&i| self.shortcuts[i].review.due);
+
+        due.extend(
+            (0..self.shortcuts.len())
+                .filter(|&i| self.shortcuts[i].review.is_new())
+                .take(NEW_CARDS_PER_SESSION),
+        );
+        due
+    }
+
+    /// How long until the next card that isn't due yet will be.
+    pub fn get_next_due(&self) -> Option<Duration> {
+        let now = history::now();
+        self.shortcuts
+            .iter()
+            .filter_map(|shortcut| shortcut.review.due)
+            .filter(|&due| due > now)
+            .min()
+            .map(|due| Duration::from_secs(due - now))
+    }
+
+    /// Schedules the next review of the shortcut at `index` and returns the
+    /// previous schedule, e.g. to roll back when saving fails.
+    pub fn grade_review(&mut self, index: usize, grade: Grade) -> Option<Review> {
+        let shortcut = self.shortcuts.get_mut(index)?;
+        let previous = shortcut.review;
+        shortcut.review.grade(grade, history::now());
+        Some(previous)
+    }
+
+    pub fn set_review(&mut self, index: usize, review: Review) {
         if let Some(shortcut) = self.shortcuts.get_mut(index) {
-            shortcut.lookup_count += amount;
+            shortcut.review = review;
         }
     }
 
+    /// Records a drill attempt at the shortcut at `index`, `None` for a miss.
+    ///
+    /// Returns the statistics from before, e.g. to roll back when saving fails.
+    pub fn record_drill(&mut self, index: usize, time: Option<Duration>) -> Option<DrillStats> {
+        let shortcut = self.shortcuts.get_mut(index)?;
+        let previous = shortcut.drill;
+        shortcut.drill.record(time);
+        Some(previous)
+    }
+
+    pub fn set_drill_stats(&mut self, index: usize, stats: DrillStats) {
+        if let Some(shortcut) = self.shortcuts.get_mut(index) {
+            shortcut.drill = stats;
+        }
+    }
+
+    /// How often and how recently the shortcut at `index` was looked up.
+    ///
+    /// Every lookup counts 1 when it happens and half as much with every
+    /// half-life that passes. Lookups from before the history was recorded
+    /// don't count.
+    pub fn get_frecency(&self, index: usize) -> f64 {
+        self.shortcuts.get(index).map_or(0.0, |shortcut| {
+            shortcut.history.frecency(history::now(), self.half_life)
+        })
+    }
+
     pub fn remove_at(&mut self, index: usize) -> Option<Shortcut> {
-        if index < self.shortcuts.len() {
-            Some(self.shortcuts.remove(index))
-        } else {
-            None
+        if index >= self.shortcuts.len() {
+            return None;
+        }
+
+        let removed = self.shortcuts.remove(index);
+        self.record(
+            format!("delete {}", removed.key_combo),
+            Change::Remove {
+                index,
+                shortcut: removed.clone(),
+            },
+        );
+        Some(removed)
+    }
+
+    /// Groups the changes made until [`Self::end_batch`] into one operation,
+    /// undone and redone as a whole, e.g. for an import.
+    pub fn begin_batch(&mut self, label: &str) {
+        self.undo_log.begin_batch(label.to_owned(), history::now());
+    }
+
+    pub fn end_batch(&mut self) {
+        self.undo_log.end_batch();
+    }
+
+    /// Sets how many operations can be undone.
+    pub fn set_undo_depth(&mut self, depth: usize) {
+        self.undo_log.set_depth(depth);
+    }
+
+    fn record(&mut self, label: String, change: Change) {
+        self.undo_log.record(label, history::now(), change);
+    }
+
+    /// Reverts the newest operation and returns what it was, `None` if there
+    /// is nothing to undo. The operation can be redone with [`Self::redo`].
+    pub fn undo(&mut self) -> Option<Operation> {
+        let operation = self.undo_log.pop_undo()?;
+        for change in operation.changes.iter().rev() {
+            self.apply(change, true);
+        }
+        self.undo_log.push_redo(operation.clone());
+        Some(operation)
+    }
+
+    /// Applies the newest undone operation again.
+    pub fn redo(&mut self) -> Option<Operation> {
+        let operation = self.undo_log.pop_redo()?;
+        for change in &operation.changes {
+            self.apply(change, false);
         }
+        self.undo_log.push_undo(operation.clone());
+        Some(operation)
     }
 
-    /// Returns the indices of all shortcuts, sorted in descending order of `lookup_count`.
+    /// Reverts the newest operation for good, e.g. when saving it failed.
+    pub fn rollback(&mut self) {
+        if let Some(operation) = self.undo_log.pop_undo() {
+            for change in operation.changes.iter().rev() {
+                self.apply(change, true);
+            }
+        }
+    }
+
+    /// Applies `change`, or its opposite if `reverse` is set.
     ///
-    /// The returned vector contains the zero-based indices into `self.shortcuts`,
-    /// ordered so that the shortcut with the highest `lookup_count` appears first.
-    /// This can be used to display shortcuts ranked by their popularity or usage frequency.
+    /// Quiz and drill progress isn't part of the undo log, replacing a
+    /// shortcut keeps the current one.
+    fn apply(&mut self, change: &Change, reverse: bool) {
+        match (change, reverse) {
+            (Change::Insert { index, shortcut }, false)
+            | (Change::Remove { index, shortcut }, true) => {
+                let index = (*index).min(self.shortcuts.len());
+                self.shortcuts.insert(index, shortcut.clone());
+            }
+            (Change::Insert { index, .. }, true) | (Change::Remove { index, .. }, false) => {
+                if *index < self.shortcuts.len() {
+                    self.shortcuts.remove(*index);
+                }
+            }
+            (
+                Change::Replace {
+                    index,
+                    before,
+                    after,
+                },
+                reverse,
+            ) => {
+                if let Some(shortcut) = self.shortcuts.get_mut(*index) {
+                    let replacement = if reverse { before } else { after };
+                    *shortcut = Shortcut {
+                        review: shortcut.review,
+                        drill: shortcut.drill,
+                        ..Shortcut::clone(replacement)
+                    };
+                }
+            }
+            (Change::ReplaceAll { before, after }, reverse) => {
+                self.shortcuts = if reverse { before } else { after }.clone();
+            }
+            (
+                Change::Lookup {
+                    index,
+                    amount,
+                    at,
+                    previous,
+                },
+                reverse,
+            ) => {
+                let Some(shortcut) = self.shortcuts.get_mut(*index) else {
+                    return;
+                };
+                if reverse {
+                    shortcut.lookup_count = shortcut.lookup_count.saturating_sub(*amount);
+                    shortcut.last_looked_up = *previous;
+                    shortcut.history.unrecord(*at, *amount);
+                } else {
+                    shortcut.lookup_count = shortcut.lookup_count.saturating_add(*amount);
+                    shortcut.last_looked_up = Some(*at);
+                    shortcut.history.record(*at, *amount);
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of all shortcuts, best ranked first.
+    ///
+    /// The returned vector contains the zero-based indices into `self.shortcuts`.
+    /// [`Ranking::Frecency`], the default, puts often and recently looked up
+    /// shortcuts first, with the lookup count breaking ties.
+    /// [`Ranking::Lookups`] orders by `lookup_count` alone.
     ///
     /// # Examples
     /// ```
-    /// let sorted_indices = shortcuts.get_sorted_indexes();
-    /// // `sorted_indices[0]` is the index of the most frequently looked-up shortcut.
+    /// let sorted_indices = shortcuts.get_sorted_indexes(Ranking::default());
+    /// // `sorted_indices[0]` is the index of the shortcut used most lately.
     /// ```
-    pub fn get_sorted_indexes(&self) -> Vec<usize> {
+    pub fn get_sorted_indexes(&self, ranking: Ranking) -> Vec<usize> {
         // get all indexes
         let mut indexes: Vec<usize> = (0..self.shortcuts.len()).collect();
-        // sort them by lookup count desc
-        indexes.sort_by_key(|&i| Reverse(self.shortcuts[i].lookup_count));
+        match ranking {
+            // sort them by lookup count desc
+            Ranking::Lookups => indexes.sort_by_key(|&i| Reverse(self.shortcuts[i].lookup_count)),
+            Ranking::Frecency => {
+                let frecencies: Vec<f64> = indexes.iter().map(|&i| self.get_frecency(i)).collect();
+                // lookup count breaks ties, e.g. between shortcuts without a history
+                indexes.sort_by(|&a, &b| {
+                    frecencies[b].total_cmp(&frecencies[a]).then(
+                        self.shortcuts[b]
+                            .lookup_count
+                            .cmp(&self.shortcuts[a].lookup_count),
+                    )
+                });
+            }
+        }
         indexes
     }
 
-    pub fn save(&self) -> Result<(), std::io::Error> {
-        // TODO: remove this unwrap
-        let file = File::create(self.save_path.clone().unwrap())?;
-        serde_json::to_writer(file, &self)?;
+    /// Writes the shortcuts to the save path, backing up the previous file first.
+    ///
+    /// The file is replaced atomically, so a failed save leaves the old
+    /// contents intact.
+    pub fn save(&self) -> Result<(), SaveError> {
+        self.write(false)
+    }
+
+    fn write(&self, force_backup: bool) -> Result<(), SaveError> {
+        let path = self.save_path.as_ref().ok_or(SaveError::NoPath)?;
+        let contents = serde_json::to_vec(&self)?;
+
+        storage::back_up(path, self.backup_count, force_backup)?;
+        storage::write_atomically(path, &contents)?;
+
+        if self.journal {
+            let journal = storage::Journal {
+                fingerprint: self.fingerprint(),
+                log: self.undo_log.clone(),
+            };
+            storage::write_atomically(
+                &storage::journal_path(path),
+                &serde_json::to_vec(&journal)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Keeps the undo log in a file next to the shortcuts from now on, and
+    /// picks up the one from the last run.
+    ///
+    /// A journal written for different contents, e.g. before the file was
+    /// edited by hand or restored by an older shotty, would undo the wrong
+    /// shortcuts and is ignored.
+    pub fn enable_journal(&mut self) -> Result<(), LoadError> {
+        self.journal = true;
+        let Some(path) = &self.save_path else {
+            return Ok(());
+        };
+
+        let contents = match std::fs::read(storage::journal_path(path)) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        let journal: storage::Journal =
+            serde_json::from_slice(&contents).map_err(LoadError::Invalid)?;
 
+        if journal.fingerprint == self.fingerprint() {
+            let depth = self.undo_log.get_depth();
+            self.undo_log = journal.log;
+            self.undo_log.set_depth(depth);
+        }
         Ok(())
     }
 
-    /// Loads shortcuts from a JSON file.
+    pub fn has_journal(&self) -> bool {
+        self.journal
+    }
+
+    /// A hash of the shortcuts, to tell whether a journal belongs to them.
+    fn fingerprint(&self) -> u64 {
+        storage::fingerprint(&serde_json::to_vec(&self.shortcuts).unwrap_or_default())
+    }
+
+    /// Backups of the save path, newest first.
+    pub fn list_backups(&self) -> Result<Vec<Backup>, SaveError> {
+        let path = self.save_path.as_ref().ok_or(SaveError::NoPath)?;
+        Ok(storage::list_backups(path)?)
+    }
+
+    /// Replaces the shortcuts with `restored`, e.g. read from a backup, and
+    /// saves them.
+    ///
+    /// The current file is backed up first, so a restore can be undone by
+    /// restoring that backup.
+    pub fn restore(&mut self, restored: Shortcuts) -> Result<(), SaveError> {
+        let before = std::mem::replace(&mut self.shortcuts, restored.shortcuts);
+        self.record(
+            "restore a backup".to_owned(),
+            Change::ReplaceAll {
+                before,
+                after: self.shortcuts.clone(),
+            },
+        );
+        self.write(true)
+    }
+
+    /// Loads the shortcuts file at `path` and makes it the save path.
     ///
-    /// If the file exists, it is read and deserialized into a `Shortcuts` instance.
-    /// If it does not exist, a error is returned.
-    /// In the path is valid, the `save_path` is set to the chosen path.
-    pub fn load_from_file(path: PathBuf) -> Result<Shortcuts, std::io::Error> {
-        let contents = std::fs::read_to_string(&path)?;
-        let mut shortcuts: Shortcuts = serde_json::from_str(&contents)?;
+    /// A file in an older format is upgraded and rewritten, after a copy of
+    /// the original was put next to the backups.
+    pub fn load_from_file(path: PathBuf) -> Result<(Shortcuts, Option<Migration>), LoadError> {
+        let (mut shortcuts, version) = Self::read(&path)?;
+        if version == FILE_VERSION {
+            shortcuts.set_save_path(path);
+            return Ok((shortcuts, None));
+        }
 
+        let original = storage::keep_original(&path, version)?;
         shortcuts.set_save_path(path);
-        Ok(shortcuts)
+        shortcuts.write(false).map_err(|err| match err {
+            SaveError::Io(err) => LoadError::Io(err),
+            SaveError::NoPath => unreachable!("the save path was just set"),
+        })?;
+
+        Ok((
+            shortcuts,
+            Some(Migration {
+                from: version,
+                original,
+            }),
+        ))
+    }
+
+    /// Reads a shortcuts file, e.g. a backup, without changing it on disk.
+    pub fn read_from_file(path: &Path) -> Result<Shortcuts, LoadError> {
+        Self::read(path).map(|(shortcuts, _)| shortcuts)
+    }
+
+    fn read(path: &Path) -> Result<(Shortcuts, u32), LoadError> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut document: Value = serde_json::from_str(&contents).map_err(LoadError::Invalid)?;
+        let version = migrate(&mut document)?;
+        let shortcuts = serde_json::from_value(document).map_err(LoadError::Invalid)?;
+        Ok((shortcuts, version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// One shortcut as each older version wrote it, oldest first.
+    fn fixtures() -> [Value; FILE_VERSION as usize - 1] {
+        let v1 = json!({
+            "shortcuts": [
+                { "lookup_count": 3, "key_combo": "Ctrl + Shift + P", "description": "palette" }
+            ]
+        });
+
+        let mut v2 = v1.clone();
+        v2["version"] = json!(2);
+        v2["shortcuts"][0]["app"] = json!("vscode");
+        v2["shortcuts"][0]["tags"] = json!(["editing"]);
+
+        let mut v3 = v2.clone();
+        v3["version"] = json!(3);
+        v3["shortcuts"][0]["created_at"] = json!(1_700_000_000);
+        v3["shortcuts"][0]["updated_at"] = json!(1_700_000_100);
+        v3["shortcuts"][0]["last_looked_up"] = json!(1_700_000_200);
+        v3["shortcuts"][0]["history"] = json!([{ "at": 1_700_000_200, "count": 3 }]);
+
+        let mut v4 = v3.clone();
+        v4["version"] = json!(4);
+        v4["shortcuts"][0]["review"] =
+            json!({ "ease": 2.2, "interval": 6, "repetitions": 2, "due": 1_700_500_000 });
+
+        let mut v5 = v4.clone();
+        v5["version"] = json!(5);
+        v5["shortcuts"][0]["drill"] =
+            json!({ "attempts": 4, "hits": 3, "best_ms": 420, "total_ms": 1500 });
+
+        [v1, v2, v3, v4, v5]
+    }
+
+    fn load(mut document: Value) -> (Shortcuts, u32) {
+        let version = migrate(&mut document).unwrap();
+        assert_eq!(document["version"], json!(FILE_VERSION));
+        (serde_json::from_value(document).unwrap(), version)
+    }
+
+    #[test]
+    fn every_version_migrates_to_the_current_one() {
+        for (i, fixture) in fixtures().into_iter().enumerate() {
+            let (shortcuts, version) = load(fixture);
+            assert_eq!(version, i as u32 + 1);
+            assert_eq!(shortcuts.version, FILE_VERSION);
+
+            let [shortcut] = shortcuts.get_all_shortcuts() else {
+                panic!("v{version}: expected one shortcut");
+            };
+            assert_eq!(shortcut.get_key_combo().to_string(), "Ctrl+Shift+P");
+            assert_eq!(shortcut.get_description(), "palette");
+            assert_eq!(shortcut.get_lookup_count(), 3);
+            assert_eq!(shortcut.get_context(), None);
+        }
+    }
+
+    #[test]
+    fn migrations_fill_in_defaults() {
+        let [v1, v2, v3, v4, _] = fixtures();
+
+        let (shortcuts, _) = load(v1);
+        let shortcut = &shortcuts.get_all_shortcuts()[0];
+        assert_eq!(shortcut.get_app(), UNASSIGNED_APP);
+        assert!(shortcut.get_tags().is_empty());
+
+        let (shortcuts, _) = load(v2);
+        let shortcut = &shortcuts.get_all_shortcuts()[0];
+        assert_eq!(shortcut.get_app(), "vscode");
+        assert_eq!(shortcut.get_created_at(), None);
+        assert_eq!(shortcut.get_last_looked_up(), None);
+
+        let (shortcuts, _) = load(v3);
+        assert_eq!(shortcuts.get_all_shortcuts()[0].review, Review::default());
+
+        let (shortcuts, _) = load(v4);
+        assert_eq!(
+            shortcuts.get_all_shortcuts()[0].get_drill_stats(),
+            &DrillStats::default()
+        );
+    }
+
+    #[test]
+    fn migrations_keep_newer_fields() {
+        let [.., v5] = fixtures();
+        let (shortcuts, version) = load(v5);
+        assert_eq!(version, 5);
+
+        let shortcut = &shortcuts.get_all_shortcuts()[0];
+        assert_eq!(shortcut.get_tags().iter().collect::<Vec<_>>(), ["editing"]);
+        assert_eq!(shortcut.get_created_at(), Some(1_700_000_000));
+        assert_eq!(shortcut.get_updated_at(), Some(1_700_000_100));
+        assert_eq!(shortcut.get_last_looked_up(), Some(1_700_000_200));
+        assert_eq!(shortcut.review.interval, 6);
+        assert_eq!(shortcut.review.due, Some(1_700_500_000));
+        assert_eq!(shortcut.get_drill_stats().best_ms, Some(420));
+    }
+
+    #[test]
+    fn current_files_are_left_as_they_are() {
+        let [.., v5] = fixtures();
+        let (shortcuts, _) = load(v5);
+        let current = serde_json::to_value(&shortcuts).unwrap();
+
+        let mut document = current.clone();
+        assert_eq!(migrate(&mut document).unwrap(), FILE_VERSION);
+        assert_eq!(document, current);
+    }
+
+    #[test]
+    fn bad_versions_are_rejected() {
+        for version in [json!(0), json!(-1), json!("2"), json!(1.5)] {
+            let mut document = json!({ "version": version, "shortcuts": [] });
+            assert!(matches!(migrate(&mut document), Err(LoadError::BadVersion)));
+        }
+
+        let mut document = json!({ "version": FILE_VERSION + 1, "shortcuts": [] });
+        assert!(matches!(
+            migrate(&mut document),
+            Err(LoadError::TooNew { version }) if version == FILE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn loading_an_old_file_keeps_the_original() {
+        let dir = std::env::temp_dir().join(format!("shotty-migrate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shortcuts.json");
+        let [v1, ..] = fixtures();
+        std::fs::write(&path, v1.to_string()).unwrap();
+
+        let (shortcuts, migration) = Shortcuts::load_from_file(path.clone()).unwrap();
+        let migration = migration.unwrap();
+        assert_eq!(migration.from, 1);
+        assert_eq!(
+            std::fs::read_to_string(&migration.original).unwrap(),
+            v1.to_string()
+        );
+        assert_eq!(shortcuts.get_apps(), [UNASSIGNED_APP]);
+        assert_eq!(shortcuts.get_indexes_for_app(UNASSIGNED_APP), [0]);
+
+        let (_, migration) = Shortcuts::load_from_file(path).unwrap();
+        assert!(migration.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn shortcut(combo: &str, description: &str) -> Shortcut {
+        Shortcut::from(combo.parse().unwrap(), description.to_owned())
+    }
+
+    fn descriptions(shortcuts: &Shortcuts) -> Vec<&str> {
+        shortcuts
+            .get_all_shortcuts()
+            .iter()
+            .map(Shortcut::get_description)
+            .collect()
+    }
+
+    fn in_app(combo: &str, description: &str, app: &str) -> Shortcut {
+        let mut shortcut = shortcut(combo, description);
+        shortcut.set_app(app);
+        shortcut
+    }
+
+    #[test]
+    fn blank_apps_are_unassigned() {
+        let mut copy = in_app("Ctrl+C", "copy", "  vim ");
+        assert_eq!(copy.get_app(), "vim");
+        copy.set_app(" ");
+        assert_eq!(copy.get_app(), UNASSIGNED_APP);
+        assert_eq!(shortcut("Ctrl+C", "copy").get_app(), UNASSIGNED_APP);
+    }
+
+    #[test]
+    fn shortcuts_group_by_app() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(in_app("Ctrl+C", "copy", "vscode"));
+        shortcuts.add_shortcut(in_app("y", "yank", "vim"));
+        shortcuts.add_shortcut(shortcut("Ctrl+Alt+T", "terminal"));
+        shortcuts.add_shortcut(in_app("Ctrl+P", "open file", "vscode"));
+
+        assert_eq!(shortcuts.get_apps(), [UNASSIGNED_APP, "vim", "vscode"]);
+        assert_eq!(shortcuts.get_indexes_for_app("vscode"), [0, 3]);
+        assert_eq!(shortcuts.get_indexes_for_app("VSCode"), [] as [usize; 0]);
+        assert_eq!(shortcuts.filter_indexes(Some("vim"), None), [1]);
+        assert_eq!(shortcuts.filter_indexes(None, None), [0, 1, 2, 3]);
+
+        let groups = shortcuts.group_by_app();
+        assert_eq!(groups[UNASSIGNED_APP], [2]);
+        assert_eq!(groups["vscode"], [0, 3]);
+    }
+
+    fn tagged(combo: &str, description: &str, app: &str, tags: &str) -> Shortcut {
+        let mut shortcut = in_app(combo, description, app);
+        shortcut.set_tags(tags);
+        shortcut
+    }
+
+    #[test]
+    fn tags_are_normalized() {
+        let mut copy = tagged("Ctrl+C", "copy", "vscode", " Editing, clipboard  EDITING,,");
+        let tags: Vec<&str> = copy.get_tags().iter().map(String::as_str).collect();
+        assert_eq!(tags, ["clipboard", "editing"]);
+        assert!(copy.has_tag(" Clipboard "));
+
+        assert!(!copy.add_tag("editing"));
+        assert!(!copy.add_tag("  "));
+        assert!(copy.add_tag("Basics"));
+        assert!(copy.has_tag("basics"));
+
+        copy.set_tags("");
+        assert!(copy.get_tags().is_empty());
+    }
+
+    #[test]
+    fn app_and_tag_filters_combine() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(tagged("Ctrl+C", "copy", "vscode", "editing"));
+        shortcuts.add_shortcut(tagged("Ctrl+G", "go to line", "vscode", "navigation"));
+        shortcuts.add_shortcut(tagged("y", "yank", "vim", "editing, registers"));
+        shortcuts.add_shortcut(shortcut("Ctrl+Alt+T", "terminal"));
+
+        assert_eq!(shortcuts.get_indexes_with_tag("Editing"), [0, 2]);
+        assert_eq!(shortcuts.filter_indexes(None, Some("editing")), [0, 2]);
+        assert_eq!(
+            shortcuts.filter_indexes(Some("vscode"), Some("editing")),
+            [0]
+        );
+        assert_eq!(
+            shortcuts.filter_indexes(Some("vim"), Some("navigation")),
+            [] as [usize; 0]
+        );
+        assert_eq!(shortcuts.filter_indexes(Some(UNASSIGNED_APP), None), [3]);
+        assert_eq!(
+            shortcuts.filter_indexes(None, Some("unknown")),
+            [] as [usize; 0]
+        );
+
+        let counts: Vec<(&str, usize)> = shortcuts.get_tag_counts().into_iter().collect();
+        assert_eq!(
+            counts,
+            [("editing", 2), ("navigation", 1), ("registers", 1)]
+        );
+    }
+
+    #[test]
+    fn undo_and_redo_adds_and_deletes() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.add_shortcut(shortcut("Ctrl+V", "paste"));
+        shortcuts.remove_at(0);
+        assert_eq!(descriptions(&shortcuts), ["paste"]);
+
+        assert_eq!(shortcuts.undo().unwrap().label, "delete Ctrl+C");
+        assert_eq!(descriptions(&shortcuts), ["copy", "paste"]);
+        shortcuts.undo();
+        assert_eq!(descriptions(&shortcuts), ["copy"]);
+
+        assert_eq!(shortcuts.redo().unwrap().label, "add Ctrl+V");
+        shortcuts.redo();
+        assert_eq!(descriptions(&shortcuts), ["paste"]);
+        assert!(shortcuts.redo().is_none());
+    }
+
+    #[test]
+    fn a_new_change_drops_what_could_be_redone() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.undo();
+        shortcuts.add_shortcut(shortcut("Ctrl+V", "paste"));
+        assert!(shortcuts.redo().is_none());
+        assert_eq!(descriptions(&shortcuts), ["paste"]);
+    }
+
+    #[test]
+    fn undo_edits() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.update(0, shortcut("Ctrl+Shift+C", "copy path"));
+
+        shortcuts.undo();
+        let copy = &shortcuts.get_all_shortcuts()[0];
+        assert_eq!(copy.get_key_combo().to_string(), "Ctrl+C");
+        assert_eq!(copy.get_description(), "copy");
+
+        shortcuts.redo();
+        assert_eq!(descriptions(&shortcuts), ["copy path"]);
+    }
+
+    #[test]
+    fn unchanged_edits_are_not_recorded() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.update(0, shortcut("Ctrl+C", "copy"));
+        assert_eq!(shortcuts.undo().unwrap().label, "add Ctrl+C");
+    }
+
+    #[test]
+    fn undo_lookups() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.increment_lookup_count(0, 1);
+        shortcuts.increment_lookup_count(0, 2);
+        assert_eq!(shortcuts.get_all_shortcuts()[0].get_lookup_count(), 3);
+
+        shortcuts.undo();
+        let copy = &shortcuts.get_all_shortcuts()[0];
+        assert_eq!(copy.get_lookup_count(), 1);
+        assert!(copy.get_last_looked_up().is_some());
+        assert!((shortcuts.get_frecency(0) - 1.0).abs() < 0.01);
+
+        shortcuts.undo();
+        let copy = &shortcuts.get_all_shortcuts()[0];
+        assert_eq!(copy.get_lookup_count(), 0);
+        assert_eq!(copy.get_last_looked_up(), None);
+        assert_eq!(shortcuts.get_frecency(0), 0.0);
+
+        shortcuts.redo();
+        shortcuts.redo();
+        assert_eq!(shortcuts.get_all_shortcuts()[0].get_lookup_count(), 3);
+        assert!((shortcuts.get_frecency(0) - 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn lookups_are_recorded_as_small_changes() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.increment_lookup_count(0, 1);
+
+        let operation = shortcuts.undo().unwrap();
+        assert!(matches!(
+            operation.changes[..],
+            [Change::Lookup {
+                index: 0,
+                amount: 1,
+                previous: None,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn batches_undo_as_a_whole() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.begin_batch("import");
+        shortcuts.add_shortcut(shortcut("Ctrl+V", "paste"));
+        shortcuts.update(0, shortcut("Ctrl+C", "copy text"));
+        shortcuts.end_batch();
+
+        assert_eq!(shortcuts.undo().unwrap().label, "import");
+        assert_eq!(descriptions(&shortcuts), ["copy"]);
+        shortcuts.redo();
+        assert_eq!(descriptions(&shortcuts), ["copy text", "paste"]);
+    }
+
+    #[test]
+    fn undo_depth_limits_the_history() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.set_undo_depth(2);
+        for key in ["A", "B", "C"] {
+            shortcuts.add_shortcut(shortcut(&format!("Ctrl+{key}"), key));
+        }
+        assert!(shortcuts.undo().is_some());
+        assert!(shortcuts.undo().is_some());
+        assert!(shortcuts.undo().is_none());
+        assert_eq!(descriptions(&shortcuts), ["A"]);
+    }
+
+    #[test]
+    fn the_journal_survives_a_restart() {
+        let dir = std::env::temp_dir().join(format!("shotty-journal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shortcuts.json");
+
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.set_save_path(path.clone());
+        shortcuts.enable_journal().unwrap();
+        shortcuts.add_shortcut(shortcut("Ctrl+C", "copy"));
+        shortcuts.increment_lookup_count(0, 1);
+        shortcuts.save().unwrap();
+
+        let (mut reloaded, _) = Shortcuts::load_from_file(path.clone()).unwrap();
+        reloaded.enable_journal().unwrap();
+        assert_eq!(reloaded.undo().unwrap().label, "look up Ctrl+C");
+        assert_eq!(reloaded.get_all_shortcuts()[0].get_lookup_count(), 0);
+
+        // a file changed behind shotty's back doesn't get the old journal
+        let (mut edited, _) = Shortcuts::load_from_file(path.clone()).unwrap();
+        edited.shortcuts[0].description = "copy text".into();
+        edited.enable_journal().unwrap();
+        assert!(edited.undo().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
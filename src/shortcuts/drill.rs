@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How fast and how accurately a shortcut was pressed in drills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrillStats {
+    pub attempts: u32,
+    /// Attempts where the right combo was pressed.
+    pub hits: u32,
+    /// Fastest hit in milliseconds.
+    pub best_ms: Option<u32>,
+    /// Sum of the times of all hits in milliseconds, for the average.
+    pub total_ms: u64,
+}
+
+impl DrillStats {
+    /// Records an attempt, `None` for a miss.
+    pub fn record(&mut self, time: Option<Duration>) {
+        self.attempts += 1;
+        let Some(time) = time else {
+            return;
+        };
+
+        let ms = u32::try_from(time.as_millis()).unwrap_or(u32::MAX);
+        self.hits += 1;
+        self.total_ms += u64::from(ms);
+
+        if self.best_ms.is_none_or(|best| ms < best) {
+            self.best_ms = Some(ms);
+        }
+    }
+
+    pub fn best(&self) -> Option<Duration> {
+        self.best_ms.map(|ms| Duration::from_millis(ms.into()))
+    }
+
+    /// Average time of the hits.
+    pub fn average(&self) -> Option<Duration> {
+        (self.hits > 0).then(|| Duration::from_millis(self.total_ms / u64::from(self.hits)))
+    }
+
+    /// Share of attempts that were hits, `None` before the first attempt.
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| f64::from(self.hits) / f64::from(self.attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Option<Duration> {
+        Some(Duration::from_millis(ms))
+    }
+
+    #[test]
+    fn keeps_the_best_and_the_average_of_hits() {
+        let mut stats = DrillStats::default();
+        assert_eq!(stats.best(), None);
+        assert_eq!(stats.average(), None);
+        assert_eq!(stats.accuracy(), None);
+
+        stats.record(ms(900));
+        stats.record(None);
+        stats.record(ms(600));
+        stats.record(ms(750));
+        assert_eq!((stats.attempts, stats.hits), (4, 3));
+        assert_eq!(stats.best(), ms(600));
+        assert_eq!(stats.average(), ms(750));
+        assert_eq!(stats.accuracy(), Some(0.75));
+    }
+
+    #[test]
+    fn misses_only_lower_the_accuracy() {
+        let mut stats = DrillStats::default();
+        stats.record(None);
+        stats.record(None);
+        assert_eq!((stats.attempts, stats.hits), (2, 0));
+        assert_eq!(stats.best(), None);
+        assert_eq!(stats.average(), None);
+        assert_eq!(stats.accuracy(), Some(0.0));
+    }
+
+    #[test]
+    fn a_slower_hit_keeps_the_best() {
+        let mut stats = DrillStats::default();
+        stats.record(ms(500));
+        stats.record(ms(1200));
+        assert_eq!(stats.best_ms, Some(500));
+        assert_eq!(stats.total_ms, 1700);
+    }
+
+    #[test]
+    fn huge_times_saturate() {
+        let mut stats = DrillStats::default();
+        stats.record(Some(Duration::from_secs(u64::MAX)));
+        assert_eq!(stats.best_ms, Some(u32::MAX));
+    }
+}
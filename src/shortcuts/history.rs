@@ -0,0 +1,127 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Lookups younger than this are kept one by one, older ones are merged into
+/// one entry per week.
+const EXACT_WINDOW: u64 = 30 * DAY;
+
+const DAY: u64 = 24 * 60 * 60;
+const WEEK: u64 = 7 * DAY;
+
+/// The current time as seconds since the Unix epoch, how all timestamps in the
+/// shortcuts file are stored.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// `count` lookups at `at`, in seconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LookupEvent {
+    pub at: u64,
+    pub count: u32,
+}
+
+/// When a shortcut was looked up, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LookupHistory {
+    events: Vec<LookupEvent>,
+}
+
+impl LookupHistory {
+    /// Records `count` lookups at `now` and compacts what fell out of the
+    /// exact window.
+    pub fn record(&mut self, now: u64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        self.events.push(LookupEvent { at: now, count });
+        self.compact(now);
+    }
+
+    /// Takes back `count` lookups recorded at `at`, which may have been merged
+    /// into their week since.
+    pub fn unrecord(&mut self, at: u64, count: u32) {
+        let week = at - at % WEEK;
+        let Some(position) = self
+            .events
+            .iter()
+            .rposition(|event| event.at == at)
+            .or_else(|| self.events.iter().rposition(|event| event.at == week))
+        else {
+            return;
+        };
+
+        let event = &mut self.events[position];
+        event.count = event.count.saturating_sub(count);
+        if event.count == 0 {
+            self.events.remove(position);
+        }
+    }
+
+    /// Merges the events older than [`EXACT_WINDOW`] into one per week, so
+    /// the history stays small while old lookups still count, just less.
+    fn compact(&mut self, now: u64) {
+        let cutoff = now.saturating_sub(EXACT_WINDOW);
+        let mut compacted: Vec<LookupEvent> = Vec::with_capacity(self.events.len());
+
+        // the clock may have been set back since an earlier lookup
+        self.events.sort_by_key(|event| event.at);
+
+        for event in self.events.drain(..) {
+            if event.at >= cutoff {
+                compacted.push(event);
+                continue;
+            }
+
+            let week = event.at - event.at % WEEK;
+            match compacted.last_mut() {
+                Some(last) if last.at == week => {
+                    last.count = last.count.saturating_add(event.count)
+                }
+                _ => compacted.push(LookupEvent {
+                    at: week,
+                    count: event.count,
+                }),
+            }
+        }
+        self.events = compacted;
+    }
+
+    /// The sum of all lookups, each weighted by `0.5^(age / half_life)`: a
+    /// lookup now counts 1, one a half-life ago 0.5.
+    pub fn frecency(&self, now: u64, half_life: Duration) -> f64 {
+        let half_life = half_life.as_secs_f64().max(1.0);
+        // a fold from 0.0, as `sum` of nothing is -0.0, which sorts below 0.0
+        self.events.iter().fold(0.0, |frecency, event| {
+            let age = now.saturating_sub(event.at) as f64;
+            frecency + event.count as f64 * (-age / half_life).exp2()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_history_is_positive_zero() {
+        let frecency = LookupHistory::default().frecency(1_000 * DAY, Duration::from_secs(DAY));
+        assert_eq!(frecency.to_bits(), 0.0_f64.to_bits());
+    }
+
+    #[test]
+    fn lookups_halve_every_half_life() {
+        let now = 1_000 * DAY;
+        let mut history = LookupHistory::default();
+        history.record(now - 2 * DAY, 4);
+        history.record(now, 1);
+
+        let frecency = history.frecency(now, Duration::from_secs(DAY));
+        assert!((frecency - 2.0).abs() < 1e-9, "{frecency}");
+    }
+}
@@ -0,0 +1,792 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The textual notations a [`KeyCombo`] can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// `Ctrl+Shift+P`, `Ctrl+K Ctrl+C`. This is the canonical, stored form.
+    #[default]
+    Standard,
+    /// `C-x C-s`, `M-f`, `s-RET`.
+    Emacs,
+    /// `<C-w>j`, `gg`, `<lt>` style sequences without separators.
+    Vim,
+}
+
+/// Modifier keys held down while pressing a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        alt: false,
+        shift: false,
+        super_key: false,
+    };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+/// A single non-modifier key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Key {
+    /// A printable character. Letters are always stored lowercase, shift is
+    /// tracked in [`Modifiers`].
+    Char(char),
+    F(u8),
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+}
+
+/// One step of a [`KeyCombo`]: a key together with its modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyStroke {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+/// A parsed keyboard shortcut.
+///
+/// Most shortcuts are a single [`KeyStroke`] (`Ctrl+S`), but chords made of
+/// several strokes pressed one after another (`Ctrl+K Ctrl+C`, `g g`,
+/// `C-x C-s`) are supported as well.
+///
+/// Combos serialize to their [`Notation::Standard`] text, so files that stored
+/// the combo as a free-form string keep loading and are normalized on the way in.
+/// Text that can't be parsed at all is kept as written rather than failing
+/// the whole file, see [`KeyCombo::is_parsed`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyCombo {
+    strokes: Vec<KeyStroke>,
+    /// The stored text of a combo that didn't parse, e.g. `ctrl+numpad_add`.
+    unparsed: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyComboParseError {
+    Empty,
+    UnknownKey(String),
+    MissingKey(String),
+    UnclosedBracket(String),
+}
+
+impl fmt::Display for KeyComboParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyComboParseError::Empty => write!(f, "shortcut is empty"),
+            KeyComboParseError::UnknownKey(key) => write!(f, "unknown key `{key}`"),
+            KeyComboParseError::MissingKey(stroke) => {
+                write!(f, "`{stroke}` has modifiers but no key")
+            }
+            KeyComboParseError::UnclosedBracket(stroke) => {
+                write!(f, "`{stroke}` is missing a closing `>`")
+            }
+        }
+    }
+}
+
+impl Error for KeyComboParseError {}
+
+impl KeyStroke {
+    pub fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self { modifiers, key }.normalized()
+    }
+
+    /// Converts a key press reported by the terminal into a stroke.
+    ///
+    /// Returns `None` for keys that can't be part of a shortcut, like a lone
+    /// modifier or media keys.
+    pub fn from_key_event(event: &KeyEvent) -> Option<Self> {
+        let mut modifiers = Modifiers {
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+            alt: event
+                .modifiers
+                .intersects(KeyModifiers::ALT | KeyModifiers::META),
+            shift: event.modifiers.contains(KeyModifiers::SHIFT),
+            super_key: event.modifiers.contains(KeyModifiers::SUPER),
+        };
+
+        let key = match event.code {
+            KeyCode::Char(c) => {
+                // for `!`, `?` and friends the shift is already part of the character
+                if !c.is_alphabetic() {
+                    modifiers.shift = false;
+                }
+                Key::Char(c)
+            }
+            KeyCode::F(n) => Key::F(n),
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::BackTab => {
+                modifiers.shift = true;
+                Key::Tab
+            }
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            _ => return None,
+        };
+
+        Some(KeyStroke::new(modifiers, key))
+    }
+
+    /// Folds uppercase letters into lowercase + shift so that `Ctrl+P` typed
+    /// as `C-P` and `Ctrl+Shift+p` compare equal.
+    fn normalized(mut self) -> Self {
+        if let Key::Char(c) = self.key {
+            if c == ' ' {
+                self.key = Key::Space;
+            } else if c.is_uppercase() {
+                self.key = Key::Char(c.to_lowercase().next().unwrap_or(c));
+                self.modifiers.shift = true;
+            }
+        }
+        self
+    }
+
+    /// Parses a single stroke such as `Ctrl+Shift+P`, `C-x` or `M-RET`.
+    ///
+    /// `notation` decides what an uppercase letter after a modifier means.
+    fn parse(text: &str, notation: Notation) -> Result<Self, KeyComboParseError> {
+        let mut modifiers = Modifiers::NONE;
+        let mut rest = text;
+
+        // caret notation used by terminals and readline: ^X
+        if let Some(key) = rest.strip_prefix('^') {
+            if key.chars().count() == 1 && key != "^" {
+                modifiers.ctrl = true;
+                rest = key;
+            }
+        }
+
+        while let Some((modifier, remaining)) = split_modifier(rest) {
+            match modifier {
+                Modifier::Ctrl => modifiers.ctrl = true,
+                Modifier::Alt => modifiers.alt = true,
+                Modifier::Shift => modifiers.shift = true,
+                Modifier::Super => modifiers.super_key = true,
+            }
+            rest = remaining;
+        }
+
+        if rest.is_empty() {
+            return Err(KeyComboParseError::MissingKey(text.to_owned()));
+        }
+
+        let mut key =
+            parse_key(rest).ok_or_else(|| KeyComboParseError::UnknownKey(rest.to_owned()))?;
+
+        // in standard notation the letter case is cosmetic: `Ctrl+S` is not
+        // `Ctrl+Shift+S`, but emacs and vim spell shift that way: `C-S` is `C-S-s`.
+        // Vim can't tell `<C-S>` from `<C-s>` though.
+        if let Key::Char(c) = key {
+            if c.is_uppercase() && !modifiers.is_empty() {
+                key = Key::Char(c.to_lowercase().next().unwrap_or(c));
+                modifiers.shift |= match notation {
+                    Notation::Standard => false,
+                    Notation::Emacs => true,
+                    Notation::Vim => !modifiers.ctrl,
+                };
+            }
+        }
+
+        Ok(KeyStroke::new(modifiers, key))
+    }
+
+    pub fn to_notation(self, notation: Notation) -> String {
+        match notation {
+            Notation::Standard => self.to_standard(),
+            Notation::Emacs => self.to_emacs(),
+            Notation::Vim => self.to_vim(),
+        }
+    }
+
+    fn to_standard(self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if self.modifiers.ctrl {
+            parts.push("Ctrl".into());
+        }
+        if self.modifiers.alt {
+            parts.push("Alt".into());
+        }
+        if self.modifiers.shift {
+            parts.push("Shift".into());
+        }
+        if self.modifiers.super_key {
+            parts.push("Super".into());
+        }
+
+        let key = match self.key {
+            // `Ctrl+S` reads better than `Ctrl+s`, but a bare `g` must not turn into a `G`
+            Key::Char(c) if !self.modifiers.is_empty() => c.to_uppercase().collect(),
+            key => key_name(key, Notation::Standard),
+        };
+        parts.push(key);
+        parts.join("+")
+    }
+
+    fn to_emacs(self) -> String {
+        let mut text = String::new();
+        if self.modifiers.ctrl {
+            text.push_str("C-");
+        }
+        if self.modifiers.alt {
+            text.push_str("M-");
+        }
+        if self.modifiers.shift {
+            text.push_str("S-");
+        }
+        if self.modifiers.super_key {
+            text.push_str("s-");
+        }
+        text.push_str(&key_name(self.key, Notation::Emacs));
+        text
+    }
+
+    fn to_vim(self) -> String {
+        // plain characters and shifted letters are written as-is: `g`, `G`
+        if let Key::Char(c) = self.key {
+            let Modifiers {
+                ctrl,
+                alt,
+                shift,
+                super_key,
+            } = self.modifiers;
+            if !ctrl && !alt && !super_key && c != '<' {
+                if !shift {
+                    return c.to_string();
+                }
+                if c.is_alphabetic() {
+                    return c.to_uppercase().collect();
+                }
+            }
+        }
+
+        let mut text = String::from("<");
+        if self.modifiers.ctrl {
+            text.push_str("C-");
+        }
+        if self.modifiers.alt {
+            text.push_str("M-");
+        }
+        if self.modifiers.shift {
+            text.push_str("S-");
+        }
+        if self.modifiers.super_key {
+            text.push_str("D-");
+        }
+        text.push_str(&key_name(self.key, Notation::Vim));
+        text.push('>');
+        text
+    }
+}
+
+impl fmt::Display for KeyStroke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_standard())
+    }
+}
+
+impl KeyCombo {
+    pub fn new(strokes: Vec<KeyStroke>) -> Self {
+        Self {
+            strokes: strokes.into_iter().map(KeyStroke::normalized).collect(),
+            unparsed: None,
+        }
+    }
+
+    /// The strokes of the combo, none if it [isn't parsed](Self::is_parsed).
+    pub fn strokes(&self) -> &[KeyStroke] {
+        &self.strokes
+    }
+
+    /// Whether the combo was understood. Combos loaded from old files may
+    /// be plain text such as `Ctrl + Shift + P`, which is shown as written
+    /// until the shortcut is edited.
+    pub fn is_parsed(&self) -> bool {
+        self.unparsed.is_none()
+    }
+
+    /// Reads a stored combo leniently: Standard notation, then with spaces
+    /// around `+` dropped, then Vim notation for runs of keys like `gg`.
+    /// Anything else is kept as unparsed text.
+    fn from_stored(text: &str) -> Self {
+        if let Ok(combo) = text.parse() {
+            return combo;
+        }
+
+        let joined = text.split('+').map(str::trim).collect::<Vec<_>>().join("+");
+        if let Ok(combo) = joined.parse() {
+            return combo;
+        }
+
+        // without a `+` a Vim reading can't swallow a modifier name as letters
+        if !text.contains('+') {
+            if let Ok(combo) = KeyCombo::parse_with(text, Notation::Vim) {
+                return combo;
+            }
+        }
+
+        Self {
+            strokes: Vec::new(),
+            unparsed: Some(text.to_owned()),
+        }
+    }
+
+    /// Parses `text` written in the given notation.
+    ///
+    /// [`Notation::Standard`] and [`Notation::Emacs`] separate strokes with
+    /// whitespace and accept each other's modifier spellings, as well as
+    /// bracketed Vim strokes like `<C-w>`. [`Notation::Vim`] additionally
+    /// treats every bare character as its own stroke, so `gg` is two strokes.
+    pub fn parse_with(text: &str, notation: Notation) -> Result<Self, KeyComboParseError> {
+        let mut strokes = Vec::new();
+
+        for token in text.split_whitespace() {
+            if notation == Notation::Vim || is_vim_token(token) {
+                strokes.extend(parse_vim_token(token)?);
+            } else {
+                strokes.push(KeyStroke::parse(token, notation)?);
+            }
+        }
+
+        if strokes.is_empty() {
+            return Err(KeyComboParseError::Empty);
+        }
+
+        Ok(Self {
+            strokes,
+            unparsed: None,
+        })
+    }
+
+    pub fn to_notation(&self, notation: Notation) -> String {
+        if let Some(text) = &self.unparsed {
+            return text.clone();
+        }
+        let strokes = self
+            .strokes
+            .iter()
+            .map(|stroke| stroke.to_notation(notation));
+        match notation {
+            Notation::Vim => strokes.collect::<Vec<_>>().concat(),
+            Notation::Standard | Notation::Emacs => strokes.collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+impl FromStr for KeyCombo {
+    type Err = KeyComboParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyCombo::parse_with(s, Notation::Standard)
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_notation(Notation::Standard))
+    }
+}
+
+impl Serialize for KeyCombo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyCombo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(KeyCombo::from_stored(&text))
+    }
+}
+
+enum Modifier {
+    Ctrl,
+    Alt,
+    Shift,
+    Super,
+}
+
+/// Splits a leading modifier and its `+`/`-` separator off `text`.
+///
+/// Returns `None` when `text` does not start with a modifier or when nothing
+/// would be left for the key, so `Ctrl+-` parses as Ctrl and the `-` key.
+fn split_modifier(text: &str) -> Option<(Modifier, &str)> {
+    let separator = text.find(['+', '-']).filter(|&i| i > 0)?;
+    let (name, rest) = text.split_at(separator);
+    let rest = &rest[1..];
+    if rest.is_empty() {
+        return None;
+    }
+
+    // the single letter emacs forms are case sensitive: `S-` is shift, `s-` is super
+    let modifier = match name {
+        "C" | "c" => Modifier::Ctrl,
+        "M" | "m" | "A" => Modifier::Alt,
+        "S" => Modifier::Shift,
+        "s" | "D" => Modifier::Super,
+        _ => match name.to_lowercase().as_str() {
+            "ctrl" | "control" | "ctl" => Modifier::Ctrl,
+            "alt" | "meta" | "option" | "opt" => Modifier::Alt,
+            "shift" => Modifier::Shift,
+            "super" | "win" | "cmd" | "command" | "logo" | "mod4" => Modifier::Super,
+            _ => return None,
+        },
+    };
+
+    Some((modifier, rest))
+}
+
+fn parse_key(text: &str) -> Option<Key> {
+    // emacs and vim wrap named keys in brackets: `<f5>`, `<delete>`
+    let text = match text.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+        Some(inner) if !inner.is_empty() => inner,
+        _ => text,
+    };
+
+    let mut chars = text.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c));
+    }
+
+    let lower = text.to_lowercase();
+    let key = match lower.as_str() {
+        "enter" | "return" | "ret" | "cr" => Key::Enter,
+        "esc" | "escape" => Key::Esc,
+        "tab" => Key::Tab,
+        "backspace" | "bs" | "bksp" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "insert" | "ins" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" | "prior" => Key::PageUp,
+        "pagedown" | "pgdn" | "pgdown" | "next" => Key::PageDown,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "space" | "spc" => Key::Space,
+        "plus" => Key::Char('+'),
+        "minus" => Key::Char('-'),
+        "lt" => Key::Char('<'),
+        "gt" => Key::Char('>'),
+        "bar" => Key::Char('|'),
+        "bslash" | "backslash" => Key::Char('\\'),
+        _ => {
+            let number = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+            if !(1..=24).contains(&number) {
+                return None;
+            }
+            Key::F(number)
+        }
+    };
+
+    Some(key)
+}
+
+fn key_name(key: Key, notation: Notation) -> String {
+    let name = match (key, notation) {
+        (Key::Char('<'), Notation::Vim) => "lt",
+        (Key::Char(c), _) => return c.to_string(),
+        (Key::F(n), Notation::Standard) => return format!("F{n}"),
+        (Key::F(n), Notation::Emacs) => return format!("<f{n}>"),
+        (Key::F(n), Notation::Vim) => return format!("F{n}"),
+
+        (Key::Enter, Notation::Emacs) => "RET",
+        (Key::Enter, Notation::Vim) => "CR",
+        (Key::Enter, Notation::Standard) => "Enter",
+        (Key::Esc, Notation::Emacs) => "ESC",
+        (Key::Esc, _) => "Esc",
+        (Key::Tab, Notation::Emacs) => "TAB",
+        (Key::Tab, _) => "Tab",
+        (Key::Space, Notation::Emacs) => "SPC",
+        (Key::Space, _) => "Space",
+        (Key::Backspace, Notation::Emacs) => "<backspace>",
+        (Key::Backspace, Notation::Vim) => "BS",
+        (Key::Backspace, Notation::Standard) => "Backspace",
+
+        (Key::Delete, Notation::Emacs) => "<delete>",
+        (Key::Delete, Notation::Vim) => "Del",
+        (Key::Delete, Notation::Standard) => "Delete",
+        (Key::Insert, Notation::Emacs) => "<insert>",
+        (Key::Insert, _) => "Insert",
+        (Key::Home, Notation::Emacs) => "<home>",
+        (Key::Home, _) => "Home",
+        (Key::End, Notation::Emacs) => "<end>",
+        (Key::End, _) => "End",
+        (Key::PageUp, Notation::Emacs) => "<prior>",
+        (Key::PageUp, _) => "PageUp",
+        (Key::PageDown, Notation::Emacs) => "<next>",
+        (Key::PageDown, _) => "PageDown",
+        (Key::Up, Notation::Emacs) => "<up>",
+        (Key::Up, _) => "Up",
+        (Key::Down, Notation::Emacs) => "<down>",
+        (Key::Down, _) => "Down",
+        (Key::Left, Notation::Emacs) => "<left>",
+        (Key::Left, _) => "Left",
+        (Key::Right, Notation::Emacs) => "<right>",
+        (Key::Right, _) => "Right",
+    };
+
+    name.to_owned()
+}
+
+/// Whether a whitespace separated token contains a bracketed Vim stroke such
+/// as `<C-w>` or `<CR>`, as opposed to a lone `<` key.
+fn is_vim_token(token: &str) -> bool {
+    token.len() > 2 && token.starts_with('<') && token.contains('>')
+}
+
+/// Parses a run of Vim keys, e.g. `<C-w>j` or `gg`, into one stroke per key.
+fn parse_vim_token(token: &str) -> Result<Vec<KeyStroke>, KeyComboParseError> {
+    let mut strokes = Vec::new();
+    let mut rest = token;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' && rest.len() > 1 {
+            let end = rest
+                .find('>')
+                .ok_or_else(|| KeyComboParseError::UnclosedBracket(token.to_owned()))?;
+            let inner = &rest[1..end];
+            // `<>` and `<<>` style sequences are literal angle brackets
+            if inner.is_empty() {
+                strokes.push(KeyStroke::new(Modifiers::NONE, Key::Char('<')));
+                rest = &rest[1..];
+                continue;
+            }
+            strokes.push(KeyStroke::parse(inner, Notation::Vim)?);
+            rest = &rest[end + 1..];
+        } else {
+            strokes.push(KeyStroke::new(Modifiers::NONE, Key::Char(c)));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    Ok(strokes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(text: &str) -> KeyCombo {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn standard_notation_round_trips() {
+        for text in [
+            "Ctrl+Shift+P",
+            "Ctrl+K Ctrl+C",
+            "g g",
+            "Alt+F4",
+            "Super+Enter",
+            "Shift+G",
+        ] {
+            assert_eq!(combo(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn modifier_spellings_normalize() {
+        assert_eq!(combo("control+shift+p").to_string(), "Ctrl+Shift+P");
+        assert_eq!(combo("C-x C-s").to_string(), "Ctrl+X Ctrl+S");
+        assert_eq!(combo("Cmd+S"), combo("Super+S"));
+        assert_eq!(combo("Ctrl+P"), combo("Ctrl+p"));
+    }
+
+    #[test]
+    fn plus_and_minus_keys() {
+        let plus = combo("Ctrl++");
+        assert_eq!(plus.strokes()[0].key, Key::Char('+'));
+        assert_eq!(plus.to_string(), "Ctrl++");
+
+        let minus = combo("Ctrl+-");
+        assert_eq!(minus.strokes()[0].key, Key::Char('-'));
+        assert_eq!(minus.to_string(), "Ctrl+-");
+    }
+
+    #[test]
+    fn emacs_notation_round_trips() {
+        for text in ["C-x C-s", "M-f", "C-M-<delete>", "s-RET", "<f5>"] {
+            let parsed = KeyCombo::parse_with(text, Notation::Emacs).unwrap();
+            assert_eq!(parsed.to_notation(Notation::Emacs), text);
+        }
+    }
+
+    #[test]
+    fn emacs_uppercase_letters_are_shifted() {
+        let emacs = |text: &str| KeyCombo::parse_with(text, Notation::Emacs).unwrap();
+
+        assert_eq!(emacs("C-S"), emacs("C-S-s"));
+        assert_eq!(emacs("C-S").to_string(), "Ctrl+Shift+S");
+        assert_ne!(emacs("M-F"), emacs("M-f"));
+        for text in ["C-S-s", "M-S-f", "C-x C-S-s"] {
+            assert_eq!(emacs(text).to_notation(Notation::Emacs), text);
+            let standard = combo(&emacs(text).to_string());
+            assert_eq!(standard.to_notation(Notation::Emacs), text);
+        }
+    }
+
+    #[test]
+    fn vim_notation_round_trips() {
+        for text in ["gg", "<C-w>j", "<lt>", "G", "<C-S-Tab>", "<CR>"] {
+            let parsed = KeyCombo::parse_with(text, Notation::Vim).unwrap();
+            assert_eq!(parsed.to_notation(Notation::Vim), text);
+        }
+        assert_eq!(
+            KeyCombo::parse_with("<C-w>j", Notation::Vim).unwrap(),
+            combo("Ctrl+W j")
+        );
+
+        let vim = |text: &str| KeyCombo::parse_with(text, Notation::Vim).unwrap();
+        assert_eq!(vim("<C-S>"), vim("<C-s>"));
+        assert_eq!(vim("<M-S>"), vim("<M-S-s>"));
+    }
+
+    #[test]
+    fn serializes_as_standard_text() {
+        let json = serde_json::to_string(&combo("C-x C-s")).unwrap();
+        assert_eq!(json, r#""Ctrl+X Ctrl+S""#);
+        assert_eq!(
+            serde_json::from_str::<KeyCombo>(&json).unwrap(),
+            combo("Ctrl+X Ctrl+S")
+        );
+    }
+
+    #[test]
+    fn legacy_strings_load() {
+        let load = |text: &str| serde_json::from_value::<KeyCombo>(text.into()).unwrap();
+
+        assert_eq!(load("Ctrl + Shift + P"), combo("Ctrl+Shift+P"));
+        assert_eq!(load("gg"), combo("g g"));
+        assert!(load("Ctrl+Shift+P").is_parsed());
+
+        // kept as written instead of failing the whole file
+        for text in ["<<", "ctrl+numpad_add", "Hyper+X", ""] {
+            let loaded = load(text);
+            assert!(!loaded.is_parsed(), "{text}");
+            assert!(loaded.strokes().is_empty());
+            assert_eq!(loaded.to_string(), text);
+            assert_eq!(serde_json::to_value(&loaded).unwrap(), text);
+        }
+    }
+
+    fn stroke(code: KeyCode, modifiers: KeyModifiers) -> Option<String> {
+        KeyStroke::from_key_event(&KeyEvent::new(code, modifiers)).map(|stroke| stroke.to_string())
+    }
+
+    #[test]
+    fn key_events_convert_to_strokes() {
+        assert_eq!(
+            stroke(KeyCode::Char('p'), KeyModifiers::CONTROL).as_deref(),
+            Some("Ctrl+P")
+        );
+        assert_eq!(
+            stroke(KeyCode::Char('x'), KeyModifiers::META).as_deref(),
+            Some("Alt+X")
+        );
+        assert_eq!(
+            stroke(KeyCode::F(5), KeyModifiers::SUPER).as_deref(),
+            Some("Super+F5")
+        );
+        assert_eq!(
+            stroke(KeyCode::Char(' '), KeyModifiers::NONE).as_deref(),
+            Some("Space")
+        );
+        assert_eq!(stroke(KeyCode::CapsLock, KeyModifiers::NONE), None);
+        assert_eq!(stroke(KeyCode::Null, KeyModifiers::CONTROL), None);
+    }
+
+    #[test]
+    fn shifted_letters_keep_the_shift() {
+        // terminals report Shift+G as `G`, with or without the shift flag
+        assert_eq!(
+            stroke(KeyCode::Char('G'), KeyModifiers::SHIFT).as_deref(),
+            Some("Shift+G")
+        );
+        assert_eq!(
+            stroke(KeyCode::Char('G'), KeyModifiers::NONE).as_deref(),
+            Some("Shift+G")
+        );
+        assert_eq!(
+            stroke(
+                KeyCode::Char('P'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            stroke(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )
+        );
+        // the shift is part of the character for symbols
+        assert_eq!(
+            stroke(KeyCode::Char('!'), KeyModifiers::SHIFT).as_deref(),
+            Some("!")
+        );
+    }
+
+    #[test]
+    fn back_tab_is_shift_tab() {
+        assert_eq!(
+            stroke(KeyCode::BackTab, KeyModifiers::SHIFT).as_deref(),
+            Some("Shift+Tab")
+        );
+        assert_eq!(
+            stroke(KeyCode::BackTab, KeyModifiers::NONE).as_deref(),
+            Some("Shift+Tab")
+        );
+    }
+
+    #[test]
+    fn ctrl_i_is_only_tab_without_keyboard_enhancement() {
+        // legacy terminals send the same byte for both, which arrives as Tab
+        assert_eq!(
+            stroke(KeyCode::Tab, KeyModifiers::NONE).as_deref(),
+            Some("Tab")
+        );
+        // with the kitty protocol Ctrl+I is reported as itself
+        assert_eq!(
+            stroke(KeyCode::Char('i'), KeyModifiers::CONTROL).as_deref(),
+            Some("Ctrl+I")
+        );
+        assert_eq!(
+            stroke(KeyCode::Tab, KeyModifiers::CONTROL).as_deref(),
+            Some("Ctrl+Tab")
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// SM-2 never lets the ease drop below this, or cards would come back daily forever.
+const MIN_EASE: f64 = 1.3;
+const INITIAL_EASE: f64 = 2.5;
+
+/// How well a card was remembered, graded by the user after seeing the answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    /// Not remembered, the card starts over.
+    Again,
+    /// Remembered with serious effort.
+    Hard,
+    Good,
+    /// Remembered instantly.
+    Easy,
+}
+
+impl Grade {
+    /// The 0–5 response quality SM-2 is defined in terms of.
+    fn quality(self) -> f64 {
+        match self {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        }
+    }
+}
+
+/// Spaced repetition state of one shortcut, scheduled with SM-2.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Review {
+    /// Multiplier for the next interval, lower for cards that were hard.
+    pub ease: f64,
+    /// Days until the card is due again after the last review.
+    pub interval: u32,
+    /// Successful reviews in a row.
+    pub repetitions: u32,
+    /// Seconds since the Unix epoch, `None` for a card never reviewed.
+    pub due: Option<u64>,
+}
+
+impl Default for Review {
+    fn default() -> Self {
+        Self {
+            ease: INITIAL_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: None,
+        }
+    }
+}
+
+impl Review {
+    pub fn is_new(&self) -> bool {
+        self.due.is_none()
+    }
+
+    pub fn is_due(&self, now: u64) -> bool {
+        self.due.is_none_or(|due| due <= now)
+    }
+
+    /// Schedules the next review after answering with `grade` at `now`.
+    pub fn grade(&mut self, grade: Grade, now: u64) {
+        let quality = grade.quality();
+
+        if grade == Grade::Again {
+            self.repetitions = 0;
+            self.interval = 1;
+        } else {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        }
+
+        self.ease =
+            (self.ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(MIN_EASE);
+        self.due = Some(now + u64::from(self.interval) * DAY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000 * DAY;
+
+    fn graded(grades: &[Grade]) -> Review {
+        let mut review = Review::default();
+        for &grade in grades {
+            review.grade(grade, NOW);
+        }
+        review
+    }
+
+    fn assert_ease(review: &Review, ease: f64) {
+        assert!(
+            (review.ease - ease).abs() < 1e-9,
+            "{} != {ease}",
+            review.ease
+        );
+    }
+
+    #[test]
+    fn new_cards_are_due_until_reviewed() {
+        let review = Review::default();
+        assert!(review.is_new());
+        assert!(review.is_due(0));
+
+        let review = graded(&[Grade::Good]);
+        assert!(!review.is_new());
+        assert!(!review.is_due(NOW));
+        assert!(review.is_due(NOW + DAY));
+    }
+
+    #[test]
+    fn intervals_grow_by_the_ease() {
+        let mut review = Review::default();
+        let mut intervals = Vec::new();
+        for _ in 0..4 {
+            review.grade(Grade::Good, NOW);
+            intervals.push(review.interval);
+        }
+        // Good leaves the ease at 2.5
+        assert_eq!(intervals, [1, 6, 15, 38]);
+        assert_eq!(review.repetitions, 4);
+        assert_eq!(review.due, Some(NOW + 38 * DAY));
+        assert_ease(&review, INITIAL_EASE);
+    }
+
+    #[test]
+    fn grades_change_the_ease() {
+        assert_ease(&graded(&[Grade::Easy]), 2.6);
+        assert_ease(&graded(&[Grade::Hard]), 2.36);
+        assert_ease(&graded(&[Grade::Again]), 1.96);
+    }
+
+    #[test]
+    fn again_starts_the_card_over() {
+        let review = graded(&[Grade::Good, Grade::Good, Grade::Good, Grade::Again]);
+        assert_eq!(review.repetitions, 0);
+        assert_eq!(review.interval, 1);
+        assert_eq!(review.due, Some(NOW + DAY));
+
+        let review = graded(&[
+            Grade::Good,
+            Grade::Good,
+            Grade::Again,
+            Grade::Good,
+            Grade::Good,
+        ]);
+        assert_eq!(review.interval, 6);
+    }
+
+    #[test]
+    fn ease_has_a_floor() {
+        assert_ease(&graded(&[Grade::Again; 10]), MIN_EASE);
+        assert_ease(&graded(&[Grade::Hard; 10]), MIN_EASE);
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::undo::UndoLog;
+
+/// Backups go into this directory next to the shortcuts file.
+const BACKUP_DIR: &str = "backups";
+
+/// Saving happens after every lookup, so a backup is only taken when the
+/// newest one is at least this old. Otherwise a burst of lookups would rotate
+/// out every backup worth restoring.
+const BACKUP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Length of the `YYYYMMDD-HHMMSS-mmm` timestamp in backup file names.
+const TIMESTAMP_LEN: usize = 19;
+
+#[derive(Debug)]
+pub enum SaveError {
+    /// The shortcuts were never loaded from or assigned a file.
+    NoPath,
+    Io(io::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::NoPath => write!(f, "no file to save shortcuts to"),
+            SaveError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Io(err.into())
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// The file isn't JSON or doesn't have the expected fields.
+    Invalid(serde_json::Error),
+    /// The `version` field isn't a positive number.
+    BadVersion,
+    /// The file was written by a newer shotty in a format this one doesn't know.
+    TooNew {
+        version: u32,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "{err}"),
+            LoadError::Invalid(err) => write!(f, "not a valid shortcuts file: {err}"),
+            LoadError::BadVersion => write!(f, "not a valid shortcuts file: bad `version`"),
+            LoadError::TooNew { version } => write!(
+                f,
+                "the file was written by a newer version of shotty (format {version}, \
+                 this version reads up to {}), please update shotty",
+                super::FILE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+
+/// A copy of the shortcuts file taken before it was overwritten.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When the backup was taken, `YYYY-MM-DD HH:MM:SS` in UTC.
+    pub taken_at: String,
+}
+
+/// Replaces the file at `path` with `contents` so that it holds either the
+/// old or the new contents, never a mix, even if shotty or the machine dies
+/// halfway.
+///
+/// The contents are written to a temporary file in the same directory, synced
+/// to disk and then renamed over the original. A symlink is followed, so the
+/// file it points to is replaced rather than the link, and the original
+/// permissions are kept.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    // the file may not exist yet
+    let path = &fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let permissions = fs::metadata(path)
+        .ok()
+        .map(|metadata| metadata.permissions());
+
+    let dir = parent_dir(path);
+    fs::create_dir_all(dir)?;
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = dir.join(format!(".{file_name}.{}.tmp", std::process::id()));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    // make the rename itself durable; not every platform can open directories
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Copies the file at `path` into the backup directory and deletes the oldest
+/// backups beyond `keep`.
+///
+/// Unless `force` is set, nothing happens when the newest backup is recent.
+pub fn back_up(path: &Path, keep: usize, force: bool) -> io::Result<()> {
+    if keep == 0 || !path.is_file() {
+        return Ok(());
+    }
+
+    let mut backups = list_backups(path)?;
+    let recent = backups.first().is_some_and(|newest| {
+        fs::metadata(&newest.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < BACKUP_INTERVAL)
+    });
+    if recent && !force {
+        return Ok(());
+    }
+
+    let backup_dir = parent_dir(path).join(BACKUP_DIR);
+    fs::create_dir_all(&backup_dir)?;
+
+    let stamp = file_stamp(SystemTime::now());
+    let backup_path = backup_dir.join(format!("{}.{stamp}.json", file_stem(path)));
+    // a forced backup within the same millisecond replaces the earlier one
+    backups.retain(|backup| backup.path != backup_path);
+
+    let contents = fs::read(path)?;
+    write_atomically(&backup_path, &contents)?;
+
+    for old in backups.iter().skip(keep - 1) {
+        fs::remove_file(&old.path)?;
+    }
+    Ok(())
+}
+
+/// The undo log as kept on disk between runs.
+#[derive(Serialize, Deserialize)]
+pub struct Journal {
+    /// [`fingerprint`] of the shortcuts the log applies to.
+    pub fingerprint: u64,
+    pub log: UndoLog,
+}
+
+/// A 64-bit FNV-1a hash of `bytes`. Unlike the standard library's hashers it
+/// is the same with every Rust release, so it can be kept on disk.
+pub fn fingerprint(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Where the journal of the shortcuts file at `path` is kept: `NAME.undo.json`
+/// next to it.
+pub fn journal_path(path: &Path) -> PathBuf {
+    parent_dir(path).join(format!("{}.undo.json", file_stem(path)))
+}
+
+/// Copies the file at `path`, in format `version`, into the backup directory
+/// as `NAME.vVERSION.json` before a migration rewrites it.
+///
+/// These copies aren't rotated, so the original survives any number of saves.
+/// An existing copy of the same version is left alone.
+pub fn keep_original(path: &Path, version: u32) -> io::Result<PathBuf> {
+    let backup_dir = parent_dir(path).join(BACKUP_DIR);
+    let original = backup_dir.join(format!("{}.v{version}.json", file_stem(path)));
+    if !original.exists() {
+        fs::create_dir_all(&backup_dir)?;
+        write_atomically(&original, &fs::read(path)?)?;
+    }
+    Ok(original)
+}
+
+/// Backups of the file at `path`, newest first.
+pub fn list_backups(path: &Path) -> io::Result<Vec<Backup>> {
+    let backup_dir = parent_dir(path).join(BACKUP_DIR);
+    let entries = match fs::read_dir(&backup_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let prefix = format!("{}.", file_stem(path));
+    let mut backups = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(stamp) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|rest| rest.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        // `work.json` and `work.old.json` share a prefix, the timestamp tells them apart
+        if let Some(taken_at) = parse_stamp(stamp) {
+            backups.push(Backup {
+                path: entry.path(),
+                taken_at,
+            });
+        }
+    }
+
+    backups.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(backups)
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// `YYYYMMDD-HHMMSS-mmm` back to `YYYY-MM-DD HH:MM:SS`, `None` if it isn't one.
+fn parse_stamp(stamp: &str) -> Option<String> {
+    let bytes = stamp.as_bytes();
+    let well_formed = bytes.len() == TIMESTAMP_LEN
+        && bytes.iter().enumerate().all(|(i, b)| {
+            if i == 8 || i == 15 {
+                *b == b'-'
+            } else {
+                b.is_ascii_digit()
+            }
+        });
+    if !well_formed {
+        return None;
+    }
+
+    Some(format!(
+        "{}-{}-{} {}:{}:{}",
+        &stamp[0..4],
+        &stamp[4..6],
+        &stamp[6..8],
+        &stamp[9..11],
+        &stamp[11..13],
+        &stamp[13..15]
+    ))
+}
+
+/// `time` as `YYYYMMDD-HHMMSS-mmm` in UTC, which sorts chronologically.
+fn file_stamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    let (hour, minute, second) = (
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+    );
+
+    format!(
+        "{year:04}{month:02}{day:02}-{hour:02}{minute:02}{second:02}-{:03}",
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date, using Howard
+/// Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::{shortcuts::Shortcuts, utils::TempDir};
+
+    /// File names in `dir`, sorted.
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Forced backups a few milliseconds apart, so each gets its own name.
+    fn force_backups(path: &Path, keep: usize, count: usize) {
+        for i in 0..count {
+            fs::write(path, format!("version {i}")).unwrap();
+            back_up(path, keep, true).unwrap();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn contents(backup: &Backup) -> String {
+        fs::read_to_string(&backup.path).unwrap()
+    }
+
+    #[test]
+    fn writes_new_and_existing_files() {
+        let dir = TempDir::new("writes-new-and-existing-files");
+        let path = dir.path().join("nested").join("shortcuts.json");
+
+        write_atomically(&path, b"first").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+        write_atomically(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        // no temporary file left behind
+        assert_eq!(names(&dir.path().join("nested")), ["shortcuts.json"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_permissions_and_follows_symlinks() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = TempDir::new("keeps-permissions-and-follows-symlinks");
+        let target = dir.path().join("shortcuts.json");
+        fs::write(&target, "old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o600)).unwrap();
+        let link = dir.path().join("link.json");
+        symlink(&target, &link).unwrap();
+
+        write_atomically(&link, b"new").unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn backups_rotate() {
+        let dir = TempDir::new("backups-rotate");
+        let path = dir.path().join("work.json");
+        force_backups(&path, 3, 5);
+
+        let backups = list_backups(&path).unwrap();
+        let kept: Vec<String> = backups.iter().map(contents).collect();
+        assert_eq!(kept, ["version 4", "version 3", "version 2"]);
+        assert_eq!(backups[0].taken_at.len(), "2024-01-31 12:00:00".len());
+    }
+
+    #[test]
+    fn recent_backups_throttle_unforced_ones() {
+        let dir = TempDir::new("recent-backups-throttle-unforced-ones");
+        let path = dir.path().join("shortcuts.json");
+        fs::write(&path, "first").unwrap();
+        back_up(&path, 5, false).unwrap();
+
+        fs::write(&path, "second").unwrap();
+        back_up(&path, 5, false).unwrap();
+        let backups = list_backups(&path).unwrap();
+        assert_eq!(backups.iter().map(contents).collect::<Vec<_>>(), ["first"]);
+
+        // once the newest backup is old enough, the next save takes one
+        let file = fs::File::options()
+            .write(true)
+            .open(&backups[0].path)
+            .unwrap();
+        file.set_modified(SystemTime::now() - BACKUP_INTERVAL)
+            .unwrap();
+        thread::sleep(Duration::from_millis(5));
+        back_up(&path, 5, false).unwrap();
+        let backups = list_backups(&path).unwrap();
+        assert_eq!(
+            backups.iter().map(contents).collect::<Vec<_>>(),
+            ["second", "first"]
+        );
+    }
+
+    #[test]
+    fn nothing_to_back_up() {
+        let dir = TempDir::new("nothing-to-back-up");
+        let path = dir.path().join("shortcuts.json");
+        back_up(&path, 5, true).unwrap();
+        fs::write(&path, "contents").unwrap();
+        back_up(&path, 0, true).unwrap();
+        assert_eq!(names(dir.path()), ["shortcuts.json"]);
+    }
+
+    #[test]
+    fn backups_of_other_files_are_not_listed() {
+        let dir = TempDir::new("backups-of-other-files-are-not-listed");
+        let path = dir.path().join("work.json");
+        force_backups(&path, 5, 1);
+        force_backups(&dir.path().join("work.old.json"), 5, 1);
+        fs::write(dir.path().join(BACKUP_DIR).join("work.notes.json"), "").unwrap();
+        assert_eq!(list_backups(&path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn stamps_sort_and_read_back() {
+        // 2000-02-29 00:00:00.005 UTC
+        let time = UNIX_EPOCH + Duration::from_millis(951_782_400_005);
+        assert_eq!(file_stamp(time), "20000229-000000-005");
+        assert_eq!(
+            parse_stamp("20000229-000000-005").as_deref(),
+            Some("2000-02-29 00:00:00")
+        );
+        assert_eq!(parse_stamp("v3"), None);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn saving_needs_a_path() {
+        assert!(matches!(Shortcuts::new().save(), Err(SaveError::NoPath)));
+    }
+
+    #[test]
+    fn fingerprint_is_fnv_1a() {
+        // test vectors from the FNV reference implementation
+        assert_eq!(fingerprint(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fingerprint(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+
+use super::Shortcut;
+
+/// How many operations can be undone unless configured otherwise.
+pub const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// One change to the list of shortcuts, with what is needed to reverse it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Change {
+    Insert {
+        index: usize,
+        shortcut: Shortcut,
+    },
+    Remove {
+        index: usize,
+        shortcut: Shortcut,
+    },
+    Replace {
+        index: usize,
+        before: Box<Shortcut>,
+        after: Box<Shortcut>,
+    },
+    /// Everything was swapped out, e.g. by restoring a backup.
+    ReplaceAll {
+        before: Vec<Shortcut>,
+        after: Vec<Shortcut>,
+    },
+    /// `amount` lookups at `at`, kept this small as there's one for every lookup.
+    Lookup {
+        index: usize,
+        amount: u32,
+        at: u64,
+        /// When the shortcut was looked up before.
+        previous: Option<u64>,
+    },
+}
+
+/// Something the user did, undone and redone as a whole.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    /// What was done, e.g. "delete Ctrl+C", for status messages.
+    pub label: String,
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+    /// In the order they were applied.
+    pub changes: Vec<Change>,
+}
+
+impl Operation {
+    /// The shortcut the first change was about, `None` if it touched all of them.
+    pub fn index(&self) -> Option<usize> {
+        match self.changes.first()? {
+            Change::Insert { index, .. }
+            | Change::Remove { index, .. }
+            | Change::Replace { index, .. }
+            | Change::Lookup { index, .. } => Some(*index),
+            Change::ReplaceAll { .. } => None,
+        }
+    }
+}
+
+/// Undo and redo stacks of [`Operation`]s, newest last.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoLog {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+
+    /// Changes are collected here instead of becoming operations of their
+    /// own while a batch is open.
+    #[serde(skip)]
+    batch: Option<Operation>,
+
+    #[serde(skip, default = "default_depth")]
+    depth: usize,
+}
+
+fn default_depth() -> usize {
+    DEFAULT_UNDO_DEPTH
+}
+
+impl Default for UndoLog {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            batch: None,
+            depth: DEFAULT_UNDO_DEPTH,
+        }
+    }
+}
+
+impl UndoLog {
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.trim();
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Records a change done at `at`, as its own operation unless a batch is open.
+    pub fn record(&mut self, label: String, at: u64, change: Change) {
+        if let Some(batch) = &mut self.batch {
+            batch.changes.push(change);
+            return;
+        }
+
+        self.push(Operation {
+            label,
+            at,
+            changes: vec![change],
+        });
+    }
+
+    /// Starts collecting changes into one operation. Batches don't nest, a
+    /// second call keeps the first label.
+    pub fn begin_batch(&mut self, label: String, at: u64) {
+        self.batch.get_or_insert(Operation {
+            label,
+            at,
+            changes: Vec::new(),
+        });
+    }
+
+    /// Finishes the batch, an empty one is dropped.
+    pub fn end_batch(&mut self) {
+        if let Some(batch) = self.batch.take() {
+            if !batch.changes.is_empty() {
+                self.push(batch);
+            }
+        }
+    }
+
+    fn push(&mut self, operation: Operation) {
+        self.undo.push(operation);
+        self.redo.clear();
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let excess = self.undo.len().saturating_sub(self.depth);
+        self.undo.drain(..excess);
+    }
+
+    /// Takes the newest operation to undo.
+    pub fn pop_undo(&mut self) -> Option<Operation> {
+        self.undo.pop()
+    }
+
+    /// Takes the newest undone operation to redo.
+    pub fn pop_redo(&mut self) -> Option<Operation> {
+        self.redo.pop()
+    }
+
+    /// Puts an operation that was just undone on the redo stack.
+    pub fn push_redo(&mut self, operation: Operation) {
+        self.redo.push(operation);
+    }
+
+    /// Puts an operation that was just redone back on the undo stack, keeping
+    /// what else can be redone.
+    pub fn push_undo(&mut self, operation: Operation) {
+        self.undo.push(operation);
+        self.trim();
+    }
+}
@@ -1,17 +1,23 @@
+use std::io::{self, Write};
+
 use ratatui::{layout::{
     Constraint,
     Direction,
     Layout,
     Rect,
 }};
+use unicode_width::UnicodeWidthStr;
 
-pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+/// Returns a rect centered in `r`, `percent_x` percent of its width wide and
+/// `height` rows high, clamped to `r`.
+pub fn centered_rect_with_height(percent_x: u16, height: u16, r: Rect) -> Rect {
+    let height = height.min(r.height);
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Length((r.height - height) / 2),
+            Constraint::Length(height),
+            Constraint::Min(0),
         ])
         .split(r);
 
@@ -24,3 +30,56 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+/// Prints rows as a plain text table with aligned columns.
+pub fn print_table(headers: Vec<String>, rows: Vec<Vec<String>>) -> io::Result<()> {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.width()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let mut out = io::stdout().lock();
+    let mut print_row = |row: &[String]| {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell}{}", " ".repeat(width - cell.width())))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())
+    };
+
+    print_row(&headers)?;
+    for row in &rows {
+        print_row(row)?;
+    }
+    Ok(())
+}
+
+/// An empty directory for a test, removed with everything in it when dropped.
+#[cfg(test)]
+pub struct TempDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    /// `name` has to be unique among the tests, which run in parallel.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("shotty-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
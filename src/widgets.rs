@@ -1,5 +1,6 @@
 use ratatui::{crossterm::event::KeyEvent, layout::Rect, Frame};
 
+pub mod button;
 pub mod input;
 
 pub trait Widget {
@@ -0,0 +1,67 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::{palette::tailwind, Color, Modifier, Style},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    style::{compose_style, PatchStyle},
+    widgets::Widget,
+};
+
+pub struct Button {
+    label: String,
+    color: Color,
+
+    focused: bool,
+}
+
+impl Button {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_owned(),
+            color: tailwind::SLATE.c400,
+            focused: false,
+        }
+    }
+
+    /// Sets the accent color used for the border and, when focused, the background.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused
+    }
+}
+
+impl Widget for Button {
+    fn render(&self, area: Rect, frame: &mut ratatui::Frame) {
+        let style = Style::default().fg(self.color);
+
+        let style = compose_style(
+            style,
+            vec![PatchStyle::new(
+                self.focused,
+                Style::default()
+                    .fg(tailwind::SLATE.c950)
+                    .bg(self.color)
+                    .add_modifier(Modifier::BOLD),
+            )],
+        );
+
+        let paragraph = Paragraph::new(self.label.as_str())
+            .centered()
+            .style(style)
+            .block(Block::bordered().border_style(Style::default().fg(self.color)));
+
+        frame.render_widget(paragraph, area);
+    }
+
+    /// Returns `true` if the key pressed the button.
+    fn handle_event(&mut self, key_event: KeyEvent) -> bool {
+        matches!(key_event.code, KeyCode::Enter | KeyCode::Char(' '))
+    }
+}
@@ -1,27 +1,46 @@
+use std::cell::Cell;
+
 use ratatui::{
-    layout::Rect,
-    style::{palette::tailwind, Style},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Position, Rect},
+    style::{palette::tailwind, Style, Stylize},
+    text::Span,
     widgets::{Block, Paragraph},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::{
     style::{compose_style, PatchStyle},
     widgets::Widget,
 };
 
+/// A single line text input with a cursor.
+///
+/// The cursor moves by grapheme cluster, so combining characters and emoji
+/// are edited as one unit, and the text scrolls horizontally when it is wider
+/// than the box. Wide (e.g. CJK) characters take up two columns.
 #[derive(Default)]
 pub struct Input {
     label: String,
-    text: String,
+    placeholder: String,
+    value: String,
+
+    /// Byte offset into `value`, always on a grapheme boundary.
+    cursor: usize,
+
+    /// Display column of `value` shown at the left edge of the box. Kept
+    /// between renders so the text only scrolls when the cursor leaves the box.
+    scroll: Cell<usize>,
 
     focused: bool,
 }
 
 impl Input {
-    pub fn new(label: &str, text: &str) -> Self {
+    pub fn new(label: &str, placeholder: &str) -> Self {
         Self {
             label: label.to_owned(),
-            text: text.to_owned(),
+            placeholder: placeholder.to_owned(),
             ..Default::default()
         }
     }
@@ -31,8 +50,149 @@ impl Input {
     }
 
     pub fn get_value(&self) -> &str {
-        &self.label
+        &self.value
+    }
+
+    /// Replaces the value and puts the cursor at its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.to_owned();
+        self.cursor = self.value.len();
+        self.scroll.set(0);
+    }
+
+    fn insert(&mut self, c: char) {
+        self.value.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        // a combining character may have merged with the grapheme before it
+        self.cursor = self.snap_to_grapheme(self.cursor);
+    }
+
+    /// Moves `offset` forward to the end of the grapheme it falls into.
+    fn snap_to_grapheme(&self, offset: usize) -> usize {
+        self.value
+            .grapheme_indices(true)
+            .map(|(i, g)| i + g.len())
+            .find(|&end| end >= offset)
+            .unwrap_or(0)
+    }
+
+    fn previous_grapheme(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_grapheme(&self) -> usize {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    /// Start of the word before the cursor, skipping any separators first.
+    fn previous_word(&self) -> usize {
+        let mut graphemes = self.value[..self.cursor]
+            .grapheme_indices(true)
+            .rev()
+            .peekable();
+        while graphemes.next_if(|(_, g)| !is_word(g)).is_some() {}
+
+        let mut start = graphemes.peek().map_or(0, |(i, _)| *i);
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| is_word(g)) {
+            start = i;
+        }
+        start
+    }
+
+    /// End of the word after the cursor, skipping any separators first.
+    fn next_word(&self) -> usize {
+        let mut graphemes = self.value[self.cursor..].grapheme_indices(true).peekable();
+        while graphemes.next_if(|(_, g)| !is_word(g)).is_some() {}
+        while graphemes.next_if(|(_, g)| is_word(g)).is_some() {}
+
+        graphemes
+            .peek()
+            .map_or(self.value.len(), |(i, _)| self.cursor + i)
+    }
+
+    /// Removes the text between the cursor and `to` and puts the cursor at the start.
+    fn delete_to(&mut self, to: usize) -> bool {
+        let (start, end) = (self.cursor.min(to), self.cursor.max(to));
+        if start == end {
+            return false;
+        }
+
+        self.value.replace_range(start..end, "");
+        self.cursor = start;
+        true
+    }
+
+    /// Updates the scroll position so the grapheme under the cursor stays
+    /// inside `width` columns, and returns it. The scroll position is always
+    /// the start of a grapheme, so wide characters are never cut in half at
+    /// the left edge.
+    fn scroll_for_width(&self, width: usize) -> usize {
+        let cursor_column = self.value[..self.cursor].width();
+        // the cursor takes one column when it sits after the last character
+        let cursor_end = cursor_column
+            + self.value[self.cursor..]
+                .graphemes(true)
+                .next()
+                .map_or(1, |g| g.width().max(1));
+        let total_width = self.value.width() + 1;
+
+        let mut scroll = self.scroll.get();
+        if cursor_column < scroll {
+            scroll = cursor_column;
+        } else if cursor_end > scroll + width {
+            scroll = cursor_end - width;
+        }
+        // don't leave empty space on the right after deleting
+        scroll = scroll.min(total_width.saturating_sub(width));
+        // a box narrower than the grapheme shows at least its start
+        scroll = self.grapheme_start_from(scroll).min(cursor_column);
+
+        self.scroll.set(scroll);
+        scroll
+    }
+
+    /// The first column at or after `column` where a grapheme starts.
+    fn grapheme_start_from(&self, column: usize) -> usize {
+        let mut start = 0;
+        for grapheme in self.value.graphemes(true) {
+            if start >= column {
+                break;
+            }
+            start += grapheme.width();
+        }
+        start
     }
+
+    /// The part of the value visible from column `scroll` on, dropping a
+    /// wide character at the right edge that would only partially fit.
+    fn visible_text(&self, scroll: usize, width: usize) -> String {
+        let mut column = 0;
+        let mut text = String::new();
+        for grapheme in self.value.graphemes(true) {
+            let grapheme_width = grapheme.width();
+            if column >= scroll && column + grapheme_width <= scroll + width {
+                text.push_str(grapheme);
+            }
+            column += grapheme_width;
+            if column >= scroll + width {
+                break;
+            }
+        }
+        text
+    }
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
 }
 
 impl Widget for Input {
@@ -47,16 +207,221 @@ impl Widget for Input {
             )],
         );
 
-        let paragraph = Paragraph::new(self.text.as_str()).block(
-            Block::bordered()
-                .border_style(style)
-                .title(self.label.as_str()),
-        );
+        let block = Block::bordered()
+            .border_style(style)
+            .title(self.label.as_str());
+        let inner = block.inner(area);
+        let width = inner.width as usize;
+
+        let scroll = self.scroll_for_width(width.max(1));
+        let text = if self.value.is_empty() {
+            Span::raw(self.placeholder.as_str())
+                .fg(tailwind::GRAY.c400)
+                .italic()
+        } else {
+            Span::raw(self.visible_text(scroll, width))
+        };
+
+        frame.render_widget(Paragraph::new(text).block(block), area);
+
+        if self.focused && width > 0 {
+            let cursor_column = self.value[..self.cursor].width() - scroll;
+            frame.set_cursor_position(Position::new(
+                inner.x + cursor_column.min(width - 1) as u16,
+                inner.y,
+            ));
+        }
+    }
+
+    /// Returns `true` if the key was used by the input.
+    fn handle_event(&mut self, key_event: KeyEvent) -> bool {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+
+        match key_event.code {
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.value.len(),
+            KeyCode::Char('b') if alt => self.cursor = self.previous_word(),
+            KeyCode::Char('f') if alt => self.cursor = self.next_word(),
+            KeyCode::Char('w') if ctrl => {
+                self.delete_to(self.previous_word());
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.delete_to(0);
+            }
+            KeyCode::Char('k') if ctrl => {
+                self.delete_to(self.value.len());
+            }
+            KeyCode::Char('d') if alt => {
+                self.delete_to(self.next_word());
+            }
+            KeyCode::Char(c) if !ctrl && !alt => self.insert(c),
+            KeyCode::Backspace if ctrl || alt => {
+                self.delete_to(self.previous_word());
+            }
+            KeyCode::Backspace => {
+                self.delete_to(self.previous_grapheme());
+            }
+            KeyCode::Delete if ctrl || alt => {
+                self.delete_to(self.next_word());
+            }
+            KeyCode::Delete => {
+                self.delete_to(self.next_grapheme());
+            }
+            KeyCode::Left if ctrl || alt => self.cursor = self.previous_word(),
+            KeyCode::Right if ctrl || alt => self.cursor = self.next_word(),
+            KeyCode::Left => self.cursor = self.previous_grapheme(),
+            KeyCode::Right => self.cursor = self.next_grapheme(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+
+    fn input_with(value: &str) -> Input {
+        let mut input = Input::new("Label", "");
+        input.set_value(value);
+        input.set_focused(true);
+        input
+    }
+
+    fn press(input: &mut Input, code: KeyCode, modifiers: KeyModifiers) {
+        input.handle_event(KeyEvent::new(code, modifiers));
+    }
+
+    /// The value with `|` at the cursor.
+    fn shown(input: &Input) -> String {
+        format!(
+            "{}|{}",
+            &input.value[..input.cursor],
+            &input.value[input.cursor..]
+        )
+    }
+
+    /// Draws the input with `width` columns inside its borders and returns
+    /// the text in the box and the cursor's column in it.
+    fn draw(input: &Input, width: u16) -> (String, u16) {
+        let mut terminal = Terminal::new(TestBackend::new(width + 2, 3)).unwrap();
+        terminal
+            .draw(|frame| input.render(frame.area(), frame))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        let mut x = 1;
+        // skip the cells a wide character covers
+        while x <= width {
+            let symbol = buffer[(x, 1)].symbol();
+            text.push_str(symbol);
+            x += symbol.width().max(1) as u16;
+        }
+        (text, terminal.get_cursor_position().unwrap().x - 1)
+    }
+
+    #[test]
+    fn moves_by_word() {
+        let mut input = input_with("foo bar_baz, qux");
+        press(&mut input, KeyCode::Char('b'), KeyModifiers::ALT);
+        assert_eq!(shown(&input), "foo bar_baz, |qux");
+        press(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "foo |bar_baz, qux");
+        press(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "|foo bar_baz, qux");
+        press(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "|foo bar_baz, qux");
+
+        press(&mut input, KeyCode::Char('f'), KeyModifiers::ALT);
+        assert_eq!(shown(&input), "foo| bar_baz, qux");
+        press(&mut input, KeyCode::Right, KeyModifiers::ALT);
+        assert_eq!(shown(&input), "foo bar_baz|, qux");
+        press(&mut input, KeyCode::Right, KeyModifiers::CONTROL);
+        press(&mut input, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "foo bar_baz, qux|");
+    }
+
+    #[test]
+    fn deletes_words_and_lines() {
+        let mut input = input_with("git commit --amend");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "git commit --|");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "git |");
+
+        press(&mut input, KeyCode::Char('x'), KeyModifiers::NONE);
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "|x");
+
+        let mut input = input_with("one two");
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Char('d'), KeyModifiers::ALT);
+        assert_eq!(shown(&input), "| two");
+        press(&mut input, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(shown(&input), "|");
+    }
+
+    #[test]
+    fn edits_whole_graphemes() {
+        let mut input = input_with("cafe\u{301}!");
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(shown(&input), "caf|!");
+
+        let mut input = input_with("a\u{1f469}\u{200d}\u{1f4bb}b");
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Right, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(shown(&input), "a|b");
+
+        // a combining mark joins the grapheme before the cursor
+        let mut input = input_with("e");
+        press(&mut input, KeyCode::Char('\u{301}'), KeyModifiers::NONE);
+        assert_eq!(shown(&input), "e\u{301}|");
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(shown(&input), "|e\u{301}");
+    }
+
+    #[test]
+    fn scrolls_wide_characters_by_whole_graphemes() {
+        let mut input = input_with("中中中中");
+        // the last one and the cursor after it, not half of the one before
+        assert_eq!(draw(&input, 4), ("中  ".into(), 2));
+
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(draw(&input, 4), ("中中".into(), 0));
+        press(&mut input, KeyCode::Right, KeyModifiers::NONE);
+        // the second character just fits, so nothing scrolls yet
+        assert_eq!(draw(&input, 4), ("中中".into(), 2));
+        press(&mut input, KeyCode::Right, KeyModifiers::NONE);
+        // the cursor is on the third character, which must be shown whole
+        assert_eq!(draw(&input, 4), ("中中".into(), 2));
+        assert_eq!(input.scroll.get(), 2);
 
-        frame.render_widget(paragraph, area);
+        // odd widths can't split a character either
+        let mut input = input_with("a中中");
+        assert_eq!(draw(&input, 4), ("中  ".into(), 2));
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(draw(&input, 4), ("a中 ".into(), 0));
     }
 
-    fn handle_event(&mut self, _key_event: ratatui::crossterm::event::KeyEvent) -> bool {
-        todo!()
+    #[test]
+    fn scroll_stays_while_the_cursor_is_visible() {
+        let mut input = input_with("abcdefgh");
+        assert_eq!(draw(&input, 4), ("fgh ".into(), 3));
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(draw(&input, 4), ("fgh ".into(), 1));
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(draw(&input, 4), ("efgh".into(), 0));
+        press(&mut input, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(draw(&input, 4), ("bcd ".into(), 3));
     }
 }